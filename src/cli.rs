@@ -0,0 +1,93 @@
+use {
+    crate::{
+        commands::{
+            account::AccountArgs, cluster::ClusterArgs, config::ConfigArgs, stake::StakeArgs,
+            token::TokenArgs, transaction::TransactionArgs, vote::VoteArgs,
+        },
+        config::OutputFormat,
+        context::ScillaContext,
+    },
+    clap::{Parser, Subcommand},
+};
+
+/// Scilla runs interactively without a subcommand; with one it runs that
+/// command once and exits, for scripts, CI and cron jobs. Subcommands only
+/// read: anything that signs stays in the interactive menus.
+#[derive(Debug, Parser)]
+#[command(name = "scilla", version, about)]
+pub struct Cli {
+    /// Answer yes/no confirmations with yes (see `assume-yes` in the config)
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+    /// Print lookups and list views as a table, CSV or JSON, overriding
+    /// `output-format` in the config
+    #[arg(short, long, global = true, value_enum)]
+    pub output: Option<OutputFormat>,
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliCommand {
+    #[command(subcommand)]
+    Account(AccountArgs),
+    #[command(subcommand)]
+    Cluster(ClusterArgs),
+    #[command(subcommand)]
+    Config(ConfigArgs),
+    #[command(subcommand)]
+    Stake(StakeArgs),
+    #[command(subcommand)]
+    Vote(VoteArgs),
+    #[command(subcommand)]
+    Token(TokenArgs),
+    #[command(subcommand)]
+    Transaction(TransactionArgs),
+}
+
+impl CliCommand {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            CliCommand::Account(args) => args.run(ctx).await,
+            CliCommand::Cluster(args) => args.run(ctx).await,
+            CliCommand::Config(args) => args.run(ctx),
+            CliCommand::Stake(args) => args.run(ctx).await,
+            CliCommand::Vote(args) => args.run(ctx).await,
+            CliCommand::Token(args) => args.run(ctx).await,
+            CliCommand::Transaction(args) => args.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, clap::CommandFactory, solana_pubkey::Pubkey};
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_subcommand() {
+        let pubkey = Pubkey::new_unique();
+        let cli = Cli::parse_from(["scilla", "-y", "stake", "show", &pubkey.to_string()]);
+
+        assert!(cli.yes);
+        assert_eq!(cli.output, None);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Stake(StakeArgs::Show { pubkey: parsed })) if parsed == pubkey
+        ));
+        assert!(Cli::parse_from(["scilla"]).command.is_none());
+
+        let cli = Cli::parse_from(["scilla", "cluster", "slot", "--output", "json"]);
+        assert_eq!(cli.output, Some(OutputFormat::Json));
+
+        let cli = Cli::parse_from(["scilla", "config", "show"]);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Config(ConfigArgs::Show))
+        ));
+    }
+}
@@ -1,19 +1,61 @@
 use {
     crate::{
         commands::CommandFlow,
+        config::{Cluster, OutputFormat},
+        constants::{
+            ACCOUNT_HISTORY_DEFAULT_LIMIT, AIRDROP_MAX_LAMPORTS_PER_REQUEST,
+            AIRDROP_RETRY_BASE_DELAY_SECS, DEFAULT_AIRDROP_SOL, DEPOSIT_POLL_INTERVAL_SECS,
+            RECIPIENT_CHECK_SIGNATURES, SIGNATURES_PAGE_LIMIT, TRANSFER_FEE_ESTIMATE_LAMPORTS,
+        },
         context::ScillaContext,
-        misc::helpers::{bincode_deserialize, lamports_to_sol, sol_to_lamports},
-        prompt::prompt_input_data,
-        ui::{print_error, show_spinner},
+        misc::{
+            authority::{
+                fetch_nonce_accounts_by_authority, fetch_wallet_authorities, rotate_authorities,
+            },
+            helpers::{
+                DurableNonce, SolAmount, bincode_deserialize, build_and_send_tx,
+                build_and_send_tx_with_nonce, decode_base58, decode_base64, explain_rpc_error,
+                lamports_to_sol, nonce_data, priority_fee_lamports, read_account,
+                with_priority_fee,
+            },
+            report::{ReportFormat, WalletReport},
+        },
+        prompt::{
+            confirm_send_summary, prompt_confirmation, prompt_durable_nonce, prompt_input_data,
+            prompt_input_data_with_default, prompt_keypair_from_path, prompt_keypair_path,
+            prompt_optional_data, prompt_select_data, prompt_transfer_amount,
+        },
+        ui::{
+            finish_progress, plain_aware, print_error, print_explorer_link, print_json,
+            print_list_table, print_record_table, show_spinner, theme,
+        },
     },
-    anyhow::bail,
+    anyhow::{anyhow, bail},
+    base64::Engine,
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    indicatif::{ProgressBar, ProgressStyle},
     inquire::Select,
-    solana_nonce::versions::Versions,
-    solana_pubkey::Pubkey,
-    solana_rpc_client_api::config::{RpcLargestAccountsConfig, RpcLargestAccountsFilter},
-    std::fmt,
+    serde::Serialize,
+    serde_json::json,
+    solana_hash::Hash,
+    solana_keypair::{Keypair, Signer},
+    solana_message::Message,
+    solana_nonce::state::{Data as NonceData, State as NonceState},
+    solana_pubkey::{MAX_SEED_LEN, MAX_SEEDS, Pubkey},
+    solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config,
+    solana_rpc_client_api::{
+        config::{RpcLargestAccountsConfig, RpcLargestAccountsFilter, RpcTransactionConfig},
+        response::RpcConfirmedTransactionStatusWithSignature,
+    },
+    solana_signature::Signature,
+    solana_system_interface::{
+        instruction::{self as system_instruction, SystemInstruction},
+        program as system_program,
+    },
+    solana_transaction::versioned::VersionedTransaction,
+    solana_transaction_status::{EncodedTransaction, UiMessage, UiTransactionEncoding},
+    std::{fmt, fs, path::Path, str::FromStr, time::Duration},
 };
 
 /// Commands related to wallet or account management
@@ -21,10 +63,24 @@ use {
 pub enum AccountCommand {
     FetchAccount,
     Balance,
+    RentReserve,
     Transfer,
     Airdrop,
+    BulkAirdrop,
     LargestAccounts,
     NonceAccount,
+    ExportAccount,
+    CloseAccount,
+    Authorities,
+    RotateAuthorities,
+    DerivePda,
+    NonceTransfer,
+    Provenance,
+    WalletReport,
+    WatchDeposits,
+    NonceStatus,
+    CreateNonceAccount,
+    History,
     GoBack,
 }
 
@@ -33,13 +89,76 @@ impl AccountCommand {
         match self {
             AccountCommand::FetchAccount => "Fetching account…",
             AccountCommand::Balance => "Checking SOL balance…",
+            AccountCommand::RentReserve => "Calculating rent-exempt reserve…",
             AccountCommand::Transfer => "Sending SOL…",
             AccountCommand::Airdrop => "Requesting SOL on devnet/testnet…",
+            AccountCommand::BulkAirdrop => "Requesting airdrops up to the target amount…",
             AccountCommand::LargestAccounts => "Fetching largest accounts on the cluster…",
             AccountCommand::NonceAccount => "Inspecting or managing durable nonces…",
+            AccountCommand::ExportAccount => "Exporting account state…",
+            AccountCommand::CloseAccount => "Closing account…",
+            AccountCommand::Authorities => "Scanning stake, vote and nonce authorities…",
+            AccountCommand::RotateAuthorities => "Rotating authorities to a new key…",
+            AccountCommand::DerivePda => "Deriving program address…",
+            AccountCommand::NonceTransfer => "Sending SOL with a durable nonce…",
+            AccountCommand::Provenance => "Paging back to the account's first transaction…",
+            AccountCommand::WalletReport => "Gathering wallet report…",
+            AccountCommand::WatchDeposits => "Watching for incoming SOL…",
+            AccountCommand::NonceStatus => "Checking nonce state…",
+            AccountCommand::CreateNonceAccount => "Creating nonce account…",
+            AccountCommand::History => "Fetching recent transactions…",
             AccountCommand::GoBack => "Going back…",
         }
     }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            AccountCommand::FetchAccount => "Show lamports, owner and data size of any account",
+            AccountCommand::Balance => "Show the SOL balance of any address",
+            AccountCommand::RentReserve => "Split a balance into rent-exempt reserve and free SOL",
+            AccountCommand::Transfer => "Send SOL from your wallet to another address",
+            AccountCommand::Airdrop => "Request SOL to your wallet (devnet/testnet only)",
+            AccountCommand::BulkAirdrop => "Repeat airdrops until a target total is reached",
+            AccountCommand::LargestAccounts => "List the largest accounts on the cluster",
+            AccountCommand::NonceAccount => "Show the stored blockhash and authority of a nonce",
+            AccountCommand::ExportAccount => "Save an account as JSON for solana-test-validator",
+            AccountCommand::CloseAccount => "Empty a data-less system account to reclaim its rent",
+            AccountCommand::Authorities => "List stake, vote and nonce authorities you rely on",
+            AccountCommand::RotateAuthorities => "Move every authority of a key to a new one",
+            AccountCommand::DerivePda => "Find a program derived address and bump from seeds",
+            AccountCommand::NonceTransfer => {
+                "Send SOL signed against a nonce account instead of a recent blockhash"
+            }
+            AccountCommand::Provenance => {
+                "When a stake, vote or any account was created, and by which transaction"
+            }
+            AccountCommand::WalletReport => {
+                "SOL, tokens, stake, vote and recent transactions as one table/JSON/CSV report"
+            }
+            AccountCommand::WatchDeposits => "Report SOL arriving at an address until Ctrl-C",
+            AccountCommand::NonceStatus => {
+                "Check whether transactions built against a nonce would still be accepted"
+            }
+            AccountCommand::CreateNonceAccount => {
+                "Create a durable nonce account from a keypair, for offline signing"
+            }
+            AccountCommand::History => "Recent transactions of an address, newest first",
+            AccountCommand::GoBack => "Return to the command groups",
+        }
+    }
+
+    /// Whether the command signs with the session keypair, which watch-only
+    /// mode does not have
+    pub fn requires_signing(&self) -> bool {
+        matches!(
+            self,
+            AccountCommand::Transfer
+                | AccountCommand::CloseAccount
+                | AccountCommand::RotateAuthorities
+                | AccountCommand::NonceTransfer
+                | AccountCommand::CreateNonceAccount
+        )
+    }
 }
 
 impl fmt::Display for AccountCommand {
@@ -47,10 +166,24 @@ impl fmt::Display for AccountCommand {
         let command = match self {
             AccountCommand::FetchAccount => "Fetch account",
             AccountCommand::Balance => "Check balance",
+            AccountCommand::RentReserve => "View rent-exempt reserve",
             AccountCommand::Transfer => "Transfer SOL",
             AccountCommand::Airdrop => "Request airdrop",
+            AccountCommand::BulkAirdrop => "Bulk airdrop",
             AccountCommand::LargestAccounts => "View largest accounts",
             AccountCommand::NonceAccount => "View nonce account",
+            AccountCommand::ExportAccount => "Export account for test validator",
+            AccountCommand::CloseAccount => "Close empty account",
+            AccountCommand::Authorities => "Review authorities",
+            AccountCommand::RotateAuthorities => "Rotate authorities",
+            AccountCommand::DerivePda => "Derive PDA",
+            AccountCommand::NonceTransfer => "Transfer with durable nonce",
+            AccountCommand::Provenance => "Account provenance",
+            AccountCommand::WalletReport => "Wallet report",
+            AccountCommand::WatchDeposits => "Watch for deposits",
+            AccountCommand::NonceStatus => "Nonce status",
+            AccountCommand::CreateNonceAccount => "Create nonce account",
+            AccountCommand::History => "Transaction history",
             AccountCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -62,24 +195,222 @@ impl AccountCommand {
         match self {
             AccountCommand::FetchAccount => {
                 let pubkey: Pubkey = prompt_input_data("Enter Pubkey:");
-                show_spinner(self.spinner_msg(), fetch_acc_data(ctx, &pubkey)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_acc_data(ctx, &pubkey)).await;
             }
             AccountCommand::Balance => {
                 let pubkey: Pubkey = prompt_input_data("Enter Pubkey :");
-                show_spinner(self.spinner_msg(), fetch_account_balance(ctx, &pubkey)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_account_balance(ctx, &pubkey)).await;
+            }
+            AccountCommand::RentReserve => {
+                let pubkey: Pubkey = prompt_input_data("Enter Pubkey:");
+                let _ = show_spinner(self.spinner_msg(), fetch_rent_reserve(ctx, &pubkey)).await;
             }
             AccountCommand::Transfer => {
-                // show_spinner(self.spinner_msg(), todo!()).await?;
+                let recipient: Pubkey = prompt_input_data_with_default(
+                    "Enter Recipient Address:",
+                    ctx.default_recipient(),
+                );
+                let amount = match prompt_select_data(
+                    "Amount:",
+                    vec!["Enter amount", "Send max (keeps fee and rent reserve)"],
+                ) {
+                    "Enter amount" => Some(
+                        prompt_transfer_amount(
+                            ctx,
+                            "Enter amount to send (SOL):",
+                            ctx.default_amount(),
+                            &[("fees", TRANSFER_FEE_ESTIMATE_LAMPORTS)],
+                        )
+                        .await,
+                    ),
+                    _ => None,
+                };
+                let nonce = prompt_durable_nonce(ctx);
+
+                return confirm_and_transfer(
+                    ctx,
+                    &recipient,
+                    amount,
+                    nonce.as_ref(),
+                    self.spinner_msg(),
+                )
+                .await;
             }
             AccountCommand::Airdrop => {
-                show_spinner(self.spinner_msg(), request_sol_airdrop(ctx)).await;
+                let amount: SolAmount = prompt_input_data_with_default(
+                    "Enter airdrop amount (SOL):",
+                    SolAmount::try_from(DEFAULT_AIRDROP_SOL).ok(),
+                );
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    request_sol_airdrop(ctx, amount.to_lamports()),
+                )
+                .await;
+            }
+            AccountCommand::BulkAirdrop => {
+                let target: SolAmount = prompt_input_data("Enter total SOL to airdrop:");
+
+                // Progress is reported by its own bar, so no spinner here
+                if let Err(e) = request_bulk_airdrop(ctx, target.to_lamports()).await {
+                    print_error(e.to_string());
+                }
             }
             AccountCommand::LargestAccounts => {
-                show_spinner(self.spinner_msg(), fetch_largest_accounts(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_largest_accounts(ctx)).await;
             }
             AccountCommand::NonceAccount => {
                 let pubkey: Pubkey = prompt_input_data("Enter nonce account pubkey:");
-                show_spinner(self.spinner_msg(), fetch_nonce_account(ctx, &pubkey)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_nonce_account(ctx, &pubkey)).await;
+            }
+            AccountCommand::NonceStatus => {
+                let pubkey: Pubkey = prompt_input_data("Enter nonce account pubkey:");
+                let queued_tx: Option<String> = prompt_optional_data(
+                    "Paste a queued transaction to check (base64 or base58, leave empty to skip):",
+                );
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    show_nonce_status(ctx, &pubkey, queued_tx.as_deref()),
+                )
+                .await;
+            }
+            AccountCommand::CreateNonceAccount => {
+                let nonce_keypair_path =
+                    prompt_keypair_path("Enter keypair for the new nonce account:", ctx);
+                let authority: Pubkey =
+                    prompt_input_data_with_default("Enter Nonce Authority:", Some(*ctx.pubkey()));
+
+                let _ = show_spinner(self.spinner_msg(), async {
+                    let nonce_keypair = prompt_keypair_from_path(&nonce_keypair_path)?;
+                    let nonce_pubkey =
+                        create_nonce_account(ctx, &nonce_keypair, &authority).await?;
+                    fetch_nonce_account(ctx, &nonce_pubkey).await
+                })
+                .await;
+            }
+            AccountCommand::ExportAccount => {
+                let pubkey: Pubkey = prompt_input_data("Enter Pubkey:");
+                let output_path: String =
+                    prompt_optional_data("Enter output file (leave empty for <pubkey>.json):")
+                        .unwrap_or_else(|| format!("{pubkey}.json"));
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    export_account(ctx, &pubkey, &output_path),
+                )
+                .await;
+            }
+            AccountCommand::CloseAccount => {
+                let account_keypair_path =
+                    prompt_keypair_path("Enter keypair of the account to close:", ctx);
+                let recipient: Pubkey =
+                    prompt_input_data_with_default("Enter Recipient Address:", Some(*ctx.pubkey()));
+
+                // Asks for confirmation halfway, so no spinner here
+                if let Err(e) = close_account(ctx, &account_keypair_path, &recipient).await {
+                    print_error(e.to_string());
+                }
+            }
+            AccountCommand::Authorities => {
+                let _ = show_spinner(self.spinner_msg(), show_wallet_authorities(ctx)).await;
+            }
+            AccountCommand::RotateAuthorities => {
+                let old_keypair_path =
+                    prompt_keypair_path("Enter keypair of the key to rotate away from:", ctx);
+                let new_authority: Pubkey = prompt_input_data("Enter New Authority Pubkey:");
+
+                // Asks for confirmation per account, so no spinner here
+                let result = match prompt_keypair_from_path(&old_keypair_path) {
+                    Ok(old) => rotate_authorities(ctx, &old, &new_authority).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    print_error(e.to_string());
+                }
+            }
+            AccountCommand::DerivePda => {
+                let program_id: Pubkey = prompt_input_data("Enter Program Id:");
+                let seeds = prompt_pda_seeds();
+
+                // pure derivation, nothing to wait on
+                if let Err(e) = show_program_address(&program_id, &seeds) {
+                    print_error(e.to_string());
+                }
+            }
+            AccountCommand::NonceTransfer => {
+                let recipient: Pubkey = prompt_input_data_with_default(
+                    "Enter Recipient Address:",
+                    ctx.default_recipient(),
+                );
+                let amount = prompt_transfer_amount(
+                    ctx,
+                    "Enter amount to send (SOL):",
+                    ctx.default_amount(),
+                    &[("fees", TRANSFER_FEE_ESTIMATE_LAMPORTS)],
+                )
+                .await;
+
+                // May create a nonce account, so no spinner around the picker
+                let account = match select_nonce_account(ctx).await {
+                    Ok(account) => account,
+                    Err(e) => {
+                        print_error(e.to_string());
+                        return CommandFlow::Process(());
+                    }
+                };
+                let nonce = DurableNonce {
+                    account,
+                    authority: *ctx.pubkey(),
+                };
+
+                return confirm_and_transfer(
+                    ctx,
+                    &recipient,
+                    Some(amount),
+                    Some(&nonce),
+                    self.spinner_msg(),
+                )
+                .await;
+            }
+            AccountCommand::Provenance => {
+                let pubkey: Pubkey = prompt_input_data("Enter Stake or Vote Account Pubkey:");
+                let _ =
+                    show_spinner(self.spinner_msg(), show_account_provenance(ctx, &pubkey)).await;
+            }
+            AccountCommand::WalletReport => {
+                let wallet: Pubkey =
+                    prompt_input_data_with_default("Enter Wallet Address:", Some(*ctx.pubkey()));
+                let format = prompt_select_data("Report Format:", ReportFormat::all());
+                let output_path: Option<String> =
+                    prompt_optional_data("Save to file (leave empty to print):");
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    write_wallet_report(ctx, &wallet, format, output_path.as_deref()),
+                )
+                .await;
+            }
+            AccountCommand::WatchDeposits => {
+                let wallet: Pubkey =
+                    prompt_input_data_with_default("Enter Address to Watch:", Some(*ctx.pubkey()));
+
+                // Runs until Ctrl-C and reports as it goes, so no spinner here
+                if let Err(e) = watch_deposits(ctx, &wallet).await {
+                    print_error(e.to_string());
+                }
+            }
+            AccountCommand::History => {
+                let pubkey: Pubkey =
+                    prompt_input_data_with_default("Enter Pubkey:", Some(*ctx.pubkey()));
+                let limit: usize = prompt_input_data_with_default(
+                    "Number of transactions:",
+                    Some(ACCOUNT_HISTORY_DEFAULT_LIMIT),
+                );
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    fetch_account_history(ctx, &pubkey, limit),
+                )
+                .await;
             }
             AccountCommand::GoBack => {
                 return CommandFlow::GoBack;
@@ -90,171 +421,1919 @@ impl AccountCommand {
     }
 }
 
-async fn request_sol_airdrop(ctx: &ScillaContext) -> anyhow::Result<()> {
-    // request an airdrop worth of 1 SOL
-    let sig = ctx
-        .rpc()
-        .request_airdrop(ctx.pubkey(), sol_to_lamports(1.0))
-        .await;
-    match sig {
-        Ok(signature) => {
-            println!(
-                "{} {}",
-                style("Airdrop requested successfully!").green().bold(),
-                style(format!("Signature: {signature}")).cyan()
+/// Account commands that run once from the command line and exit
+#[derive(Debug, Clone, Subcommand)]
+pub enum AccountArgs {
+    /// SOL balance of an address, your wallet by default
+    Balance { pubkey: Option<Pubkey> },
+    /// Lamports, owner and data size of an account
+    Fetch { pubkey: Pubkey },
+    /// Rent-exempt reserve of an account and how much above it is free
+    RentReserve { pubkey: Pubkey },
+    /// Stored blockhash, fee and authority of a nonce account
+    Nonce { pubkey: Pubkey },
+    /// Recent transactions of an address, your wallet by default
+    History {
+        pubkey: Option<Pubkey>,
+        #[arg(long, default_value_t = ACCOUNT_HISTORY_DEFAULT_LIMIT)]
+        limit: usize,
+    },
+}
+
+impl AccountArgs {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            AccountArgs::Balance { pubkey } => {
+                fetch_account_balance(ctx, &pubkey.unwrap_or(*ctx.pubkey())).await
+            }
+            AccountArgs::Fetch { pubkey } => fetch_acc_data(ctx, pubkey).await,
+            AccountArgs::RentReserve { pubkey } => fetch_rent_reserve(ctx, pubkey).await,
+            AccountArgs::Nonce { pubkey } => fetch_nonce_account(ctx, pubkey).await,
+            AccountArgs::History { pubkey, limit } => {
+                fetch_account_history(ctx, &pubkey.unwrap_or(*ctx.pubkey()), *limit).await
+            }
+        }
+    }
+}
+
+/// How a PDA seed typed at the prompt is turned into bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedKind {
+    Text,
+    Pubkey,
+    Hex,
+    U64,
+    Done,
+}
+
+impl SeedKind {
+    fn all() -> Vec<Self> {
+        vec![
+            SeedKind::Text,
+            SeedKind::Pubkey,
+            SeedKind::Hex,
+            SeedKind::U64,
+            SeedKind::Done,
+        ]
+    }
+
+    fn parse(&self, input: &str) -> anyhow::Result<Vec<u8>> {
+        let seed = match self {
+            SeedKind::Text => input.as_bytes().to_vec(),
+            SeedKind::Pubkey => Pubkey::from_str(input.trim())
+                .map_err(|_| anyhow!("Invalid pubkey: {input}"))?
+                .to_bytes()
+                .to_vec(),
+            SeedKind::Hex => decode_hex(input.trim())?,
+            SeedKind::U64 => input
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| anyhow!("Invalid u64: {input}"))?
+                .to_le_bytes()
+                .to_vec(),
+            SeedKind::Done => bail!("No seed to parse"),
+        };
+
+        if seed.len() > MAX_SEED_LEN {
+            bail!(
+                "Seed is {} bytes; seeds can be at most {MAX_SEED_LEN} bytes",
+                seed.len()
             );
         }
-        Err(err) => {
-            print_error(format!("Airdrop failed: {err}"));
+
+        Ok(seed)
+    }
+}
+
+impl fmt::Display for SeedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedKind::Text => write!(f, "Text (UTF-8)"),
+            SeedKind::Pubkey => write!(f, "Pubkey"),
+            SeedKind::Hex => write!(f, "Bytes (hex)"),
+            SeedKind::U64 => write!(f, "u64 (little-endian)"),
+            SeedKind::Done => write!(f, "Done, derive the address"),
+        }
+    }
+}
+
+fn decode_hex(input: &str) -> anyhow::Result<Vec<u8>> {
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+    if digits.len() % 2 != 0 {
+        bail!("Hex seed needs an even number of digits: {input}");
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            digits
+                .get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| anyhow!("Invalid hex seed: {input}"))
+        })
+        .collect()
+}
+
+/// Asks for seeds one at a time until "Done"; the bump takes the last of the
+/// `MAX_SEEDS` slots
+fn prompt_pda_seeds() -> Vec<(SeedKind, String, Vec<u8>)> {
+    let mut seeds = Vec::new();
+
+    while seeds.len() < MAX_SEEDS - 1 {
+        let kind = prompt_select_data(&format!("Seed {} type:", seeds.len() + 1), SeedKind::all());
+        if kind == SeedKind::Done {
+            break;
+        }
+
+        let input: String = prompt_input_data("Enter seed:");
+        match kind.parse(&input) {
+            Ok(bytes) => seeds.push((kind, input, bytes)),
+            Err(e) => print_error(e),
         }
     }
 
-    Ok(())
+    seeds
 }
 
-async fn fetch_acc_data(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
-    let acc = ctx.rpc().get_account(pubkey).await?;
+fn show_program_address(
+    program_id: &Pubkey,
+    seeds: &[(SeedKind, String, Vec<u8>)],
+) -> anyhow::Result<()> {
+    let seed_slices: Vec<&[u8]> = seeds.iter().map(|(_, _, bytes)| bytes.as_slice()).collect();
+    let (address, bump) = Pubkey::try_find_program_address(&seed_slices, program_id)
+        .ok_or_else(|| anyhow!("No valid program address exists for these seeds"))?;
 
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Lamports"),
-            Cell::new(format!("{}", acc.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Data Length"),
-            Cell::new(format!("{}", acc.data.len())),
-        ])
-        .add_row(vec![
-            Cell::new("Owner"),
-            Cell::new(format!("{}", acc.owner)),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
+        .add_row(vec![Cell::new("Program"), Cell::new(program_id)]);
+
+    for (i, (kind, input, _)) in seeds.iter().enumerate() {
+        table.add_row(vec![
+            Cell::new(format!("Seed {} ({kind})", i + 1)),
+            Cell::new(input),
+        ]);
+    }
+
+    table
         .add_row(vec![
-            Cell::new("Executable"),
-            Cell::new(format!("{}", acc.executable)),
+            Cell::new("Program Derived Address"),
+            Cell::new(theme::success(address)),
         ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(format!("{}", acc.rent_epoch)),
-        ]);
+        .add_row(vec![Cell::new("Bump"), Cell::new(bump)]);
 
-    println!("{}\n{}", style("ACCOUNT INFO").green().bold(), table);
+    println!("\n{}", theme::success("PROGRAM DERIVED ADDRESS").bold());
+    println!("{table}");
 
     Ok(())
 }
 
-async fn fetch_account_balance(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
-    let acc = ctx.rpc().get_account(pubkey).await?;
-    let acc_balance = lamports_to_sol(acc.lamports);
-
-    println!(
-        "{} {}",
-        style("Account balance in SOL:").green().bold(),
-        style(format!("{acc_balance:#?}")).cyan()
-    );
+/// Outcome of a confirmed SOL transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferResult {
+    pub signature: Signature,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+    pub fee: u64,
+}
 
-    Ok(())
+impl TransferResult {
+    fn render(&self, ctx: &ScillaContext) {
+        println!(
+            "{} {}\n{}\n{}",
+            theme::success(format!("Sent {} SOL!", lamports_to_sol(self.lamports))).bold(),
+            theme::info(format!("Signature: {}", self.signature)),
+            theme::warning(format!("To Recipient: {}", self.recipient)),
+            theme::warning(format!("Fee: {} SOL", lamports_to_sol(self.fee)))
+        );
+        print_explorer_link(ctx, &self.signature);
+    }
 }
 
-async fn fetch_largest_accounts(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let filter_choice = Select::new(
-        "Filter accounts by:",
-        vec!["All", "Circulating", "Non-Circulating"],
+/// Shows the transfer summary, checks the recipient and sends with
+/// [`transfer_sol`], shared by the plain and durable nonce transfers
+async fn confirm_and_transfer(
+    ctx: &ScillaContext,
+    recipient: &Pubkey,
+    amount: Option<SolAmount>,
+    nonce: Option<&DurableNonce>,
+    spinner_msg: &str,
+) -> CommandFlow<()> {
+    let Ok(plan) = show_spinner(
+        "Checking balance and fee…",
+        plan_transfer(ctx, recipient, amount),
     )
-    .prompt()?;
+    .await
+    else {
+        return CommandFlow::Process(());
+    };
 
-    let filter = match filter_choice {
-        "Circulating" => Some(RpcLargestAccountsFilter::Circulating),
-        "Non-Circulating" => Some(RpcLargestAccountsFilter::NonCirculating),
-        _ => None,
+    let mut rows = vec![
+        ("From", ctx.pubkey().to_string()),
+        ("To", recipient.to_string()),
+        (
+            "Amount",
+            format!(
+                "{} SOL{}",
+                lamports_to_sol(plan.lamports),
+                if amount.is_none() {
+                    " (max, keeps fee and rent reserve)"
+                } else {
+                    ""
+                }
+            ),
+        ),
+        ("Fee", format!("{} SOL", lamports_to_sol(plan.fee))),
+    ];
+    let priority_fee = ctx.priority_fee_micro_lamports();
+    if priority_fee > 0 {
+        rows.push((
+            "Priority Fee",
+            format!(
+                "up to {} SOL ({priority_fee} micro-lamports per compute unit)",
+                lamports_to_sol(priority_fee_lamports(priority_fee))
+            ),
+        ));
+    }
+    if let Some(nonce) = nonce {
+        rows.push(("Durable Nonce", nonce.account.to_string()));
+    }
+
+    if !confirm_send_summary(ctx, "TRANSFER SUMMARY", &rows) {
+        println!("{}", theme::warning("Transfer cancelled."));
+        return CommandFlow::GoBack;
+    }
+
+    if !confirm_recipient(ctx, recipient).await {
+        println!("{}", theme::warning("Transfer cancelled."));
+        return CommandFlow::GoBack;
+    }
+
+    if let Ok(result) = show_spinner(spinner_msg, transfer_sol(ctx, recipient, &plan, nonce)).await
+    {
+        result.render(ctx);
+    }
+
+    CommandFlow::Process(())
+}
+
+/// What a transfer sends and the fee it pays, worked out before the summary
+/// so it can show them
+struct TransferPlan {
+    lamports: u64,
+    fee: u64,
+}
+
+/// Works out what a transfer sends after checking that the wallet can still
+/// pay the fee. `None` is the maximum, leaving only the fee and the
+/// rent-exempt minimum.
+async fn plan_transfer(
+    ctx: &ScillaContext,
+    recipient: &Pubkey,
+    amount: Option<SolAmount>,
+) -> anyhow::Result<TransferPlan> {
+    if recipient == ctx.pubkey() {
+        bail!("Recipient {recipient} is your own wallet");
+    }
+
+    let balance = ctx.rpc().get_balance(ctx.pubkey()).await?;
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+
+    // the fee doesn't depend on the amount, so any placeholder amount works
+    let fee = ctx
+        .rpc()
+        .get_fee_for_message(&Message::new_with_blockhash(
+            &with_priority_fee(
+                ctx,
+                &[system_instruction::transfer(ctx.pubkey(), recipient, 0)],
+            ),
+            Some(ctx.pubkey()),
+            &blockhash,
+        ))
+        .await?;
+
+    // a wallet left with less than this (but not zero) is rejected for rent
+    let rent_reserve = ctx.rpc().get_minimum_balance_for_rent_exemption(0).await?;
+
+    let lamports = match amount {
+        Some(amount) => {
+            let lamports = amount.to_lamports();
+            let remaining = balance
+                .checked_sub(fee)
+                .and_then(|spendable| spendable.checked_sub(lamports));
+
+            match remaining {
+                None => bail!(
+                    "Insufficient balance: sending {} SOL plus a {} SOL fee needs more than your \
+                     {} SOL",
+                    lamports_to_sol(lamports),
+                    lamports_to_sol(fee),
+                    lamports_to_sol(balance)
+                ),
+                Some(remaining) if remaining != 0 && remaining < rent_reserve => bail!(
+                    "This would leave {} SOL in your wallet, below the {} SOL rent-exempt \
+                     minimum. Send less or use \"Send max\"",
+                    lamports_to_sol(remaining),
+                    lamports_to_sol(rent_reserve)
+                ),
+                Some(_) => lamports,
+            }
+        }
+        None => balance
+            .checked_sub(fee)
+            .and_then(|spendable| spendable.checked_sub(rent_reserve))
+            .filter(|lamports| *lamports > 0)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Nothing to send: {} SOL does not cover the {} SOL fee and {} SOL rent reserve",
+                    lamports_to_sol(balance),
+                    lamports_to_sol(fee),
+                    lamports_to_sol(rent_reserve)
+                )
+            })?,
     };
 
-    let config = RpcLargestAccountsConfig {
-        commitment: Some(ctx.rpc().commitment()),
-        filter,
-        sort_results: Some(true),
+    check_transfer_limit(lamports, ctx.max_transfer(), ctx.cluster())?;
+
+    Ok(TransferPlan { lamports, fee })
+}
+
+/// Sends a planned transfer from the wallet. With a `nonce` it is signed
+/// against that durable nonce.
+async fn transfer_sol(
+    ctx: &ScillaContext,
+    recipient: &Pubkey,
+    plan: &TransferPlan,
+    nonce: Option<&DurableNonce>,
+) -> anyhow::Result<TransferResult> {
+    let instructions = [system_instruction::transfer(
+        ctx.pubkey(),
+        recipient,
+        plan.lamports,
+    )];
+    let signers: [&dyn Signer; 1] = [ctx.keypair()?];
+    let signature = match nonce {
+        Some(nonce) => build_and_send_tx_with_nonce(ctx, &instructions, &signers, nonce).await?,
+        None => build_and_send_tx(ctx, &instructions, &signers).await?,
     };
 
-    let response = ctx.rpc().get_largest_accounts_with_config(config).await?;
-    let largest_accounts = response.value;
+    Ok(TransferResult {
+        signature,
+        recipient: *recipient,
+        lamports: plan.lamports,
+        fee: plan.fee,
+    })
+}
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL).set_header(vec![
-        Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Address").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
-    ]);
+/// What the recipient's account looks like right before SOL is sent to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecipientSummary {
+    /// Lamports, owner and whether it is a program, when the account exists
+    account: Option<(u64, Pubkey, bool)>,
+    recent_transactions: usize,
+    last_active: Option<i64>,
+}
 
-    for (idx, account) in largest_accounts.iter().enumerate() {
-        let balance_sol = lamports_to_sol(account.lamports);
-        table.add_row(vec![
-            Cell::new(format!("{}", idx + 1)),
-            Cell::new(&account.address),
-            Cell::new(format!("{balance_sol:.2}")),
-        ]);
+impl RecipientSummary {
+    /// One line describing the account, and a warning when SOL sent there is
+    /// likely lost or the address looks unused
+    fn describe(&self, now: i64) -> (String, Option<String>) {
+        let activity = match (self.recent_transactions, self.last_active) {
+            (0, _) => "no transactions".to_string(),
+            (count, Some(time)) => format!(
+                "{count}{} recent transactions, last {} ago",
+                if count >= RECIPIENT_CHECK_SIGNATURES {
+                    "+"
+                } else {
+                    ""
+                },
+                format_age(now - time)
+            ),
+            (count, None) => format!("{count} recent transactions"),
+        };
+
+        match self.account {
+            None => (
+                format!("no account yet, {activity}"),
+                (self.recent_transactions == 0).then(|| {
+                    "This address has never been used. Double-check it before sending.".to_string()
+                }),
+            ),
+            Some((lamports, owner, executable)) => {
+                let line = format!(
+                    "{} SOL, owned by {owner}, {activity}",
+                    lamports_to_sol(lamports)
+                );
+                let warning = if executable {
+                    Some("This is a program account. SOL sent to it is usually lost.".to_string())
+                } else if owner != system_program::id() {
+                    Some(format!(
+                        "This account belongs to program {owner}, not a wallet. Only that program \
+                         can move SOL out of it."
+                    ))
+                } else {
+                    None
+                };
+                (line, warning)
+            }
+        }
     }
+}
 
-    println!("\n{}", style("LARGEST ACCOUNTS").green().bold());
-    println!("{table}");
+/// With `preflight-recipient-check` on, prints a summary of the recipient's
+/// account and asks whether to go on. Always true when the check is off.
+async fn confirm_recipient(ctx: &ScillaContext, recipient: &Pubkey) -> bool {
+    if !ctx.preflight_recipient_check() {
+        return true;
+    }
+
+    let summary = async {
+        let account = ctx
+            .rpc()
+            .get_account_with_commitment(recipient, ctx.read_commitment())
+            .await?
+            .value;
+        let signatures = ctx
+            .rpc()
+            .get_signatures_for_address_with_config(
+                recipient,
+                GetConfirmedSignaturesForAddress2Config {
+                    limit: Some(RECIPIENT_CHECK_SIGNATURES),
+                    commitment: Some(ctx.read_commitment()),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )
+            .await?;
+
+        Ok::<_, anyhow::Error>(RecipientSummary {
+            account: account.map(|account| (account.lamports, account.owner, account.executable)),
+            recent_transactions: signatures.len(),
+            last_active: signatures.first().and_then(|status| status.block_time),
+        })
+    };
+
+    let summary = match summary.await {
+        Ok(summary) => summary,
+        Err(e) => {
+            print_error(format!("Could not check the recipient: {e}"));
+            return prompt_confirmation("Send anyway?");
+        }
+    };
+
+    let (line, warning) = summary.describe(chrono::Utc::now().timestamp());
+    println!(
+        "{} {}",
+        theme::info(format!("Recipient {recipient}:")),
+        line
+    );
+    if let Some(warning) = warning {
+        println!("{}", theme::warning(warning).bold());
+    }
+
+    prompt_confirmation("Send to this recipient?")
+}
+
+/// Rejects transfers above the `max-transfer-sol` cap of the current cluster
+fn check_transfer_limit(
+    lamports: u64,
+    limit: Option<SolAmount>,
+    cluster: &Cluster,
+) -> anyhow::Result<()> {
+    if let Some(limit) = limit
+        && lamports > limit.to_lamports()
+    {
+        bail!(
+            "Sending {} SOL exceeds the {limit} SOL limit for {cluster}. Raise max-transfer-sol \
+             in the config to send more",
+            lamports_to_sol(lamports)
+        );
+    }
 
     Ok(())
 }
 
-async fn fetch_nonce_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
-    let account = ctx.rpc().get_account(pubkey).await?;
+/// Closes a system-owned account without data by moving its whole balance to
+/// `recipient`. The wallet pays the fee, so the account ends at zero lamports
+/// and is purged by the runtime.
+async fn close_account(
+    ctx: &ScillaContext,
+    account_keypair_path: &Path,
+    recipient: &Pubkey,
+) -> anyhow::Result<()> {
+    let account_keypair = prompt_keypair_from_path(account_keypair_path)?;
+    let address = account_keypair.pubkey();
+
+    if address == *ctx.pubkey() {
+        bail!("{address} is your wallet; use \"Transfer SOL\" with \"Send max\" instead");
+    }
+    if address == *recipient {
+        bail!("Recipient cannot be the account being closed");
+    }
 
-    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+    let account = read_account(ctx, &address).await?;
 
-    let solana_nonce::state::State::Initialized(data) = versions.state() else {
-        bail!("This account is not an initialized nonce account");
-    };
+    if account.owner != system_program::id() {
+        bail!(
+            "{address} is owned by program {}. Only that program can close it and release its \
+             rent, usually through its own close instruction",
+            account.owner
+        );
+    }
+    if !account.data.is_empty() {
+        bail!(
+            "{address} still holds {} bytes of data. Only system accounts without data can be \
+             closed by transferring their balance out",
+            account.data.len()
+        );
+    }
+    if account.lamports == 0 {
+        bail!("{address} has no lamports to reclaim");
+    }
 
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Address"), Cell::new(pubkey)])
-        .add_row(vec![
-            Cell::new("Lamports"),
-            Cell::new(format!("{}", account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Balance (SOL)"),
-            Cell::new(format!("{:.6}", lamports_to_sol(account.lamports))),
-        ])
-        .add_row(vec![Cell::new("Owner"), Cell::new(account.owner)])
-        .add_row(vec![
-            Cell::new("Executable"),
-            Cell::new(format!("{}", account.executable)),
-        ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(format!("{}", account.rent_epoch)),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
+        .add_row(vec![Cell::new("Account"), Cell::new(address)])
         .add_row(vec![
-            Cell::new("Nonce blockhash"),
-            Cell::new(data.blockhash()),
+            Cell::new("Balance"),
+            Cell::new(format!("{} SOL", lamports_to_sol(account.lamports))),
         ])
-        .add_row(vec![Cell::new("Authority"), Cell::new(data.authority)]);
+        .add_row(vec![Cell::new("Recipient"), Cell::new(recipient)]);
 
-    println!("\n{}", style("NONCE ACCOUNT INFO").green().bold());
-    println!("{table}");
+    println!("\n{}", theme::warning("CLOSE ACCOUNT").bold());
+    println!("{}", table);
+
+    if !prompt_confirmation("This moves the full balance out and closes the account. Continue?") {
+        println!("{}", theme::warning("Close cancelled."));
+        return Ok(());
+    }
+
+    let signature = build_and_send_tx(
+        ctx,
+        &[system_instruction::transfer(
+            &address,
+            recipient,
+            account.lamports,
+        )],
+        &[ctx.keypair()?, &account_keypair],
+    )
+    .await?;
+
+    println!(
+        "{} {}",
+        theme::success(format!(
+            "Closed {address}, reclaimed {} SOL!",
+            lamports_to_sol(account.lamports)
+        ))
+        .bold(),
+        theme::info(format!("Signature: {signature}"))
+    );
+    print_explorer_link(ctx, &signature);
 
     Ok(())
 }
+
+/// Why the faucet turned an airdrop down, as far as its error message tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FaucetRefusal {
+    RunDry,
+    RateLimited,
+    Other,
+}
+
+impl FaucetRefusal {
+    fn from_error(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("run dry") || message.contains("faucet is empty") {
+            FaucetRefusal::RunDry
+        } else if message.contains("rate limit")
+            || message.contains("too many requests")
+            || message.contains("429")
+            || message.contains("limit reached")
+        {
+            FaucetRefusal::RateLimited
+        } else {
+            FaucetRefusal::Other
+        }
+    }
+
+    /// Waiting only helps when the faucet is throttling this address
+    fn is_retryable(&self) -> bool {
+        !matches!(self, FaucetRefusal::RunDry)
+    }
+
+    fn hint(&self) -> &'static str {
+        match self {
+            FaucetRefusal::RunDry => {
+                "The faucet has run dry; waiting will not help. Try https://faucet.solana.com or \
+                 another RPC provider's faucet"
+            }
+            FaucetRefusal::RateLimited => {
+                "The faucet is rate limiting this address; wait a while before trying again"
+            }
+            FaucetRefusal::Other => "The faucet refused the request",
+        }
+    }
+}
+
+/// Seconds to wait before the next airdrop request after `failures` refusals,
+/// doubling each time
+fn airdrop_retry_delay(failures: u32) -> u64 {
+    AIRDROP_RETRY_BASE_DELAY_SECS.saturating_mul(2u64.saturating_pow(failures.saturating_sub(1)))
+}
+
+/// Requests one airdrop, retrying with exponential backoff while the faucet
+/// refuses it and the refusal looks temporary. `on_retry` is told the delay
+/// before each new attempt. Only the request is retried: once the faucet
+/// accepted it, a failure to confirm reports the pending signature instead
+/// of asking for the SOL again.
+async fn request_airdrop_with_retries(
+    ctx: &ScillaContext,
+    lamports: u64,
+    mut on_retry: impl FnMut(u64),
+) -> anyhow::Result<Signature> {
+    let mut failures: u32 = 0;
+
+    let signature = loop {
+        let err = match ctx.rpc().request_airdrop(ctx.pubkey(), lamports).await {
+            Ok(signature) => break signature,
+            Err(err) => err,
+        };
+
+        let refusal = FaucetRefusal::from_error(&err.to_string());
+        failures += 1;
+        if !refusal.is_retryable() || failures > ctx.airdrop_retries() {
+            bail!(
+                "{}. Gave up after {failures} attempt(s). Last error: {err}",
+                refusal.hint()
+            );
+        }
+
+        let delay = airdrop_retry_delay(failures);
+        on_retry(delay);
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+    };
+
+    if let Err(err) = ctx.rpc().poll_for_signature(&signature).await {
+        bail!(
+            "The faucet accepted the airdrop but it was not confirmed: {err}. Check signature \
+             {signature} before requesting again"
+        );
+    }
+
+    Ok(signature)
+}
+
+/// Requests a single airdrop of `lamports` and returns once it confirmed
+async fn request_sol_airdrop(ctx: &ScillaContext, lamports: u64) -> anyhow::Result<()> {
+    if ctx.cluster() == &Cluster::Mainnet {
+        bail!("Airdrops are not available on mainnet");
+    }
+
+    let signature = request_airdrop_with_retries(ctx, lamports, |_| {}).await?;
+
+    println!(
+        "{} {}",
+        theme::success(format!(
+            "Airdrop of {} SOL confirmed!",
+            lamports_to_sol(lamports)
+        ))
+        .bold(),
+        theme::info(format!("Signature: {signature}"))
+    );
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+async fn request_bulk_airdrop(ctx: &ScillaContext, target_lamports: u64) -> anyhow::Result<()> {
+    if ctx.cluster() == &Cluster::Mainnet {
+        bail!("Airdrops are not available on mainnet");
+    }
+
+    let progress = plain_aware(ProgressBar::new(target_lamports));
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40.cyan/blue} {percent}% {msg}")
+            .unwrap()
+            .progress_chars("█▉▊▋▌▍▎▏ "),
+    );
+
+    let mut received: u64 = 0;
+
+    while received < target_lamports {
+        let chunk = (target_lamports - received).min(AIRDROP_MAX_LAMPORTS_PER_REQUEST);
+        progress.set_message(format!("requesting {} SOL…", lamports_to_sol(chunk)));
+
+        let result = request_airdrop_with_retries(ctx, chunk, |delay| {
+            progress.set_message(format!("faucet refused, retrying in {delay}s…"));
+        })
+        .await;
+
+        if let Err(err) = result {
+            progress.abandon_with_message("stopped");
+            bail!(
+                "Stopped after receiving {} of {} SOL. {err}",
+                lamports_to_sol(received),
+                lamports_to_sol(target_lamports)
+            );
+        }
+
+        received += chunk;
+        progress.set_position(received);
+    }
+
+    progress.finish_with_message("done");
+
+    println!(
+        "{} {}",
+        theme::success("Airdropped").bold(),
+        theme::info(format!(
+            "{} SOL to {}",
+            lamports_to_sol(received),
+            ctx.pubkey()
+        ))
+    );
+
+    Ok(())
+}
+
+/// `signature` cut down to its first and last 8 characters for tables
+fn short_signature(signature: &str) -> String {
+    match (
+        signature.get(..8),
+        signature.get(signature.len().saturating_sub(8)..),
+    ) {
+        (Some(head), Some(tail)) if signature.len() > 16 => format!("{head}…{tail}"),
+        _ => signature.to_string(),
+    }
+}
+
+async fn fetch_account_history(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+    limit: usize,
+) -> anyhow::Result<()> {
+    if limit == 0 || limit > SIGNATURES_PAGE_LIMIT {
+        bail!("Number of transactions must be between 1 and {SIGNATURES_PAGE_LIMIT}");
+    }
+
+    let signatures = ctx
+        .rpc()
+        .get_signatures_for_address_with_config(
+            pubkey,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(limit),
+                commitment: Some(ctx.read_commitment()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )
+        .await?;
+
+    if signatures.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!("No transactions found for {pubkey}"))
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Signature"),
+        theme::bold_cell("Slot"),
+        theme::bold_cell("Block Time"),
+        theme::bold_cell("Confirmation"),
+        theme::bold_cell("Result"),
+    ]);
+
+    for entry in &signatures {
+        // CSV and JSON keep the full signature so it can be looked up later
+        let signature = match ctx.output_format() {
+            OutputFormat::Table => short_signature(&entry.signature),
+            OutputFormat::Csv | OutputFormat::Json => entry.signature.clone(),
+        };
+        let block_time = entry
+            .block_time
+            .and_then(chrono::DateTime::<chrono::Utc>::from_timestamp_secs)
+            .map_or_else(
+                || "Unknown".to_string(),
+                |dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            );
+        let confirmation = entry
+            .confirmation_status
+            .as_ref()
+            .map_or_else(|| "Unknown".to_string(), |status| format!("{status:?}"));
+
+        table.add_row(vec![
+            Cell::new(signature),
+            Cell::new(entry.slot),
+            Cell::new(block_time),
+            Cell::new(confirmation),
+            if entry.err.is_some() {
+                theme::error_cell("Failed")
+            } else {
+                theme::success_cell("Success")
+            },
+        ]);
+    }
+
+    print_list_table(ctx, &format!("TRANSACTION HISTORY ({pubkey})"), &table);
+
+    Ok(())
+}
+
+async fn fetch_acc_data(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let acc = read_account(ctx, pubkey).await?;
+
+    if ctx.output_format() == OutputFormat::Json {
+        return print_json(&json!({
+            "address": pubkey.to_string(),
+            "lamports": acc.lamports,
+            "dataLength": acc.data.len(),
+            "owner": acc.owner.to_string(),
+            "executable": acc.executable,
+            "rentEpoch": acc.rent_epoch,
+        }));
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Lamports"),
+            Cell::new(format!("{}", acc.lamports)),
+        ])
+        .add_row(vec![
+            Cell::new("Data Length"),
+            Cell::new(format!("{}", acc.data.len())),
+        ])
+        .add_row(vec![
+            Cell::new("Owner"),
+            Cell::new(format!("{}", acc.owner)),
+        ])
+        .add_row(vec![
+            Cell::new("Executable"),
+            Cell::new(format!("{}", acc.executable)),
+        ])
+        .add_row(vec![
+            Cell::new("Rent Epoch"),
+            Cell::new(format!("{}", acc.rent_epoch)),
+        ]);
+
+    println!("{}\n{}", theme::success("ACCOUNT INFO").bold(), table);
+
+    Ok(())
+}
+
+async fn fetch_account_balance(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let balance = ctx
+        .rpc()
+        .get_balance_with_commitment(pubkey, ctx.read_commitment())
+        .await?
+        .value;
+    let acc_balance = lamports_to_sol(balance);
+
+    if ctx.output_format() == OutputFormat::Json {
+        return print_json(&json!({
+            "address": pubkey.to_string(),
+            "lamports": balance,
+            "sol": acc_balance,
+        }));
+    }
+
+    println!(
+        "{} {}",
+        theme::success("Account balance in SOL:").bold(),
+        theme::info(format!("{acc_balance:#?}"))
+    );
+
+    Ok(())
+}
+
+async fn fetch_rent_reserve(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let acc = read_account(ctx, pubkey).await?;
+    let rent_reserve = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(acc.data.len())
+        .await?;
+
+    // an account below the reserve has nothing it can move without being closed
+    let free = acc.lamports.saturating_sub(rent_reserve);
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Owner"),
+            Cell::new(format!("{}", acc.owner)),
+        ])
+        .add_row(vec![
+            Cell::new("Data Length"),
+            Cell::new(format!("{} bytes", acc.data.len())),
+        ])
+        .add_row(vec![
+            Cell::new("Total Balance"),
+            Cell::new(format!("{} SOL", lamports_to_sol(acc.lamports))),
+        ])
+        .add_row(vec![
+            Cell::new("Rent-Exempt Reserve"),
+            Cell::new(format!("{} SOL", lamports_to_sol(rent_reserve))),
+        ])
+        .add_row(vec![
+            Cell::new("Free Balance"),
+            Cell::new(theme::success(format!("{} SOL", lamports_to_sol(free)))),
+        ]);
+
+    print_record_table(ctx, "RENT-EXEMPT RESERVE", &table);
+
+    if acc.lamports < rent_reserve && ctx.output_format() == OutputFormat::Table {
+        println!(
+            "{}",
+            theme::warning("Account balance is below its rent-exempt reserve")
+        );
+    }
+
+    Ok(())
+}
+
+async fn fetch_largest_accounts(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let filter_choice = Select::new(
+        "Filter accounts by:",
+        vec!["All", "Circulating", "Non-Circulating"],
+    )
+    .prompt()?;
+
+    let filter = match filter_choice {
+        "Circulating" => Some(RpcLargestAccountsFilter::Circulating),
+        "Non-Circulating" => Some(RpcLargestAccountsFilter::NonCirculating),
+        _ => None,
+    };
+
+    let config = RpcLargestAccountsConfig {
+        commitment: Some(ctx.rpc().commitment()),
+        filter,
+        sort_results: Some(true),
+    };
+
+    let response = ctx
+        .rpc()
+        .get_largest_accounts_with_config(config)
+        .await
+        .map_err(|e| explain_rpc_error(ctx, "getLargestAccounts", e))?;
+    let largest_accounts = response.value;
+
+    if ctx.output_format() == OutputFormat::Json {
+        let accounts: Vec<_> = largest_accounts
+            .iter()
+            .map(|account| {
+                json!({
+                    "address": account.address,
+                    "lamports": account.lamports,
+                    "sol": lamports_to_sol(account.lamports),
+                })
+            })
+            .collect();
+        return print_json(&accounts);
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("#"),
+        theme::bold_cell("Address"),
+        theme::bold_cell("Balance (SOL)"),
+    ]);
+
+    for (idx, account) in largest_accounts.iter().enumerate() {
+        let balance_sol = lamports_to_sol(account.lamports);
+        table.add_row(vec![
+            Cell::new(format!("{}", idx + 1)),
+            Cell::new(&account.address),
+            Cell::new(format!("{balance_sol:.2}")),
+        ]);
+    }
+
+    print_list_table(ctx, "LARGEST ACCOUNTS", &table);
+
+    Ok(())
+}
+
+async fn show_wallet_authorities(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let wallet = ctx.pubkey();
+    let records = fetch_wallet_authorities(ctx, wallet).await?;
+
+    if records.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "No stake, vote or nonce accounts reference {wallet}"
+            ))
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Account"),
+        theme::bold_cell("Type"),
+        theme::bold_cell("Role"),
+        theme::bold_cell("Authority"),
+        theme::bold_cell("Held By"),
+    ]);
+
+    let mut external = 0;
+    for record in &records {
+        let held_by = if record.authority == *wallet {
+            theme::success_cell("Wallet")
+        } else {
+            external += 1;
+            theme::error_cell("External")
+        };
+
+        table.add_row(vec![
+            Cell::new(record.account),
+            Cell::new(record.role.account_kind()),
+            Cell::new(record.role),
+            Cell::new(record.authority),
+            held_by,
+        ]);
+    }
+
+    print_list_table(ctx, "WALLET AUTHORITIES", &table);
+
+    if external > 0 && ctx.output_format() == OutputFormat::Table {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "{external} authorit{} held by keys other than {wallet}",
+                if external == 1 { "y is" } else { "ies are" }
+            ))
+        );
+    }
+
+    Ok(())
+}
+
+async fn fetch_nonce_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let account = read_account(ctx, pubkey).await?;
+    let data = nonce_data(&account)?;
+
+    if ctx.output_format() == OutputFormat::Json {
+        return print_json(&json!({
+            "address": pubkey.to_string(),
+            "lamports": account.lamports,
+            "owner": account.owner.to_string(),
+            "nonceBlockhash": data.blockhash().to_string(),
+            "authority": data.authority.to_string(),
+        }));
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Address"), Cell::new(pubkey)])
+        .add_row(vec![
+            Cell::new("Lamports"),
+            Cell::new(format!("{}", account.lamports)),
+        ])
+        .add_row(vec![
+            Cell::new("Balance (SOL)"),
+            Cell::new(format!("{:.6}", lamports_to_sol(account.lamports))),
+        ])
+        .add_row(vec![Cell::new("Owner"), Cell::new(account.owner)])
+        .add_row(vec![
+            Cell::new("Executable"),
+            Cell::new(format!("{}", account.executable)),
+        ])
+        .add_row(vec![
+            Cell::new("Rent Epoch"),
+            Cell::new(format!("{}", account.rent_epoch)),
+        ])
+        .add_row(vec![
+            Cell::new("Nonce blockhash"),
+            Cell::new(data.blockhash()),
+        ])
+        .add_row(vec![Cell::new("Authority"), Cell::new(data.authority)]);
+
+    print_record_table(ctx, "NONCE ACCOUNT INFO", &table);
+
+    Ok(())
+}
+
+/// Whether a transaction built against a nonce account would be accepted now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueuedNonceTx {
+    /// Advances this nonce first and was built against its stored value
+    Valid,
+    /// Built against an older value; the nonce has been advanced since
+    NonceAdvanced,
+    /// Does not start by advancing this nonce, so its blockhash is treated as
+    /// a regular recent blockhash and expires after ~150 slots
+    NotDurable,
+}
+
+impl QueuedNonceTx {
+    fn check(tx: &VersionedTransaction, nonce_account: &Pubkey, stored_nonce: &Hash) -> Self {
+        let keys = tx.message.static_account_keys();
+        let advances_nonce = tx.message.instructions().first().is_some_and(|ix| {
+            keys.get(ix.program_id_index as usize) == Some(&system_program::id())
+                && ix
+                    .accounts
+                    .first()
+                    .and_then(|index| keys.get(*index as usize))
+                    == Some(nonce_account)
+                && matches!(
+                    bincode_deserialize::<SystemInstruction>(&ix.data, "system instruction"),
+                    Ok(SystemInstruction::AdvanceNonceAccount)
+                )
+        });
+
+        if !advances_nonce {
+            QueuedNonceTx::NotDurable
+        } else if tx.message.recent_blockhash() == stored_nonce {
+            QueuedNonceTx::Valid
+        } else {
+            QueuedNonceTx::NonceAdvanced
+        }
+    }
+
+    fn guidance(&self) -> &'static str {
+        match self {
+            QueuedNonceTx::Valid => {
+                "Still valid: it will be accepted as long as nothing advances the nonce first"
+            }
+            QueuedNonceTx::NonceAdvanced => {
+                "Rejected: the nonce was advanced after it was built. Rebuild it against the \
+                 stored nonce above and sign it again"
+            }
+            QueuedNonceTx::NotDurable => {
+                "Not a durable nonce transaction for this account: its first instruction must \
+                 advance this nonce, otherwise the blockhash expires like any recent blockhash"
+            }
+        }
+    }
+}
+
+/// Shows the stored nonce and when it was last used, and whether `queued_tx`
+/// (base64 or base58) would still be accepted against it
+async fn show_nonce_status(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+    queued_tx: Option<&str>,
+) -> anyhow::Result<()> {
+    let queued_tx = queued_tx
+        .map(|encoded| {
+            let bytes = decode_base64(encoded).or_else(|_| decode_base58(encoded))?;
+            bincode_deserialize::<VersionedTransaction>(&bytes, "transaction")
+        })
+        .transpose()?;
+
+    let account = read_account(ctx, pubkey).await?;
+    let data = nonce_data(&account)?;
+    let current_slot = ctx
+        .rpc()
+        .get_slot_with_commitment(ctx.read_commitment())
+        .await?;
+    let last_used = ctx
+        .rpc()
+        .get_signatures_for_address_with_config(
+            pubkey,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(1),
+                commitment: Some(ctx.read_commitment()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )
+        .await?
+        .first()
+        .map(|status| status.slot);
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Address"), Cell::new(pubkey)])
+        .add_row(vec![Cell::new("Authority"), Cell::new(data.authority)])
+        .add_row(vec![Cell::new("Stored Nonce"), Cell::new(data.blockhash())])
+        .add_row(vec![
+            Cell::new("Fee per Signature"),
+            Cell::new(format!(
+                "{} lamports",
+                data.fee_calculator.lamports_per_signature
+            )),
+        ])
+        .add_row(vec![
+            Cell::new("Last Used"),
+            Cell::new(match last_used {
+                Some(slot) => format!(
+                    "slot {slot} ({} slots ago)",
+                    current_slot.saturating_sub(slot)
+                ),
+                None => "never".to_string(),
+            }),
+        ]);
+
+    let verdict = queued_tx.map(|tx| {
+        let verdict = QueuedNonceTx::check(&tx, pubkey, &data.blockhash());
+        table.add_row(vec![
+            Cell::new("Queued Tx Blockhash"),
+            Cell::new(tx.message.recent_blockhash()),
+        ]);
+        table.add_row(vec![
+            Cell::new("Queued Tx"),
+            match verdict {
+                QueuedNonceTx::Valid => theme::success_cell("Valid"),
+                _ => theme::error_cell("Will be rejected"),
+            },
+        ]);
+        verdict
+    });
+
+    println!("\n{}", theme::success("NONCE STATUS").bold());
+    println!("{table}");
+
+    match verdict {
+        Some(verdict) => println!("{}", theme::info(verdict.guidance())),
+        None => println!(
+            "{}",
+            theme::muted(
+                "A durable nonce never expires with time. A transaction built against the stored \
+                 nonce stays valid until the nonce is advanced, which any transaction using it \
+                 does; one built against an older value is rejected."
+            )
+        ),
+    }
+
+    Ok(())
+}
+
+/// Lets the user pick one of the nonce accounts the wallet is authority of, or
+/// create one when there are none
+async fn select_nonce_account(ctx: &ScillaContext) -> anyhow::Result<Pubkey> {
+    let mut owned: Vec<(Pubkey, NonceData)> = fetch_nonce_accounts_by_authority(ctx, ctx.pubkey())
+        .await?
+        .into_iter()
+        .filter_map(|(pubkey, account)| {
+            nonce_data(&account)
+                .ok()
+                .filter(|data| data.authority == *ctx.pubkey())
+                .map(|data| (pubkey, data))
+        })
+        .collect();
+    owned.sort_by_key(|(pubkey, _)| *pubkey);
+
+    if owned.is_empty() {
+        if !prompt_confirmation(&format!(
+            "{} is not the authority of any nonce account. Create one now?",
+            ctx.pubkey()
+        )) {
+            bail!("A durable nonce transfer needs a nonce account");
+        }
+        return create_nonce_account(ctx, &Keypair::new(), ctx.pubkey()).await;
+    }
+
+    let choices: Vec<String> = owned
+        .iter()
+        .map(|(pubkey, data)| format!("{pubkey} (nonce {})", data.blockhash()))
+        .collect();
+    let choice = prompt_select_data("Nonce account:", choices.clone());
+    let index = choices
+        .iter()
+        .position(|c| *c == choice)
+        .unwrap_or_default();
+
+    Ok(owned[index].0)
+}
+
+/// Creates a rent-exempt nonce account at the address of `nonce_keypair`,
+/// funded by the wallet. The nonce keypair is only needed for this one
+/// signature; `authority` advances and withdraws from the nonce afterwards.
+async fn create_nonce_account(
+    ctx: &ScillaContext,
+    nonce_keypair: &Keypair,
+    authority: &Pubkey,
+) -> anyhow::Result<Pubkey> {
+    let nonce_pubkey = nonce_keypair.pubkey();
+    if ctx
+        .rpc()
+        .get_account_with_commitment(&nonce_pubkey, ctx.rpc().commitment())
+        .await?
+        .value
+        .is_some()
+    {
+        bail!("Account {nonce_pubkey} already exists; use a fresh keypair for the nonce account");
+    }
+
+    let rent = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(NonceState::size())
+        .await?;
+
+    let signature = build_and_send_tx(
+        ctx,
+        &system_instruction::create_nonce_account(ctx.pubkey(), &nonce_pubkey, authority, rent),
+        &[ctx.keypair()?, nonce_keypair],
+    )
+    .await?;
+
+    println!(
+        "{} {}",
+        theme::success(format!(
+            "Created nonce account {nonce_pubkey} with {} SOL rent reserve",
+            lamports_to_sol(rent)
+        ))
+        .bold(),
+        theme::info(format!("Signature: {signature}"))
+    );
+    print_explorer_link(ctx, &signature);
+
+    Ok(nonce_pubkey)
+}
+
+/// Oldest successful transaction that touched `address`, found by paging back
+/// through its whole signature history. For an account that was never closed
+/// this is the transaction that created it.
+async fn fetch_creation_signature(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+) -> anyhow::Result<RpcConfirmedTransactionStatusWithSignature> {
+    let mut before = None;
+    let mut oldest = None;
+
+    loop {
+        let page = ctx
+            .rpc()
+            .get_signatures_for_address_with_config(
+                address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(SIGNATURES_PAGE_LIMIT),
+                    commitment: Some(ctx.read_commitment()),
+                },
+            )
+            .await?;
+
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        let is_full_page = page.len() == SIGNATURES_PAGE_LIMIT;
+
+        // pages run newest to oldest, so the last success is the oldest one
+        if let Some(status) = page.into_iter().rev().find(|status| status.err.is_none()) {
+            oldest = Some(status);
+        }
+
+        if !is_full_page {
+            break;
+        }
+    }
+
+    oldest.ok_or_else(|| anyhow!("No successful transactions found for {address}"))
+}
+
+/// `seconds` as days and hours, or hours and minutes below a day
+fn format_age(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (days, hours, minutes) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+    );
+
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else {
+        format!("{hours}h {minutes}m")
+    }
+}
+
+async fn show_account_provenance(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let account = ctx
+        .rpc()
+        .get_account_with_commitment(pubkey, ctx.read_commitment())
+        .await?;
+    let creation = fetch_creation_signature(ctx, pubkey).await?;
+
+    let kind = match account.value {
+        Some(account) if account.owner == solana_stake_interface::program::id() => {
+            "Stake account".to_string()
+        }
+        Some(account) if account.owner == solana_vote_interface::program::id() => {
+            "Vote account".to_string()
+        }
+        Some(account) => format!("Owned by {}", account.owner),
+        None => "Closed or never funded".to_string(),
+    };
+
+    let created_at = creation.block_time.map(|block_time| {
+        let date = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "Invalid timestamp".to_string());
+        let age = format_age(chrono::Utc::now().timestamp() - block_time);
+        (date, age)
+    });
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Account"), Cell::new(pubkey)])
+        .add_row(vec![Cell::new("Type"), Cell::new(kind)])
+        .add_row(vec![
+            Cell::new("Creation Signature"),
+            Cell::new(&creation.signature),
+        ])
+        .add_row(vec![Cell::new("Creation Slot"), Cell::new(creation.slot)]);
+
+    match created_at {
+        Some((date, age)) => {
+            table
+                .add_row(vec![Cell::new("Created At"), Cell::new(date)])
+                .add_row(vec![Cell::new("Age"), Cell::new(age)]);
+        }
+        None => {
+            table.add_row(vec![
+                Cell::new("Created At"),
+                theme::warning_cell("Block time unavailable"),
+            ]);
+        }
+    }
+
+    println!("\n{}", theme::success("ACCOUNT PROVENANCE").bold());
+    println!("{table}");
+    println!(
+        "{}",
+        theme::muted("RPC nodes without full ledger history may not know the oldest transactions.")
+    );
+
+    let signature = Signature::from_str(&creation.signature)?;
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+/// SOL `wallet` received in a transaction and the account that paid the most
+/// into it. Balances are indexed like `account_keys`.
+fn incoming_transfer(
+    account_keys: &[String],
+    pre_balances: &[u64],
+    post_balances: &[u64],
+    wallet: &str,
+) -> Option<(String, u64)> {
+    let index = account_keys.iter().position(|key| key == wallet)?;
+    let received = post_balances
+        .get(index)?
+        .checked_sub(*pre_balances.get(index)?)
+        .filter(|received| *received > 0)?;
+
+    let sender = (0..account_keys.len())
+        .filter(|i| *i != index)
+        .max_by_key(|i| {
+            let pre = pre_balances.get(*i).copied().unwrap_or_default();
+            let post = post_balances.get(*i).copied().unwrap_or_default();
+            pre.saturating_sub(post)
+        })
+        .map(|i| account_keys[i].clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Some((sender, received))
+}
+
+async fn fetch_incoming_transfer(
+    ctx: &ScillaContext,
+    signature: &Signature,
+    wallet: &Pubkey,
+) -> anyhow::Result<Option<(String, u64)>> {
+    let tx = ctx
+        .rpc()
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::JsonParsed),
+                commitment: Some(ctx.read_commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+
+    let Some(meta) = tx.transaction.meta else {
+        return Ok(None);
+    };
+    let EncodedTransaction::Json(ui_tx) = tx.transaction.transaction else {
+        return Ok(None);
+    };
+    let UiMessage::Parsed(message) = ui_tx.message else {
+        return Ok(None);
+    };
+
+    let account_keys: Vec<String> = message
+        .account_keys
+        .into_iter()
+        .map(|account| account.pubkey)
+        .collect();
+
+    Ok(incoming_transfer(
+        &account_keys,
+        &meta.pre_balances,
+        &meta.post_balances,
+        &wallet.to_string(),
+    ))
+}
+
+/// Polls `wallet`'s signatures and prints every transaction that raised its
+/// SOL balance, with sender, amount and signature, until Ctrl-C is pressed.
+/// Transactions from before the watch started are not reported.
+async fn watch_deposits(ctx: &ScillaContext, wallet: &Pubkey) -> anyhow::Result<()> {
+    let signatures_until = |until: Option<Signature>, limit: usize| {
+        ctx.rpc().get_signatures_for_address_with_config(
+            wallet,
+            GetConfirmedSignaturesForAddress2Config {
+                before: None,
+                until,
+                limit: Some(limit),
+                commitment: Some(ctx.read_commitment()),
+            },
+        )
+    };
+
+    let mut last_seen = match signatures_until(None, 1).await?.first() {
+        Some(entry) => Some(Signature::from_str(&entry.signature)?),
+        None => None,
+    };
+
+    let progress = plain_aware(ProgressBar::new_spinner());
+    progress.set_style(
+        ProgressStyle::with_template("{spinner:.cyan} [{elapsed}] {msg}")
+            .unwrap()
+            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+    );
+    progress.enable_steady_tick(Duration::from_millis(100));
+    progress.set_message(format!("Watching {wallet} for deposits, Ctrl-C to stop"));
+
+    let mut interval = tokio::time::interval(Duration::from_secs(DEPOSIT_POLL_INTERVAL_SECS));
+    let mut deposits = 0;
+    let mut received = 0;
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = interval.tick() => {}
+        }
+
+        let page = signatures_until(last_seen, SIGNATURES_PAGE_LIMIT).await?;
+        let Some(newest) = page.first() else {
+            continue;
+        };
+        last_seen = Some(Signature::from_str(&newest.signature)?);
+
+        for entry in page.iter().rev().filter(|entry| entry.err.is_none()) {
+            let signature = Signature::from_str(&entry.signature)?;
+            let Some((sender, lamports)) = fetch_incoming_transfer(ctx, &signature, wallet).await?
+            else {
+                continue;
+            };
+
+            deposits += 1;
+            received += lamports;
+            progress.suspend(|| {
+                println!(
+                    "{} {} {}",
+                    theme::success(format!("+{} SOL", lamports_to_sol(lamports))).bold(),
+                    theme::info(format!("from {sender}")),
+                    theme::muted(signature)
+                );
+            });
+            progress.set_message(format!(
+                "Watching {wallet}: {deposits} deposit(s), {} SOL, Ctrl-C to stop",
+                lamports_to_sol(received)
+            ));
+        }
+    }
+
+    finish_progress(
+        &progress,
+        format!(
+            "Stopped watching {wallet}: {deposits} deposit(s), {} SOL",
+            lamports_to_sol(received)
+        ),
+    );
+
+    Ok(())
+}
+
+async fn write_wallet_report(
+    ctx: &ScillaContext,
+    wallet: &Pubkey,
+    format: ReportFormat,
+    output_path: Option<&str>,
+) -> anyhow::Result<()> {
+    let report = WalletReport::gather(ctx, wallet).await?;
+    let rendered = report.render(format)?;
+
+    match output_path {
+        Some(path) => {
+            fs::write(path, console::strip_ansi_codes(&rendered).as_ref())?;
+            println!(
+                "{} {}",
+                theme::success("Wallet report saved to").bold(),
+                theme::info(path)
+            );
+        }
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
+
+/// Account file layout accepted by `solana-test-validator --account`
+#[derive(Serialize)]
+struct ExportedAccount {
+    pubkey: String,
+    account: ExportedAccountData,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedAccountData {
+    lamports: u64,
+    data: [String; 2],
+    owner: String,
+    executable: bool,
+    rent_epoch: u64,
+    space: u64,
+}
+
+async fn export_account(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+    output_path: &str,
+) -> anyhow::Result<()> {
+    let account = ctx.rpc().get_account(pubkey).await?;
+
+    let exported = ExportedAccount {
+        pubkey: pubkey.to_string(),
+        account: ExportedAccountData {
+            lamports: account.lamports,
+            data: [
+                base64::engine::general_purpose::STANDARD.encode(&account.data),
+                "base64".to_string(),
+            ],
+            owner: account.owner.to_string(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            space: account.data.len() as u64,
+        },
+    };
+
+    fs::write(output_path, serde_json::to_string_pretty(&exported)?)?;
+
+    println!(
+        "{} {}",
+        theme::success("Account exported to").bold(),
+        theme::info(output_path)
+    );
+    println!(
+        "{}",
+        theme::muted(format!(
+            "Load it with: solana-test-validator --account {pubkey} {output_path}"
+        ))
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::misc::helpers::sol_to_lamports, solana_instruction::Instruction,
+        solana_transaction::Transaction,
+    };
+
+    #[test]
+    fn test_queued_nonce_tx_check() {
+        let nonce = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let stored = Hash::new_unique();
+        let tx = |ixs: &[Instruction], blockhash: Hash| {
+            VersionedTransaction::from(Transaction::new_unsigned(Message::new_with_blockhash(
+                ixs,
+                Some(&authority),
+                &blockhash,
+            )))
+        };
+        let advance = system_instruction::advance_nonce_account(&nonce, &authority);
+        let transfer = system_instruction::transfer(&authority, &Pubkey::new_unique(), 1);
+
+        assert_eq!(
+            QueuedNonceTx::check(
+                &tx(&[advance.clone(), transfer.clone()], stored),
+                &nonce,
+                &stored
+            ),
+            QueuedNonceTx::Valid
+        );
+        assert_eq!(
+            QueuedNonceTx::check(
+                &tx(&[advance.clone(), transfer.clone()], Hash::new_unique()),
+                &nonce,
+                &stored
+            ),
+            QueuedNonceTx::NonceAdvanced
+        );
+        assert_eq!(
+            QueuedNonceTx::check(&tx(&[transfer, advance], stored), &nonce, &stored),
+            QueuedNonceTx::NotDurable
+        );
+    }
+
+    #[test]
+    fn test_incoming_transfer_picks_largest_payer() {
+        let keys: Vec<String> = ["payer", "wallet", "other"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            incoming_transfer(&keys, &[10_000, 0, 500], &[4_995, 5_000, 500], "wallet"),
+            Some(("payer".to_string(), 5_000))
+        );
+        // the wallet paying out is not a deposit
+        assert_eq!(
+            incoming_transfer(&keys, &[0, 10_000, 0], &[5_000, 4_995, 0], "wallet"),
+            None
+        );
+        assert_eq!(
+            incoming_transfer(&keys, &[0, 0, 0], &[0, 0, 0], "absent"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recipient_summary_warnings() {
+        let now = 1_700_000_000;
+
+        let wallet = RecipientSummary {
+            account: Some((
+                crate::constants::LAMPORTS_PER_SOL,
+                system_program::id(),
+                false,
+            )),
+            recent_transactions: RECIPIENT_CHECK_SIGNATURES,
+            last_active: Some(now - 7_200),
+        };
+        let (line, warning) = wallet.describe(now);
+        assert!(line.contains(&format!(
+            "{RECIPIENT_CHECK_SIGNATURES}+ recent transactions, last 2h 0m ago"
+        )));
+        assert!(warning.is_none());
+
+        let program = RecipientSummary {
+            account: Some((
+                crate::constants::LAMPORTS_PER_SOL,
+                Pubkey::new_unique(),
+                true,
+            )),
+            recent_transactions: 3,
+            last_active: None,
+        };
+        assert!(program.describe(now).1.unwrap().contains("program account"));
+
+        let unused = RecipientSummary {
+            account: None,
+            recent_transactions: 0,
+            last_active: None,
+        };
+        let (line, warning) = unused.describe(now);
+        assert_eq!(line, "no account yet, no transactions");
+        assert!(warning.unwrap().contains("never been used"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(0), "0h 0m");
+        assert_eq!(format_age(3 * 3_600 + 25 * 60), "3h 25m");
+        assert_eq!(format_age(400 * 86_400 + 5 * 3_600), "400d 5h");
+        assert_eq!(format_age(-10), "0h 0m");
+    }
+
+    #[test]
+    fn test_check_transfer_limit() {
+        let limit = Some(SolAmount::try_from(2.0).unwrap());
+
+        assert!(check_transfer_limit(sol_to_lamports(2.0), limit, &Cluster::Mainnet).is_ok());
+        assert!(check_transfer_limit(sol_to_lamports(500.0), None, &Cluster::Mainnet).is_ok());
+
+        let err = check_transfer_limit(sol_to_lamports(2.5), limit, &Cluster::Mainnet)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("2 SOL limit for mainnet-beta"));
+    }
+
+    #[test]
+    fn test_seed_kinds_parse_to_bytes() {
+        let pubkey = Pubkey::new_unique();
+
+        assert_eq!(SeedKind::Text.parse("vault").unwrap(), b"vault");
+        assert_eq!(
+            SeedKind::Pubkey.parse(&pubkey.to_string()).unwrap(),
+            pubkey.to_bytes()
+        );
+        assert_eq!(SeedKind::Hex.parse("0x00ff10").unwrap(), vec![0, 255, 16]);
+        assert_eq!(
+            SeedKind::U64.parse("258").unwrap(),
+            vec![2, 1, 0, 0, 0, 0, 0, 0]
+        );
+
+        assert!(SeedKind::Hex.parse("abc").is_err());
+        assert!(SeedKind::Hex.parse("zz").is_err());
+        assert!(SeedKind::U64.parse("-1").is_err());
+        assert!(SeedKind::Text.parse(&"a".repeat(MAX_SEED_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_program_address_matches_ata_derivation() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::from_str(crate::constants::TOKEN_PROGRAM_ID).unwrap();
+        let ata_program = Pubkey::from_str(crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID).unwrap();
+
+        let seeds: Vec<Vec<u8>> = [wallet, token_program, mint]
+            .iter()
+            .map(|key| SeedKind::Pubkey.parse(&key.to_string()).unwrap())
+            .collect();
+        let seed_slices: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+
+        assert_eq!(
+            Pubkey::find_program_address(&seed_slices, &ata_program).0,
+            crate::misc::helpers::get_associated_token_address(&wallet, &mint, &token_program)
+        );
+    }
+
+    #[test]
+    fn test_short_signature() {
+        let signature = Signature::from([7u8; 64]).to_string();
+        let short = short_signature(&signature);
+        assert_eq!(short.chars().count(), 17);
+        assert!(short.starts_with(&signature[..8]));
+        assert!(short.ends_with(&signature[signature.len() - 8..]));
+        assert_eq!(short_signature("abc"), "abc");
+    }
+
+    #[test]
+    fn test_faucet_refusal_from_error() {
+        assert_eq!(
+            FaucetRefusal::from_error(
+                "airdrop request failed. This can happen when the rate limit is reached."
+            ),
+            FaucetRefusal::RateLimited
+        );
+        assert_eq!(
+            FaucetRefusal::from_error("HTTP status client error (429 Too Many Requests)"),
+            FaucetRefusal::RateLimited
+        );
+        assert_eq!(
+            FaucetRefusal::from_error("Faucet has run dry"),
+            FaucetRefusal::RunDry
+        );
+        assert_eq!(
+            FaucetRefusal::from_error("connection refused"),
+            FaucetRefusal::Other
+        );
+        assert!(!FaucetRefusal::RunDry.is_retryable());
+        assert!(FaucetRefusal::RateLimited.is_retryable());
+    }
+
+    #[test]
+    fn test_airdrop_retry_delay() {
+        assert_eq!(airdrop_retry_delay(1), AIRDROP_RETRY_BASE_DELAY_SECS);
+        assert_eq!(airdrop_retry_delay(3), AIRDROP_RETRY_BASE_DELAY_SECS * 4);
+        assert_eq!(airdrop_retry_delay(100), u64::MAX);
+    }
+}
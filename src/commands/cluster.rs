@@ -1,11 +1,39 @@
 use {
     crate::{
-        commands::CommandFlow, constants::LAMPORTS_PER_SOL, context::ScillaContext,
-        ui::show_spinner,
+        commands::CommandFlow,
+        config::{Cluster, OutputFormat},
+        constants::{
+            DEVNET_RPC, LAMPORTS_PER_SOL, LEADER_SCHEDULE_WINDOW, MAINNET_RPC,
+            RPC_BENCHMARK_DEFAULT_ROUNDS, RPC_SLOTS_BEHIND_WARN, SKIP_RATE_CRITICAL_PERCENT,
+            SKIP_RATE_WARN_PERCENT, STAKE_DISTRIBUTION_TOP_N, TESTNET_RPC,
+        },
+        context::ScillaContext,
+        misc::helpers::{bincode_deserialize, explain_rpc_error},
+        prompt::{
+            prompt_input_data, prompt_input_data_with_default, prompt_optional_data,
+            prompt_select_data,
+        },
+        ui::{print_error, print_list_table, print_record_table, show_spinner, theme},
     },
+    anyhow::bail,
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
-    std::{fmt, ops::Div},
+    solana_account::Account,
+    solana_clock::Clock,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        client_error::ErrorKind,
+        config::RpcBlockProductionConfig,
+        request::{RpcError, RpcResponseErrorData},
+    },
+    solana_sdk_ids::feature,
+    solana_sysvar::{
+        clock,
+        rent::{self, Rent},
+    },
+    std::{fmt, ops::Div, time::Instant},
+    tokio::try_join,
 };
 
 /// Commands related to cluster operations
@@ -16,9 +44,18 @@ pub enum ClusterCommand {
     BlockHeight,
     BlockTime,
     Validators,
+    StakeDistribution,
+    LeaderSchedule,
+    BlockProduction,
+    RpcHealth,
+    RpcBenchmark,
+    PriorityFees,
     SupplyInfo,
     Inflation,
     ClusterVersion,
+    ClusterIdentity,
+    FeatureGates,
+    Sysvars,
     GoBack,
 }
 
@@ -30,12 +67,48 @@ impl ClusterCommand {
             ClusterCommand::BlockHeight => "Fetching current block height…",
             ClusterCommand::BlockTime => "Fetching block timestamp…",
             ClusterCommand::Validators => "Fetching active validators…",
+            ClusterCommand::StakeDistribution => "Computing stake distribution…",
+            ClusterCommand::LeaderSchedule => "Fetching upcoming slot leaders…",
+            ClusterCommand::BlockProduction => "Fetching block production for this epoch…",
+            ClusterCommand::RpcHealth => "Checking how far behind the RPC node is…",
+            ClusterCommand::RpcBenchmark => "Timing common RPC calls…",
+            ClusterCommand::PriorityFees => "Fetching recent prioritization fees…",
             ClusterCommand::ClusterVersion => "Fetching cluster Solana version…",
+            ClusterCommand::ClusterIdentity => "Fetching genesis hash and node identity…",
+            ClusterCommand::FeatureGates => "Fetching feature gate accounts…",
+            ClusterCommand::Sysvars => "Fetching Clock and Rent sysvars…",
             ClusterCommand::SupplyInfo => "Fetching total and circulating supply…",
             ClusterCommand::Inflation => "Fetching inflation parameters…",
             ClusterCommand::GoBack => "Going back…",
         }
     }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            ClusterCommand::EpochInfo => "Current epoch, slot index and epoch progress",
+            ClusterCommand::CurrentSlot => "Latest slot at the configured commitment",
+            ClusterCommand::BlockHeight => "Current block height of the cluster",
+            ClusterCommand::BlockTime => "Timestamp of the latest slot",
+            ClusterCommand::Validators => "Current and delinquent validators by stake",
+            ClusterCommand::StakeDistribution => {
+                "Validators needed to reach 33% and 66% of the stake"
+            }
+            ClusterCommand::LeaderSchedule => "Leaders of the next few slots from the current one",
+            ClusterCommand::BlockProduction => "Leader slots, blocks produced and skip rate",
+            ClusterCommand::RpcHealth => "Compare the RPC's slot with the public endpoint",
+            ClusterCommand::RpcBenchmark => "Latency of common read calls against this RPC",
+            ClusterCommand::PriorityFees => "Recent priority fee percentiles for given accounts",
+            ClusterCommand::ClusterVersion => "Solana version and feature set of the RPC node",
+            ClusterCommand::ClusterIdentity => "Genesis hash, node identity and first block",
+            ClusterCommand::FeatureGates => {
+                "Which feature gates are active or pending, and since when"
+            }
+            ClusterCommand::Sysvars => "Clock and Rent sysvars as programs see them",
+            ClusterCommand::SupplyInfo => "Total, circulating and non-circulating SOL",
+            ClusterCommand::Inflation => "Current total, validator and foundation inflation",
+            ClusterCommand::GoBack => "Return to the command groups",
+        }
+    }
 }
 
 impl fmt::Display for ClusterCommand {
@@ -46,7 +119,16 @@ impl fmt::Display for ClusterCommand {
             ClusterCommand::BlockHeight => "Block Height",
             ClusterCommand::BlockTime => "Block Time",
             ClusterCommand::Validators => "Validators",
+            ClusterCommand::StakeDistribution => "Stake Distribution",
+            ClusterCommand::LeaderSchedule => "Leader Schedule",
+            ClusterCommand::BlockProduction => "Block Production",
+            ClusterCommand::RpcHealth => "RPC Health",
+            ClusterCommand::RpcBenchmark => "RPC Benchmark",
+            ClusterCommand::PriorityFees => "Priority Fees",
             ClusterCommand::ClusterVersion => "Cluster Version",
+            ClusterCommand::ClusterIdentity => "Cluster Identity",
+            ClusterCommand::FeatureGates => "Feature Gates",
+            ClusterCommand::Sysvars => "Sysvars",
             ClusterCommand::SupplyInfo => "Supply Info",
             ClusterCommand::Inflation => "Inflation",
             ClusterCommand::GoBack => "Go back",
@@ -59,28 +141,83 @@ impl ClusterCommand {
     pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
         match self {
             ClusterCommand::EpochInfo => {
-                show_spinner(self.spinner_msg(), fetch_epoch_info(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_epoch_info(ctx)).await;
             }
             ClusterCommand::CurrentSlot => {
-                show_spinner(self.spinner_msg(), fetch_current_slot(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_current_slot(ctx)).await;
             }
             ClusterCommand::BlockHeight => {
-                show_spinner(self.spinner_msg(), fetch_block_height(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_block_height(ctx)).await;
             }
             ClusterCommand::BlockTime => {
-                show_spinner(self.spinner_msg(), fetch_block_time(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_block_time(ctx)).await;
             }
             ClusterCommand::Validators => {
-                show_spinner(self.spinner_msg(), fetch_validators(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_validators(ctx)).await;
+            }
+            ClusterCommand::StakeDistribution => {
+                let _ = show_spinner(self.spinner_msg(), fetch_stake_distribution(ctx)).await;
+            }
+            ClusterCommand::LeaderSchedule => {
+                let _ = show_spinner(self.spinner_msg(), fetch_leader_schedule(ctx)).await;
+            }
+            ClusterCommand::BlockProduction => {
+                let identity: Pubkey =
+                    prompt_optional_data("Enter Identity Pubkey (leave empty for your wallet):")
+                        .unwrap_or(*ctx.pubkey());
+                let _ =
+                    show_spinner(self.spinner_msg(), fetch_block_production(ctx, &identity)).await;
+            }
+            ClusterCommand::RpcHealth => {
+                let _ = show_spinner(self.spinner_msg(), process_rpc_health(ctx)).await;
+            }
+            ClusterCommand::RpcBenchmark => {
+                let rounds: u32 = prompt_input_data_with_default(
+                    "Enter number of rounds:",
+                    Some(RPC_BENCHMARK_DEFAULT_ROUNDS),
+                );
+                let _ = show_spinner(self.spinner_msg(), process_rpc_benchmark(ctx, rounds)).await;
+            }
+            ClusterCommand::PriorityFees => {
+                let accounts: Vec<Pubkey> = loop {
+                    let input: String = prompt_optional_data(
+                        "Enter accounts, comma separated (leave empty for all transactions):",
+                    )
+                    .unwrap_or_default();
+
+                    match parse_pubkey_list(&input) {
+                        Ok(accounts) => break accounts,
+                        Err(e) => print_error(e),
+                    }
+                };
+                let _ = show_spinner(self.spinner_msg(), fetch_priority_fees(ctx, &accounts)).await;
             }
             ClusterCommand::SupplyInfo => {
-                show_spinner(self.spinner_msg(), fetch_supply_info(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_supply_info(ctx)).await;
             }
             ClusterCommand::Inflation => {
-                show_spinner(self.spinner_msg(), fetch_inflation_info(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_inflation_info(ctx)).await;
             }
             ClusterCommand::ClusterVersion => {
-                show_spinner(self.spinner_msg(), fetch_cluster_version(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), fetch_cluster_version(ctx)).await;
+            }
+            ClusterCommand::ClusterIdentity => {
+                let _ = show_spinner(self.spinner_msg(), fetch_cluster_identity(ctx)).await;
+            }
+            ClusterCommand::FeatureGates => {
+                let filter = prompt_select_data("Show features:", FeatureFilter::all());
+                let feature = match filter {
+                    FeatureFilter::Lookup => Some(prompt_input_data("Enter Feature Pubkey:")),
+                    _ => None,
+                };
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    fetch_feature_gates(ctx, filter, feature),
+                )
+                .await;
+            }
+            ClusterCommand::Sysvars => {
+                let _ = show_spinner(self.spinner_msg(), fetch_sysvars(ctx)).await;
             }
             ClusterCommand::GoBack => {
                 return CommandFlow::GoBack;
@@ -91,6 +228,48 @@ impl ClusterCommand {
     }
 }
 
+/// Cluster commands that run once from the command line and exit
+#[derive(Debug, Clone, Subcommand)]
+pub enum ClusterArgs {
+    /// Current epoch and how far into it the cluster is
+    EpochInfo,
+    /// Current slot
+    Slot,
+    /// Current block height
+    BlockHeight,
+    /// Total, circulating and non-circulating supply
+    Supply,
+    /// Solana version of the RPC node
+    Version,
+    /// How far the RPC node is behind the cluster
+    Health,
+    /// Current inflation rates
+    Inflation,
+    /// Leaders of the upcoming slots
+    LeaderSchedule,
+    /// Leader slots and skip rate of a validator this epoch, your wallet by
+    /// default
+    BlockProduction { identity: Option<Pubkey> },
+}
+
+impl ClusterArgs {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            ClusterArgs::EpochInfo => fetch_epoch_info(ctx).await,
+            ClusterArgs::Slot => fetch_current_slot(ctx).await,
+            ClusterArgs::BlockHeight => fetch_block_height(ctx).await,
+            ClusterArgs::Supply => fetch_supply_info(ctx).await,
+            ClusterArgs::Version => fetch_cluster_version(ctx).await,
+            ClusterArgs::Health => process_rpc_health(ctx).await,
+            ClusterArgs::Inflation => fetch_inflation_info(ctx).await,
+            ClusterArgs::LeaderSchedule => fetch_leader_schedule(ctx).await,
+            ClusterArgs::BlockProduction { identity } => {
+                fetch_block_production(ctx, &identity.unwrap_or(*ctx.pubkey())).await
+            }
+        }
+    }
+}
+
 async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     let epoch_info = ctx.rpc().get_epoch_info().await?;
 
@@ -104,12 +283,8 @@ async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Epoch"),
@@ -140,8 +315,405 @@ async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
             Cell::new(format!("{}", epoch_info.transaction_count.unwrap_or(0))),
         ]);
 
-    println!("\n{}", style("EPOCH INFORMATION").green().bold());
+    print_record_table(ctx, "EPOCH INFORMATION", &table);
+
+    Ok(())
+}
+
+async fn fetch_leader_schedule(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let current_slot = ctx.rpc().get_slot().await?;
+    let leaders = ctx
+        .rpc()
+        .get_slot_leaders(current_slot, LEADER_SCHEDULE_WINDOW)
+        .await?;
+
+    // leaders hold consecutive slots, so collapse them into ranges
+    let mut ranges: Vec<(u64, u64, _)> = Vec::new();
+    for (slot, leader) in (current_slot..).zip(leaders) {
+        match ranges.last_mut() {
+            Some((_, end, last_leader)) if *last_leader == leader => *end = slot,
+            _ => ranges.push((slot, slot, leader)),
+        }
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Slots"),
+        theme::bold_cell("Leader Identity"),
+    ]);
+
+    for (start, end, leader) in ranges {
+        let slots = if start == end {
+            start.to_string()
+        } else {
+            format!("{start}-{end}")
+        };
+
+        // highlight blocks the active wallet is scheduled to produce
+        let leader_cell = if &leader == ctx.pubkey() {
+            theme::success_cell(format!("{leader} (you)"))
+        } else {
+            Cell::new(leader)
+        };
+
+        table.add_row(vec![Cell::new(slots), leader_cell]);
+    }
+
+    print_list_table(
+        ctx,
+        &format!("LEADER SCHEDULE (NEXT {LEADER_SCHEDULE_WINDOW} SLOTS)"),
+        &table,
+    );
+
+    Ok(())
+}
+
+async fn fetch_block_production(ctx: &ScillaContext, identity: &Pubkey) -> anyhow::Result<()> {
+    let production = ctx
+        .rpc()
+        .get_block_production_with_config(RpcBlockProductionConfig {
+            identity: Some(identity.to_string()),
+            range: None, // defaults to the current epoch
+            commitment: Some(ctx.rpc().commitment()),
+        })
+        .await
+        .map_err(|e| explain_rpc_error(ctx, "getBlockProduction", e))?
+        .value;
+
+    let Some(&(leader_slots, blocks_produced)) = production.by_identity.get(&identity.to_string())
+    else {
+        bail!("{identity} has no leader slots in the current epoch");
+    };
+
+    let skipped = leader_slots.saturating_sub(blocks_produced);
+    let skip_rate = if leader_slots > 0 {
+        skipped as f64 / leader_slots as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    let skip_rate_cell = {
+        let text = format!("{skip_rate:.2}%");
+        if skip_rate < SKIP_RATE_WARN_PERCENT {
+            theme::success_cell(text)
+        } else if skip_rate < SKIP_RATE_CRITICAL_PERCENT {
+            theme::warning_cell(text)
+        } else {
+            theme::error_cell(text)
+        }
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Identity"), Cell::new(identity)])
+        .add_row(vec![
+            Cell::new("Slot Range"),
+            Cell::new(format!(
+                "{}-{}",
+                production.range.first_slot, production.range.last_slot
+            )),
+        ])
+        .add_row(vec![Cell::new("Leader Slots"), Cell::new(leader_slots)])
+        .add_row(vec![
+            Cell::new("Blocks Produced"),
+            Cell::new(blocks_produced),
+        ])
+        .add_row(vec![Cell::new("Skipped Slots"), Cell::new(skipped)])
+        .add_row(vec![Cell::new("Skip Rate"), skip_rate_cell]);
+
+    print_record_table(ctx, "BLOCK PRODUCTION", &table);
+
+    Ok(())
+}
+
+/// How far the configured RPC lags behind, from its own health report and
+/// from comparing slots with the cluster's public endpoint
+pub struct RpcLag {
+    pub slot: u64,
+    pub reference_slot: Option<u64>,
+    pub slots_behind: Option<u64>,
+}
+
+pub async fn measure_rpc_lag(ctx: &ScillaContext) -> anyhow::Result<RpcLag> {
+    let slot = ctx.rpc().get_slot().await?;
+
+    // an unhealthy node reports its own lag through the getHealth error
+    let reported_behind = match ctx.rpc().get_health().await {
+        Ok(()) => None,
+        Err(err) => match err.kind() {
+            ErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::NodeUnhealthy { num_slots_behind },
+                ..
+            }) => *num_slots_behind,
+            _ => return Err(err.into()),
+        },
+    };
+
+    let reference_url = match ctx.cluster() {
+        Cluster::Mainnet => Some(MAINNET_RPC),
+        Cluster::Devnet => Some(DEVNET_RPC),
+        Cluster::Testnet => Some(TESTNET_RPC),
+        Cluster::Localnet | Cluster::Custom(_) => None,
+    }
+    .filter(|url| *url != ctx.rpc().url());
+
+    let reference_slot = match reference_url {
+        Some(url) => RpcClient::new_with_commitment(url.to_string(), ctx.rpc().commitment())
+            .get_slot()
+            .await
+            .ok(),
+        None => None,
+    };
+
+    let compared_behind = reference_slot.map(|reference| reference.saturating_sub(slot));
+
+    Ok(RpcLag {
+        slot,
+        reference_slot,
+        slots_behind: reported_behind.max(compared_behind),
+    })
+}
+
+async fn process_rpc_health(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let lag = measure_rpc_lag(ctx).await?;
+
+    let behind_cell = match lag.slots_behind {
+        Some(behind) if behind >= RPC_SLOTS_BEHIND_WARN => theme::error_cell(behind),
+        Some(behind) => theme::success_cell(behind),
+        None => Cell::new("Unknown (no reference endpoint)"),
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Endpoint"), Cell::new(ctx.rpc().url())])
+        .add_row(vec![Cell::new("Slot"), Cell::new(lag.slot)])
+        .add_row(vec![
+            Cell::new("Reference Slot"),
+            Cell::new(
+                lag.reference_slot
+                    .map(|slot| slot.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ])
+        .add_row(vec![Cell::new("Slots Behind"), behind_cell]);
+
+    print_record_table(ctx, "RPC HEALTH", &table);
+
+    if let Some(slots_behind) = lag.slots_behind
+        && slots_behind >= RPC_SLOTS_BEHIND_WARN
+        && ctx.output_format() == OutputFormat::Table
+    {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "⚠ RPC {} is {slots_behind} slots behind the cluster; balances and stake may be \
+                 stale. Consider switching endpoints.",
+                ctx.rpc().url()
+            ))
+            .bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// Successful call latencies and failures of one RPC method
+#[derive(Debug, Default)]
+struct MethodBenchmark {
+    latencies_us: Vec<u64>,
+    errors: usize,
+    first_error: Option<String>,
+}
+
+impl MethodBenchmark {
+    fn record<T, E: fmt::Display>(&mut self, started: Instant, result: Result<T, E>) {
+        match result {
+            Ok(_) => self.latencies_us.push(started.elapsed().as_micros() as u64),
+            Err(e) => {
+                self.errors += 1;
+                self.first_error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+}
+
+fn format_latency(micros: u64) -> String {
+    format!("{:.1} ms", micros as f64 / 1000.0)
+}
+
+/// Calls a few common read methods `rounds` times each, one after another so
+/// they don't queue behind each other, and reports their latency spread
+async fn process_rpc_benchmark(ctx: &ScillaContext, rounds: u32) -> anyhow::Result<()> {
+    if rounds == 0 {
+        bail!("Run at least one round");
+    }
+
+    let mut benchmarks = [
+        ("getSlot", MethodBenchmark::default()),
+        ("getLatestBlockhash", MethodBenchmark::default()),
+        ("getAccountInfo", MethodBenchmark::default()),
+    ];
+
+    for _ in 0..rounds {
+        let started = Instant::now();
+        let result = ctx.rpc().get_slot().await;
+        benchmarks[0].1.record(started, result);
+
+        let started = Instant::now();
+        let result = ctx.rpc().get_latest_blockhash().await;
+        benchmarks[1].1.record(started, result);
+
+        let started = Instant::now();
+        let result = ctx
+            .rpc()
+            .get_account_with_commitment(ctx.pubkey(), ctx.read_commitment())
+            .await;
+        benchmarks[2].1.record(started, result);
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::header_cell("Method"),
+        theme::header_cell("Calls"),
+        theme::header_cell("Errors"),
+        theme::header_cell("Min"),
+        theme::header_cell("Median"),
+        theme::header_cell("p95"),
+        theme::header_cell("Max"),
+    ]);
+
+    for (method, benchmark) in &mut benchmarks {
+        benchmark.latencies_us.sort_unstable();
+        let latencies = &benchmark.latencies_us;
+
+        let errors = if benchmark.errors > 0 {
+            theme::error_cell(benchmark.errors)
+        } else {
+            Cell::new(0)
+        };
+        let latency_cells = match (latencies.first(), latencies.last()) {
+            (Some(min), Some(max)) => vec![
+                Cell::new(format_latency(*min)),
+                Cell::new(format_latency(percentile(latencies, 50.0))),
+                Cell::new(format_latency(percentile(latencies, 95.0))),
+                Cell::new(format_latency(*max)),
+            ],
+            _ => vec![Cell::new("-"); 4],
+        };
+
+        table.add_row(
+            [Cell::new(*method), Cell::new(rounds), errors]
+                .into_iter()
+                .chain(latency_cells),
+        );
+    }
+
+    print_list_table(ctx, "RPC BENCHMARK", &table);
+
+    if ctx.output_format() == OutputFormat::Table {
+        println!(
+            "{}",
+            theme::muted(format!("{rounds} round(s) against {}", ctx.rpc().url()))
+        );
+        for (method, benchmark) in &benchmarks {
+            if let Some(error) = &benchmark.first_error {
+                println!("{}", theme::warning(format!("{method}: {error}")));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_pubkey_list(input: &str) -> anyhow::Result<Vec<Pubkey>> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse()
+                .map_err(|_| anyhow::anyhow!("Invalid pubkey: {part}"))
+        })
+        .collect()
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile(sorted: &[u64], percent: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((percent / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+async fn fetch_priority_fees(ctx: &ScillaContext, accounts: &[Pubkey]) -> anyhow::Result<()> {
+    let fees = ctx
+        .rpc()
+        .get_recent_prioritization_fees(accounts)
+        .await
+        .map_err(|e| explain_rpc_error(ctx, "getRecentPrioritizationFees", e))?;
+
+    if fees.is_empty() {
+        bail!("The RPC returned no recent prioritization fees");
+    }
+
+    let mut samples: Vec<u64> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+    samples.sort_unstable();
+    let paying = samples.iter().filter(|fee| **fee > 0).count();
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Percentile"),
+            theme::header_cell("Micro-lamports / CU"),
+        ])
+        .add_row(vec![Cell::new("Min"), Cell::new(samples[0])])
+        .add_row(vec![
+            Cell::new("25th"),
+            Cell::new(percentile(&samples, 25.0)),
+        ])
+        .add_row(vec![
+            Cell::new("Median"),
+            Cell::new(percentile(&samples, 50.0)),
+        ])
+        .add_row(vec![
+            Cell::new("75th"),
+            Cell::new(percentile(&samples, 75.0)),
+        ])
+        .add_row(vec![
+            Cell::new("90th"),
+            Cell::new(percentile(&samples, 90.0)),
+        ])
+        .add_row(vec![
+            Cell::new("Max"),
+            Cell::new(samples[samples.len() - 1]),
+        ]);
+
+    let scope = if accounts.is_empty() {
+        "all transactions".to_string()
+    } else {
+        format!("{} account(s)", accounts.len())
+    };
+
+    println!("\n{}", theme::success("RECENT PRIORITIZATION FEES").bold());
     println!("{table}");
+    println!(
+        "{}",
+        theme::muted(format!(
+            "{} recent slots for {scope}, {paying} with a non-zero fee",
+            samples.len()
+        ))
+    );
 
     Ok(())
 }
@@ -153,20 +725,15 @@ async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Current Slot"),
             Cell::new(format!("{slot}")),
         ]);
 
-    println!("\n{}", style("CURRENT SLOT").green().bold());
-    println!("{table}");
+    print_record_table(ctx, "CURRENT SLOT", &table);
 
     Ok(())
 }
@@ -178,27 +745,26 @@ async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Block Height"),
             Cell::new(format!("{block_height}")),
         ]);
 
-    println!("\n{}", style("BLOCK HEIGHT").green().bold());
-    println!("{table}");
+    print_record_table(ctx, "BLOCK HEIGHT", &table);
 
     Ok(())
 }
 
 async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<()> {
     let slot = ctx.rpc().get_slot().await?;
-    let block_time = ctx.rpc().get_block_time(slot).await?;
+    let block_time = ctx
+        .rpc()
+        .get_block_time(slot)
+        .await
+        .map_err(|e| explain_rpc_error(ctx, "getBlockTime", e))?;
 
     let datetime = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
         .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
@@ -208,12 +774,8 @@ async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![Cell::new("Slot"), Cell::new(format!("{slot}"))])
         .add_row(vec![
@@ -222,7 +784,7 @@ async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<()> {
         ])
         .add_row(vec![Cell::new("Date/Time"), Cell::new(datetime)]);
 
-    println!("\n{}", style("BLOCK TIME").green().bold());
+    println!("\n{}", theme::success("BLOCK TIME").bold());
     println!("{table}");
 
     Ok(())
@@ -236,12 +798,8 @@ async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
     summary_table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Current Validators"),
@@ -252,8 +810,10 @@ async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
             Cell::new(format!("{}", validators.delinquent.len())),
         ]);
 
-    println!("\n{}", style("VALIDATORS SUMMARY").green().bold());
-    println!("{summary_table}");
+    if ctx.output_format() == OutputFormat::Table {
+        println!("\n{}", theme::success("VALIDATORS SUMMARY").bold());
+        println!("{summary_table}");
+    }
 
     // Validators detail table
     if !validators.current.is_empty() {
@@ -262,10 +822,10 @@ async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
 
         let mut validators_table = Table::new();
         validators_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Node Pubkey").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Vote Account").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Activated Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            theme::bold_cell("#"),
+            theme::bold_cell("Node Pubkey"),
+            theme::bold_cell("Vote Account"),
+            theme::bold_cell("Activated Stake (SOL)"),
         ]);
 
         for (idx, validator) in validators.iter().take(10).enumerate() {
@@ -279,13 +839,126 @@ async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
             ]);
         }
 
-        println!("\n{}", style("TOP 10 VALIDATORS BY STAKE").green().bold());
-        println!("{validators_table}");
+        print_list_table(ctx, "TOP 10 VALIDATORS BY STAKE", &validators_table);
     }
 
     Ok(())
 }
 
+/// Smallest number of validators, taken from the largest down, whose combined
+/// stake exceeds `fraction` of `total`. `stakes` must be sorted descending.
+fn validators_to_exceed(stakes: &[u64], total: u64, fraction: f64) -> usize {
+    let threshold = total as f64 * fraction;
+    let mut cumulative: u64 = 0;
+
+    for (idx, stake) in stakes.iter().enumerate() {
+        cumulative += stake;
+        if cumulative as f64 > threshold {
+            return idx + 1;
+        }
+    }
+
+    stakes.len()
+}
+
+async fn fetch_stake_distribution(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let vote_accounts = ctx.rpc().get_vote_accounts().await?;
+
+    // delinquent validators still hold stake, so they count toward the total
+    let mut validators: Vec<_> = vote_accounts
+        .current
+        .into_iter()
+        .chain(vote_accounts.delinquent)
+        .filter(|validator| validator.activated_stake > 0)
+        .collect();
+    validators.sort_by(|a, b| b.activated_stake.cmp(&a.activated_stake));
+
+    let stakes: Vec<u64> = validators.iter().map(|v| v.activated_stake).collect();
+    let total: u64 = stakes.iter().sum();
+    if total == 0 {
+        bail!("No activated stake found on this cluster");
+    }
+
+    let superminority = validators_to_exceed(&stakes, total, 1.0 / 3.0);
+    let supermajority = validators_to_exceed(&stakes, total, 2.0 / 3.0);
+
+    let mut summary_table = Table::new();
+    summary_table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Staked Validators"),
+            Cell::new(validators.len()),
+        ])
+        .add_row(vec![
+            Cell::new("Total Active Stake (SOL)"),
+            Cell::new(format!("{:.2}", total as f64 / LAMPORTS_PER_SOL as f64)),
+        ])
+        .add_row(vec![
+            Cell::new("Superminority (>33%)"),
+            Cell::new(format!("{superminority} validators")),
+        ])
+        .add_row(vec![
+            Cell::new("Supermajority (>66%)"),
+            Cell::new(format!("{supermajority} validators")),
+        ]);
+
+    println!("\n{}", theme::success("STAKE DISTRIBUTION").bold());
+    println!("{summary_table}");
+
+    let mut top_table = Table::new();
+    top_table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("#"),
+        theme::bold_cell("Vote Account"),
+        theme::bold_cell("Activated Stake (SOL)"),
+        theme::bold_cell("Share"),
+        theme::bold_cell("Cumulative"),
+    ]);
+
+    let mut cumulative: u64 = 0;
+    for (idx, validator) in validators.iter().take(STAKE_DISTRIBUTION_TOP_N).enumerate() {
+        cumulative += validator.activated_stake;
+        let share = validator.activated_stake as f64 / total as f64 * 100.0;
+        let cumulative_share = cumulative as f64 / total as f64 * 100.0;
+
+        // mark the validators that together form the superminority
+        let rank = if idx < superminority {
+            theme::error(idx + 1).to_string()
+        } else {
+            (idx + 1).to_string()
+        };
+
+        top_table.add_row(vec![
+            Cell::new(rank),
+            Cell::new(&validator.vote_pubkey),
+            Cell::new(format!(
+                "{:.2}",
+                validator.activated_stake as f64 / LAMPORTS_PER_SOL as f64
+            )),
+            Cell::new(format!("{share:.2}%")),
+            Cell::new(format!("{cumulative_share:.2}%")),
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        theme::success(format!(
+            "TOP {STAKE_DISTRIBUTION_TOP_N} VALIDATORS BY STAKE"
+        ))
+        .bold()
+    );
+    println!("{top_table}");
+    println!(
+        "{}",
+        theme::muted("Red ranks belong to the superminority, enough stake to halt the cluster.")
+    );
+
+    Ok(())
+}
+
 async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     let supply = ctx.rpc().supply().await?;
 
@@ -298,11 +971,9 @@ async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value (SOL)").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Percentage").add_attribute(comfy_table::Attribute::Bold),
+            theme::header_cell("Field"),
+            theme::bold_cell("Value (SOL)"),
+            theme::bold_cell("Percentage"),
         ])
         .add_row(vec![
             Cell::new("Total Supply"),
@@ -320,8 +991,7 @@ async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
             Cell::new(format!("{:.2}%", 100.0 - circulating_pct)),
         ]);
 
-    println!("\n{}", style("SUPPLY INFORMATION").green().bold());
-    println!("{table}");
+    print_record_table(ctx, "SUPPLY INFORMATION", &table);
 
     Ok(())
 }
@@ -332,12 +1002,8 @@ async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Epoch"),
@@ -356,8 +1022,7 @@ async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<()> {
             Cell::new(format!("{:.4}%", inflation.foundation * 100.0)),
         ]);
 
-    println!("\n{}", style("INFLATION INFORMATION").green().bold());
-    println!("{table}");
+    print_record_table(ctx, "INFLATION INFORMATION", &table);
 
     Ok(())
 }
@@ -369,12 +1034,8 @@ async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Solana Core"),
@@ -388,8 +1049,371 @@ async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
         ]);
     }
 
-    println!("\n{}", style("CLUSTER VERSION").green().bold());
+    print_record_table(ctx, "CLUSTER VERSION", &table);
+
+    Ok(())
+}
+
+async fn fetch_cluster_identity(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let (genesis_hash, identity, first_block) = try_join!(
+        ctx.rpc().get_genesis_hash(),
+        ctx.rpc().get_identity(),
+        ctx.rpc().get_first_available_block(),
+    )?;
+
+    let genesis_hash = genesis_hash.to_string();
+    let cluster_cell = match Cluster::from_genesis_hash(&genesis_hash) {
+        Some(cluster) => theme::success_cell(cluster),
+        None => theme::warning_cell("Unknown (localnet or fork)"),
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Genesis Hash"), Cell::new(&genesis_hash)])
+        .add_row(vec![Cell::new("Cluster"), cluster_cell])
+        .add_row(vec![Cell::new("Node Identity"), Cell::new(identity)])
+        .add_row(vec![
+            Cell::new("First Available Block"),
+            Cell::new(first_block),
+        ]);
+
+    println!("\n{}", theme::success("CLUSTER IDENTITY").bold());
+    println!("{table}");
+
+    if let Some(cluster) = Cluster::from_genesis_hash(&genesis_hash)
+        && !matches!(ctx.cluster(), Cluster::Custom(_))
+        && cluster != *ctx.cluster()
+    {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "RPC URL looks like {} but the genesis hash belongs to {cluster}",
+                ctx.cluster()
+            ))
+        );
+    }
+
+    Ok(())
+}
+
+async fn fetch_sysvars(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let accounts = ctx
+        .rpc()
+        .get_multiple_accounts(&[clock::id(), rent::id()])
+        .await?;
+    let [Some(clock_account), Some(rent_account)] = accounts.as_slice() else {
+        bail!("Clock or Rent sysvar account not found");
+    };
+
+    let clock: Clock = bincode_deserialize(&clock_account.data, "clock sysvar")?;
+    let rent: Rent = bincode_deserialize(&rent_account.data, "rent sysvar")?;
+
+    let format_timestamp = |timestamp: i64| {
+        chrono::DateTime::<chrono::Utc>::from_timestamp_secs(timestamp)
+            .map(|dt| format!("{timestamp} ({})", dt.format("%Y-%m-%d %H:%M:%S UTC")))
+            .unwrap_or_else(|| timestamp.to_string())
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![theme::bold_cell("Clock"), Cell::new(clock::id())])
+        .add_row(vec![Cell::new("Slot"), Cell::new(clock.slot)])
+        .add_row(vec![Cell::new("Epoch"), Cell::new(clock.epoch)])
+        .add_row(vec![
+            Cell::new("Leader Schedule Epoch"),
+            Cell::new(clock.leader_schedule_epoch),
+        ])
+        .add_row(vec![
+            Cell::new("Unix Timestamp"),
+            Cell::new(format_timestamp(clock.unix_timestamp)),
+        ])
+        .add_row(vec![
+            Cell::new("Epoch Start Timestamp"),
+            Cell::new(format_timestamp(clock.epoch_start_timestamp)),
+        ])
+        .add_row(vec![theme::bold_cell("Rent"), Cell::new(rent::id())])
+        .add_row(vec![
+            Cell::new("Lamports per Byte-Year"),
+            Cell::new(rent.lamports_per_byte_year),
+        ])
+        .add_row(vec![
+            Cell::new("Exemption Threshold"),
+            Cell::new(format!("{} years", rent.exemption_threshold)),
+        ])
+        .add_row(vec![
+            Cell::new("Burn Percent"),
+            Cell::new(format!("{}%", rent.burn_percent)),
+        ])
+        .add_row(vec![
+            Cell::new("Rent-Exempt Minimum (0 bytes)"),
+            Cell::new(format!("{} lamports", rent.minimum_balance(0))),
+        ]);
+
+    println!("\n{}", theme::success("SYSVARS").bold());
     println!("{table}");
 
     Ok(())
 }
+
+/// Which feature gates to list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FeatureFilter {
+    All,
+    Pending,
+    Activated,
+    Lookup,
+}
+
+impl FeatureFilter {
+    fn all() -> Vec<Self> {
+        vec![
+            FeatureFilter::All,
+            FeatureFilter::Pending,
+            FeatureFilter::Activated,
+            FeatureFilter::Lookup,
+        ]
+    }
+
+    fn matches(&self, gate: &FeatureGate) -> bool {
+        match self {
+            FeatureFilter::All | FeatureFilter::Lookup => true,
+            FeatureFilter::Pending => gate.activated_at.is_none(),
+            FeatureFilter::Activated => gate.activated_at.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for FeatureFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureFilter::All => write!(f, "All features"),
+            FeatureFilter::Pending => write!(f, "Pending only"),
+            FeatureFilter::Activated => write!(f, "Activated only"),
+            FeatureFilter::Lookup => write!(f, "Look up one feature"),
+        }
+    }
+}
+
+/// A feature account; `activated_at` stays `None` until the runtime activates
+/// it at an epoch boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FeatureGate {
+    pubkey: Pubkey,
+    activated_at: Option<u64>,
+}
+
+impl FeatureGate {
+    fn from_account(pubkey: Pubkey, account: &Account) -> anyhow::Result<Self> {
+        if account.owner != feature::id() {
+            bail!("{pubkey} is not a feature account");
+        }
+        Ok(Self {
+            pubkey,
+            activated_at: bincode_deserialize(&account.data, "feature account data")?,
+        })
+    }
+}
+
+/// Pending features first, then the most recently activated
+fn sort_feature_gates(gates: &mut [FeatureGate]) {
+    gates.sort_by(|a, b| match (a.activated_at, b.activated_at) {
+        (None, None) => a.pubkey.cmp(&b.pubkey),
+        (None, Some(_)) => std::cmp::Ordering::Less,
+        (Some(_), None) => std::cmp::Ordering::Greater,
+        (Some(a_slot), Some(b_slot)) => b_slot.cmp(&a_slot),
+    });
+}
+
+async fn fetch_feature_gates(
+    ctx: &ScillaContext,
+    filter: FeatureFilter,
+    feature_pubkey: Option<Pubkey>,
+) -> anyhow::Result<()> {
+    let (mut gates, epoch_schedule) = match feature_pubkey {
+        Some(pubkey) => {
+            let account = ctx.rpc().get_account(&pubkey).await.map_err(|_| {
+                anyhow::anyhow!(
+                    "Feature {pubkey} has no account on this cluster, so it has not been proposed \
+                     for activation here"
+                )
+            })?;
+            (
+                vec![FeatureGate::from_account(pubkey, &account)?],
+                ctx.rpc().get_epoch_schedule().await?,
+            )
+        }
+        None => {
+            let (accounts, epoch_schedule) = try_join!(
+                ctx.rpc().get_program_accounts(&feature::id()),
+                ctx.rpc().get_epoch_schedule()
+            )?;
+            let gates = accounts
+                .iter()
+                .filter_map(|(pubkey, account)| FeatureGate::from_account(*pubkey, account).ok())
+                .filter(|gate| filter.matches(gate))
+                .collect();
+            (gates, epoch_schedule)
+        }
+    };
+
+    if gates.is_empty() {
+        println!("{}", theme::warning("No matching feature gates found."));
+        return Ok(());
+    }
+
+    sort_feature_gates(&mut gates);
+    let pending = gates
+        .iter()
+        .filter(|gate| gate.activated_at.is_none())
+        .count();
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::header_cell("Feature"),
+        theme::header_cell("Status"),
+        theme::header_cell("Activation Slot"),
+        theme::header_cell("Activation Epoch"),
+    ]);
+
+    for gate in &gates {
+        let (status, slot, epoch) = match gate.activated_at {
+            Some(slot) => (
+                theme::success_cell("Activated"),
+                slot.to_string(),
+                epoch_schedule.get_epoch(slot).to_string(),
+            ),
+            None => (
+                theme::warning_cell("Pending"),
+                "-".to_string(),
+                "-".to_string(),
+            ),
+        };
+        table.add_row(vec![
+            Cell::new(gate.pubkey),
+            status,
+            Cell::new(slot),
+            Cell::new(epoch),
+        ]);
+    }
+
+    print_list_table(ctx, "FEATURE GATES", &table);
+    if ctx.output_format() == OutputFormat::Table && feature_pubkey.is_none() {
+        println!(
+            "{}",
+            theme::muted(format!(
+                "{} feature(s): {} activated, {pending} pending",
+                gates.len(),
+                gates.len() - pending
+            ))
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature_account(activated_at: Option<u64>) -> Account {
+        Account {
+            lamports: 1,
+            data: bincode::serialize(&activated_at).unwrap(),
+            owner: feature::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_feature_gate_from_account() {
+        let pubkey = Pubkey::new_unique();
+
+        let gate = FeatureGate::from_account(pubkey, &feature_account(Some(42))).unwrap();
+        assert_eq!(gate.activated_at, Some(42));
+
+        let gate = FeatureGate::from_account(pubkey, &feature_account(None)).unwrap();
+        assert_eq!(gate.activated_at, None);
+
+        let mut not_feature = feature_account(None);
+        not_feature.owner = Pubkey::new_unique();
+        assert!(FeatureGate::from_account(pubkey, &not_feature).is_err());
+    }
+
+    #[test]
+    fn test_sort_and_filter_feature_gates() {
+        let gate = |activated_at| FeatureGate {
+            pubkey: Pubkey::new_unique(),
+            activated_at,
+        };
+        let mut gates = vec![gate(Some(10)), gate(None), gate(Some(300))];
+
+        sort_feature_gates(&mut gates);
+        let order: Vec<Option<u64>> = gates.iter().map(|gate| gate.activated_at).collect();
+        assert_eq!(order, vec![None, Some(300), Some(10)]);
+
+        assert_eq!(
+            gates
+                .iter()
+                .filter(|gate| FeatureFilter::Activated.matches(gate))
+                .count(),
+            2
+        );
+        assert!(FeatureFilter::Pending.matches(&gates[0]));
+    }
+
+    #[test]
+    fn test_method_benchmark_records_errors_separately() {
+        let mut benchmark = MethodBenchmark::default();
+        benchmark.record(Instant::now(), Ok::<_, String>(()));
+        benchmark.record(Instant::now(), Err::<(), _>("timed out".to_string()));
+        benchmark.record(Instant::now(), Err::<(), _>("refused".to_string()));
+
+        assert_eq!(benchmark.latencies_us.len(), 1);
+        assert_eq!(benchmark.errors, 2);
+        assert_eq!(benchmark.first_error.as_deref(), Some("timed out"));
+        assert_eq!(format_latency(1_260), "1.3 ms");
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let samples = [0, 0, 10, 20, 30, 40, 50, 60, 70, 1000];
+
+        assert_eq!(percentile(&samples, 50.0), 30);
+        assert_eq!(percentile(&samples, 90.0), 70);
+        assert_eq!(percentile(&samples, 100.0), 1000);
+        assert_eq!(percentile(&[5], 25.0), 5);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn test_parse_pubkey_list() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert_eq!(parse_pubkey_list(&format!("{a}, {b}")).unwrap(), vec![a, b]);
+        assert!(parse_pubkey_list("").unwrap().is_empty());
+        assert!(parse_pubkey_list("not-a-key").is_err());
+    }
+
+    #[test]
+    fn test_validators_to_exceed_thresholds() {
+        let stakes = [40, 30, 20, 10];
+
+        assert_eq!(validators_to_exceed(&stakes, 100, 1.0 / 3.0), 1);
+        assert_eq!(validators_to_exceed(&stakes, 100, 2.0 / 3.0), 2);
+        // exactly reaching the threshold is not enough
+        assert_eq!(validators_to_exceed(&[50, 50], 100, 0.5), 2);
+        assert_eq!(validators_to_exceed(&[], 0, 1.0 / 3.0), 0);
+    }
+}
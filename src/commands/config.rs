@@ -1,25 +1,64 @@
 use {
     crate::{
         commands::CommandFlow,
-        config::{ScillaConfig, scilla_config_path},
+        config::{Explorer, OutputFormat, ScillaConfig, Theme, expand_tilde, scilla_config_path},
+        constants::{DEFAULT_AIRDROP_RETRIES, DEFAULT_CONFIRM_TIMEOUT_SECS, HISTORY_DISPLAY_LIMIT},
         context::ScillaContext,
-        misc::helpers::short_pubkey,
-        prompt::{prompt_input_data, prompt_keypair_path},
-        ui::print_error,
+        error::ScillaError,
+        misc::{
+            encrypted_keypair::encrypt_keypair,
+            helpers::{
+                SendPipeline, is_encrypted_keypair_file, read_keypair_with_passphrase, short_pubkey,
+            },
+            history::{read_history, scilla_history_path},
+        },
+        prompt::{
+            apply_assume_yes, prompt_confirmation, prompt_input_data,
+            prompt_input_data_with_default, prompt_keypair_path, prompt_optional_data,
+            prompt_passphrase,
+        },
+        ui::{print_error, print_record_table, theme},
     },
+    anyhow::{anyhow, bail},
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
     inquire::{Confirm, Select},
     serde::{Deserialize, Serialize},
     solana_commitment_config::CommitmentLevel,
-    std::{fmt, fs, path::PathBuf},
+    solana_keypair::Signer,
+    std::{
+        collections::BTreeMap,
+        fmt, fs,
+        path::{Path, PathBuf},
+    },
 };
 
+/// Config views that run non-interactively as `scilla config <command>`
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigArgs {
+    /// Settings of the config file and the active wallet
+    Show,
+}
+
+impl ConfigArgs {
+    pub fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            ConfigArgs::Show => show_config(ctx),
+        }
+    }
+}
+
 /// Commands related to configuration like RPC_URL , KEYAPAIR_PATH etc
 #[derive(Debug, Clone)]
 pub enum ConfigCommand {
     Show,
+    Generate,
     Edit,
+    Inspect,
+    History,
+    RenameWallet,
+    SwitchWallet,
+    EncryptKeypair,
     GoBack,
 }
 
@@ -27,17 +66,43 @@ impl ConfigCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
             ConfigCommand::Show => "Displaying current Scilla configuration…",
+            ConfigCommand::Generate => "Generating Scilla configuration…",
             ConfigCommand::Edit => "Editing existing Scilla configuration…",
+            ConfigCommand::Inspect => "Parsing config file…",
+            ConfigCommand::History => "Reading session history…",
+            ConfigCommand::RenameWallet => "Renaming wallet…",
+            ConfigCommand::SwitchWallet => "Switching wallet…",
+            ConfigCommand::EncryptKeypair => "Encrypting keypair…",
             ConfigCommand::GoBack => "Going back…",
         }
     }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            ConfigCommand::Show => "Show the RPC URL, commitment and keypair in use",
+            ConfigCommand::Generate => "Write a fresh config file and switch the session to it",
+            ConfigCommand::Edit => "Change one config field and reload the session",
+            ConfigCommand::Inspect => "Parse any config TOML and show its effective settings",
+            ConfigCommand::History => "Commands run in previous sessions, newest first",
+            ConfigCommand::RenameWallet => "Relabel the active wallet in the [wallets] table",
+            ConfigCommand::SwitchWallet => "Use another wallet from the [wallets] table",
+            ConfigCommand::EncryptKeypair => "Write a passphrase-protected copy of a keypair file",
+            ConfigCommand::GoBack => "Return to the command groups",
+        }
+    }
 }
 
 impl fmt::Display for ConfigCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
             ConfigCommand::Show => "View ScillaConfig",
+            ConfigCommand::Generate => "Generate ScillaConfig",
             ConfigCommand::Edit => "Edit ScillaConfig",
+            ConfigCommand::Inspect => "Inspect config file",
+            ConfigCommand::History => "Session History",
+            ConfigCommand::RenameWallet => "Rename Wallet",
+            ConfigCommand::SwitchWallet => "Switch Wallet",
+            ConfigCommand::EncryptKeypair => "Encrypt Keypair",
             ConfigCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -48,7 +113,22 @@ impl fmt::Display for ConfigCommand {
 enum ConfigField {
     RpcUrl,
     CommitmentLevel,
+    ReadCommitment,
     KeypairPath,
+    WatchAddress,
+    Explorer,
+    OutputFormat,
+    Theme,
+    ConfirmTimeout,
+    AirdropRetries,
+    DefaultRecipient,
+    DefaultAmount,
+    PreflightRecipientCheck,
+    SimulateBeforeSend,
+    DryRun,
+    SkipConfirm,
+    PriorityFee,
+    AssumeYes,
     None, // if None is chosen , we go back to previous context
 }
 
@@ -57,7 +137,22 @@ impl fmt::Display for ConfigField {
         match self {
             ConfigField::RpcUrl => write!(f, "RPC URL"),
             ConfigField::CommitmentLevel => write!(f, "Commitment Level"),
+            ConfigField::ReadCommitment => write!(f, "Read Commitment"),
             ConfigField::KeypairPath => write!(f, "Keypair Path"),
+            ConfigField::WatchAddress => write!(f, "Watch Address"),
+            ConfigField::Explorer => write!(f, "Explorer"),
+            ConfigField::OutputFormat => write!(f, "Output Format"),
+            ConfigField::Theme => write!(f, "Theme"),
+            ConfigField::ConfirmTimeout => write!(f, "Confirmation Timeout"),
+            ConfigField::AirdropRetries => write!(f, "Airdrop Retries"),
+            ConfigField::DefaultRecipient => write!(f, "Default Recipient"),
+            ConfigField::DefaultAmount => write!(f, "Default Amount"),
+            ConfigField::PreflightRecipientCheck => write!(f, "Preflight Recipient Check"),
+            ConfigField::SimulateBeforeSend => write!(f, "Simulate Before Send"),
+            ConfigField::DryRun => write!(f, "Dry Run"),
+            ConfigField::SkipConfirm => write!(f, "Skip Confirm"),
+            ConfigField::PriorityFee => write!(f, "Priority Fee"),
+            ConfigField::AssumeYes => write!(f, "Assume Yes"),
             ConfigField::None => write!(f, "None"),
         }
     }
@@ -68,7 +163,22 @@ impl ConfigField {
         vec![
             ConfigField::RpcUrl,
             ConfigField::CommitmentLevel,
+            ConfigField::ReadCommitment,
             ConfigField::KeypairPath,
+            ConfigField::WatchAddress,
+            ConfigField::Explorer,
+            ConfigField::OutputFormat,
+            ConfigField::Theme,
+            ConfigField::ConfirmTimeout,
+            ConfigField::AirdropRetries,
+            ConfigField::DefaultRecipient,
+            ConfigField::DefaultAmount,
+            ConfigField::PreflightRecipientCheck,
+            ConfigField::SimulateBeforeSend,
+            ConfigField::DryRun,
+            ConfigField::SkipConfirm,
+            ConfigField::PriorityFee,
+            ConfigField::AssumeYes,
             ConfigField::None,
         ]
     }
@@ -102,7 +212,28 @@ impl ConfigCommand {
     pub fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
         let res = match self {
             ConfigCommand::Show => show_config(ctx),
+            ConfigCommand::Generate => regenerate_config(ctx),
             ConfigCommand::Edit => edit_config(ctx),
+            ConfigCommand::Inspect => {
+                let path: String = prompt_input_data("Enter config TOML path:");
+                inspect_config(&expand_tilde(&path))
+            }
+            ConfigCommand::History => show_history(),
+            ConfigCommand::RenameWallet => rename_wallet(ctx),
+            ConfigCommand::SwitchWallet => switch_wallet(ctx),
+            ConfigCommand::EncryptKeypair => {
+                let source = prompt_keypair_path("Enter keypair file to encrypt:", ctx);
+                let output: String = prompt_input_data_with_default(
+                    "Save encrypted keypair to:",
+                    Some(
+                        source
+                            .with_extension("encrypted.json")
+                            .display()
+                            .to_string(),
+                    ),
+                );
+                encrypt_keypair_file(&source, &expand_tilde(&output))
+            }
             ConfigCommand::GoBack => return CommandFlow::GoBack,
         };
 
@@ -114,56 +245,295 @@ impl ConfigCommand {
     }
 }
 
-fn show_config(ctx: &ScillaContext) -> anyhow::Result<()> {
+fn show_history() -> anyhow::Result<()> {
+    let entries = read_history(&scilla_history_path(), HISTORY_DISPLAY_LIMIT)?;
+
+    if entries.is_empty() {
+        println!("{}", theme::warning("No commands recorded yet."));
+        return Ok(());
+    }
+
     let mut table = Table::new();
-    let config = ScillaConfig::load()?;
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::header_cell("Time"),
+        theme::header_cell("Wallet"),
+        theme::header_cell("Command"),
+    ]);
+
+    for entry in entries {
+        table.add_row(vec![
+            Cell::new(entry.timestamp.format("%Y-%m-%d %H:%M:%S")),
+            Cell::new(entry.wallet),
+            Cell::new(entry.command),
+        ]);
+    }
+
+    println!("\n{}", theme::success("SESSION HISTORY").bold());
+    println!("{table}");
+
+    Ok(())
+}
+
+fn show_config(ctx: &ScillaContext) -> anyhow::Result<()> {
+    // read quietly, as a subcommand's stdout must only carry the settings
+    let config = ScillaConfig::load_from_path(&scilla_config_path())?;
 
-    let wallet_pubkey = ctx.pubkey();
-    let keypair_display = format!(
-        "{} ({})",
-        config.keypair_path.display(),
-        short_pubkey(wallet_pubkey),
+    // the session's keypair, which differs from the file's after a switch
+    let keypair_display = if ctx.is_watch_only() {
+        "Not loaded (watch-only)".to_string()
+    } else {
+        format!(
+            "{} ({})",
+            ctx.keypair_path().display(),
+            short_pubkey(ctx.pubkey()),
+        )
+    };
+    let mut table = settings_table(&config, keypair_display);
+    table.add_row(vec![
+        Cell::new("Active Wallet"),
+        Cell::new(ctx.wallet_name().unwrap_or("Unnamed")),
+    ]);
+
+    print_record_table(ctx, "SCILLA CONFIG", &table);
+
+    Ok(())
+}
+
+/// Parses a config file the same way startup does and shows what it resolves
+/// to: effective settings, expanded paths, defaulted and unrecognised keys
+fn inspect_config(path: &Path) -> anyhow::Result<()> {
+    let config = match ScillaConfig::load_from_path(path) {
+        Ok(config) => config,
+        Err(ScillaError::ConfigPathDoesNotExist) => bail!("No file at {}", path.display()),
+        Err(ScillaError::TomlParseError(e)) => {
+            bail!("{} is not a valid Scilla config:\n{e}", path.display())
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let raw: toml::Table = toml::from_str(&fs::read_to_string(path)?)?;
+    let effective = toml::Table::try_from(&config)?;
+
+    let table = settings_table(&config, config.keypair_path.display().to_string());
+    println!(
+        "\n{}",
+        theme::success(format!("CONFIG AT {}", path.display())).bold()
     );
+    println!("{table}");
+
+    let mut paths = Table::new();
+    paths.load_preset(UTF8_FULL).set_header(vec![
+        theme::header_cell("Key"),
+        theme::header_cell("As Written"),
+        theme::header_cell("Resolved"),
+    ]);
+    if let Some(written) = raw.get("keypair-path").and_then(|value| value.as_str()) {
+        paths.add_row(vec![
+            Cell::new("keypair-path"),
+            Cell::new(written),
+            Cell::new(config.keypair_path.display()),
+        ]);
+    }
+    if let Some(wallets) = raw.get("wallets").and_then(|value| value.as_table()) {
+        for (name, written) in wallets {
+            let resolved = config
+                .wallets
+                .get(name)
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            paths.add_row(vec![
+                Cell::new(format!("wallets.{name}")),
+                Cell::new(written.as_str().unwrap_or_default()),
+                Cell::new(resolved),
+            ]);
+        }
+    }
+
+    println!("\n{}", theme::success("PATHS").bold());
+    println!("{paths}");
+
+    let defaulted: Vec<&str> = effective
+        .keys()
+        .filter(|key| !raw.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+    if !defaulted.is_empty() {
+        println!(
+            "{}",
+            theme::muted(format!("Using defaults for: {}", defaulted.join(", ")))
+        );
+    }
+
+    let ignored: Vec<&str> = raw
+        .keys()
+        .filter(|key| !effective.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+    if !ignored.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "Ignored keys (misspelled or unsupported): {}",
+                ignored.join(", ")
+            ))
+        );
+    }
+
+    Ok(())
+}
+
+fn settings_table(config: &ScillaConfig, keypair_display: String) -> Table {
+    let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
-        .add_row(vec![Cell::new("RPC URL"), Cell::new(config.rpc_url)])
+        .add_row(vec![Cell::new("RPC URL"), Cell::new(&config.rpc_url)])
         .add_row(vec![
             Cell::new("Commitment Level"),
             Cell::new(config.commitment_level),
         ])
-        .add_row(vec![Cell::new("Keypair Path"), Cell::new(keypair_display)]);
+        .add_row(vec![
+            Cell::new("Read Commitment"),
+            Cell::new(read_commitment_display(config)),
+        ])
+        .add_row(vec![Cell::new("Keypair Path"), Cell::new(keypair_display)])
+        .add_row(vec![
+            Cell::new("Watch Address"),
+            Cell::new(optional_display(config.watch_address)),
+        ])
+        .add_row(vec![Cell::new("Explorer"), Cell::new(config.explorer)])
+        .add_row(vec![
+            Cell::new("Output Format"),
+            Cell::new(config.output_format),
+        ])
+        .add_row(vec![Cell::new("Theme"), Cell::new(config.theme)])
+        .add_row(vec![
+            Cell::new("Confirmation Timeout"),
+            Cell::new(format!("{}s", config.confirm_timeout_secs)),
+        ])
+        .add_row(vec![
+            Cell::new("Airdrop Retries"),
+            Cell::new(config.airdrop_retries),
+        ])
+        .add_row(vec![
+            Cell::new("Default Recipient"),
+            Cell::new(optional_display(config.default_recipient)),
+        ])
+        .add_row(vec![
+            Cell::new("Default Amount"),
+            Cell::new(optional_display(
+                config.default_amount.map(|amount| format!("{amount} SOL")),
+            )),
+        ])
+        .add_row(vec![
+            Cell::new("Max Transfer"),
+            Cell::new(
+                config
+                    .max_transfer_sol
+                    .map_or_else(|| "No limit".to_string(), |limit| limit.to_string()),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("Preflight Recipient Check"),
+            Cell::new(if config.preflight_recipient_check {
+                "On"
+            } else {
+                "Off"
+            }),
+        ])
+        .add_row(vec![
+            Cell::new("Send Pipeline"),
+            Cell::new(SendPipeline::from_config(config)),
+        ])
+        .add_row(vec![
+            Cell::new("Priority Fee"),
+            Cell::new(priority_fee_display(config)),
+        ])
+        .add_row(vec![
+            Cell::new("Assume Yes"),
+            Cell::new(assume_yes_display(config)),
+        ]);
 
-    println!("\n{}", style("SCILLA CONFIG").green().bold());
-    println!("{}", table);
+    table
+}
 
-    Ok(())
+fn priority_fee_display(config: &ScillaConfig) -> String {
+    match config.priority_fee_micro_lamports {
+        0 => "Off".to_string(),
+        price => format!("{price} micro-lamports per compute unit"),
+    }
+}
+
+fn assume_yes_display(config: &ScillaConfig) -> &'static str {
+    match (config.assume_yes, config.assume_yes_mainnet) {
+        (false, _) => "Off",
+        (true, false) => "On (not on mainnet)",
+        (true, true) => "On, including mainnet",
+    }
+}
+
+fn read_commitment_display(config: &ScillaConfig) -> String {
+    match config.read_commitment {
+        Some(level) => format!("{level:?}"),
+        None => "Same as commitment level".to_string(),
+    }
+}
+
+fn optional_display(value: Option<impl fmt::Display>) -> String {
+    value.map_or_else(|| "Not set".to_string(), |value| value.to_string())
 }
 
 pub fn generate_config() -> anyhow::Result<()> {
     // Check if config already exists
     let config_path = scilla_config_path();
     if config_path.exists() {
-        println!("{}", style("Config file already exists!").yellow().bold());
+        println!("{}", theme::warning("Config file already exists!").bold());
         println!(
             "{}",
-            style(format!("Location: {}", config_path.display())).cyan()
+            theme::info(format!("Location: {}", config_path.display()))
         );
         println!(
             "{}",
-            style("Use the 'Edit' option to modify your existing config.").cyan()
+            theme::info("Use the 'Edit' option to modify your existing config.")
         );
         return Ok(());
     }
 
-    println!("\n{}", style("Generate New Config").green().bold());
+    write_new_config()?;
+    Ok(())
+}
+
+/// Generates a config from the menu, replacing an existing one only once
+/// confirmed, and reloads the session with it
+fn regenerate_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+    let config_path = scilla_config_path();
+    if config_path.exists()
+        && !prompt_confirmation(&format!(
+            "Overwrite the existing config at {}? All of its settings are replaced",
+            config_path.display()
+        ))
+    {
+        println!("{}", theme::warning("Config left unchanged."));
+        return Ok(());
+    }
+
+    if write_new_config()? {
+        let config = ScillaConfig::load()?;
+        theme::apply(config.theme);
+        apply_assume_yes(&config);
+        ctx.reload(config)?;
+    }
+
+    Ok(())
+}
+
+/// Asks for the settings of a new config and writes it, returning whether
+/// anything was written
+fn write_new_config() -> anyhow::Result<bool> {
+    println!("\n{}", theme::success("Generate New Config").bold());
 
     // Ask if user wants to use defaults
     let use_defaults = Confirm::new("Use default config? (Devnet RPC, Confirmed commitment)")
@@ -173,7 +543,7 @@ pub fn generate_config() -> anyhow::Result<()> {
     let config = if use_defaults {
         let config = ScillaConfig::default();
 
-        println!("{}", style("Using default configuration:").cyan());
+        println!("{}", theme::info("Using default configuration:"));
         println!("  RPC: {}", config.rpc_url);
         println!("  Commitment: {:?}", config.commitment_level);
         println!("  Keypair: {}", config.keypair_path.display());
@@ -185,7 +555,7 @@ pub fn generate_config() -> anyhow::Result<()> {
         let commitment_level =
             match Select::new("Select commitment level:", get_commitment_levels()).prompt()? {
                 UICommitmentOptions::Level(level) => level,
-                UICommitmentOptions::None => return Ok(()),
+                UICommitmentOptions::None => return Ok(false),
             };
 
         let keypair_path = loop {
@@ -194,11 +564,10 @@ pub fn generate_config() -> anyhow::Result<()> {
             if !keypair_input.exists() {
                 println!(
                     "{}",
-                    style(format!(
+                    theme::error(format!(
                         "Keypair file not found at: {}",
                         keypair_input.display()
                     ))
-                    .red()
                 );
                 continue;
             }
@@ -209,7 +578,25 @@ pub fn generate_config() -> anyhow::Result<()> {
         ScillaConfig {
             rpc_url,
             commitment_level,
+            read_commitment: None,
             keypair_path,
+            watch_address: None,
+            explorer: Explorer::default(),
+            output_format: OutputFormat::default(),
+            theme: Theme::default(),
+            confirm_timeout_secs: DEFAULT_CONFIRM_TIMEOUT_SECS,
+            airdrop_retries: DEFAULT_AIRDROP_RETRIES,
+            default_recipient: None,
+            default_amount: None,
+            max_transfer_sol: None,
+            preflight_recipient_check: false,
+            simulate_before_send: false,
+            dry_run: false,
+            skip_confirm: false,
+            priority_fee_micro_lamports: 0,
+            assume_yes: false,
+            assume_yes_mainnet: false,
+            wallets: Default::default(),
         }
     };
 
@@ -222,32 +609,96 @@ pub fn generate_config() -> anyhow::Result<()> {
     let toml_string = toml::to_string_pretty(&config)?;
     fs::write(&config_path, toml_string)?;
 
-    println!("{}", style("Config generated successfully!").green().bold());
     println!(
         "{}",
-        style(format!("Saved to: {}", config_path.display())).cyan()
+        theme::success("Config generated successfully!").bold()
+    );
+    println!(
+        "{}",
+        theme::info(format!("Saved to: {}", config_path.display()))
     );
 
-    Ok(())
+    Ok(true)
 }
 
 fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
     let mut config = ScillaConfig::load()?;
 
-    println!("\n{}", style("Edit Config").green().bold());
+    println!("\n{}", theme::success("Edit Config").bold());
 
     // Show current configuration
-    println!("\n{} {}", style("Current RPC URL:").cyan(), config.rpc_url);
+    println!("\n{} {}", theme::info("Current RPC URL:"), config.rpc_url);
     println!(
         "{} {:?}",
-        style("Current Commitment Level:").cyan(),
+        theme::info("Current Commitment Level:"),
         config.commitment_level
     );
     println!(
         "{} {}",
-        style("Current Keypair Path:").cyan(),
+        theme::info("Current Read Commitment:"),
+        read_commitment_display(&config)
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Keypair Path:"),
         config.keypair_path.display()
     );
+    println!(
+        "{} {}",
+        theme::info("Current Watch Address:"),
+        optional_display(config.watch_address)
+    );
+    println!("{} {}", theme::info("Current Explorer:"), config.explorer);
+    println!(
+        "{} {}",
+        theme::info("Current Output Format:"),
+        config.output_format
+    );
+    println!("{} {}", theme::info("Current Theme:"), config.theme);
+    println!(
+        "{} {}s",
+        theme::info("Current Confirmation Timeout:"),
+        config.confirm_timeout_secs
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Airdrop Retries:"),
+        config.airdrop_retries
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Default Recipient:"),
+        optional_display(config.default_recipient)
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Default Amount:"),
+        optional_display(config.default_amount)
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Preflight Recipient Check:"),
+        if config.preflight_recipient_check {
+            "On"
+        } else {
+            "Off"
+        }
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Send Pipeline:"),
+        SendPipeline::from_config(&config)
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Priority Fee:"),
+        priority_fee_display(&config)
+    );
+    println!(
+        "{} {}",
+        theme::info("Current Assume Yes:"),
+        assume_yes_display(&config)
+    );
 
     // Prompt user to select which field to edit
     let field_options = ConfigField::all();
@@ -268,24 +719,112 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
 
             config.commitment_level = level
         }
+        ConfigField::ReadCommitment => {
+            let selected = Select::new(
+                "Select Read Commitment",
+                vec![
+                    "Same as commitment level",
+                    "Processed",
+                    "Confirmed",
+                    "Finalized",
+                ],
+            )
+            .prompt()?;
+
+            config.read_commitment = match selected {
+                "Processed" => Some(CommitmentLevel::Processed),
+                "Confirmed" => Some(CommitmentLevel::Confirmed),
+                "Finalized" => Some(CommitmentLevel::Finalized),
+                _ => None,
+            };
+        }
         ConfigField::KeypairPath => loop {
             let keypair_input = prompt_keypair_path("Enter new keypair path:", ctx);
 
             if !keypair_input.exists() {
                 println!(
                     "{}",
-                    style(format!(
+                    theme::error(format!(
                         "Keypair file not found at: {}",
                         keypair_input.display()
                     ))
-                    .red()
                 );
                 continue;
             }
 
+            // A file that exists but doesn't load would break every signing
+            // command after the reload, so load it once before saving. An
+            // encrypted one is decrypted by the reload, asking only once.
+            if !is_encrypted_keypair_file(&keypair_input)
+                && let Err(e) = read_keypair_with_passphrase(&keypair_input, None)
+            {
+                print_error(format!(
+                    "Could not load a keypair from {}: {e}",
+                    keypair_input.display()
+                ));
+                continue;
+            }
+
             config.keypair_path = keypair_input;
             break;
         },
+        ConfigField::WatchAddress => {
+            config.watch_address = prompt_optional_data(
+                "Enter address to watch without a keypair (leave empty to sign again):",
+            );
+        }
+        ConfigField::Explorer => {
+            config.explorer = Select::new("Select Explorer", Explorer::all()).prompt()?;
+        }
+        ConfigField::OutputFormat => {
+            config.output_format =
+                Select::new("Select Output Format", OutputFormat::all()).prompt()?;
+        }
+        ConfigField::Theme => {
+            config.theme = Select::new("Select Theme", Theme::all()).prompt()?;
+        }
+        ConfigField::ConfirmTimeout => {
+            config.confirm_timeout_secs =
+                prompt_input_data("Enter confirmation timeout in seconds:");
+        }
+        ConfigField::AirdropRetries => {
+            config.airdrop_retries =
+                prompt_input_data("Enter how often to retry a refused airdrop:");
+        }
+        ConfigField::DefaultRecipient => {
+            config.default_recipient =
+                prompt_optional_data("Enter default recipient (leave empty to clear):");
+        }
+        ConfigField::DefaultAmount => {
+            config.default_amount =
+                prompt_optional_data("Enter default amount in SOL (leave empty to clear):");
+        }
+        ConfigField::PreflightRecipientCheck => {
+            config.preflight_recipient_check =
+                prompt_confirmation("Check the recipient's account before each SOL transfer?");
+        }
+        ConfigField::SimulateBeforeSend => {
+            config.simulate_before_send =
+                prompt_confirmation("Simulate every transaction before sending it?");
+        }
+        ConfigField::DryRun => {
+            config.dry_run = prompt_confirmation("Only simulate transactions, never send them?");
+        }
+        ConfigField::SkipConfirm => {
+            config.skip_confirm =
+                prompt_confirmation("Send a successful simulation without asking again?");
+        }
+        ConfigField::PriorityFee => {
+            config.priority_fee_micro_lamports = prompt_input_data(
+                "Enter priority fee in micro-lamports per compute unit (0 for none):",
+            );
+        }
+        ConfigField::AssumeYes => {
+            // Only asked, never assumed, since it decides whether asking happens
+            config.assume_yes = Confirm::new("Answer every confirmation with yes?")
+                .with_default(false)
+                .prompt()?;
+        }
         ConfigField::None => return Ok(()),
     }
 
@@ -294,13 +833,175 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
     let toml_string = toml::to_string_pretty(&config)?;
     fs::write(&config_path, toml_string)?;
 
+    keep_session_wallet(&mut config, ctx, Some(&selected_field));
+    theme::apply(config.theme);
+    apply_assume_yes(&config);
     ctx.reload(config)?;
 
-    println!("{}", style("Config updated successfully!").green().bold());
+    println!("{}", theme::success("Config updated successfully!").bold());
     println!(
         "{}",
-        style(format!("Saved to: {}", config_path.display())).cyan()
+        theme::info(format!("Saved to: {}", config_path.display()))
     );
 
     Ok(())
 }
+
+/// Carries the session's keypair and RPC URL over to a config just read from
+/// disk, since Switch Wallet changes them without saving. A field the user
+/// has just `edited` keeps its new value.
+fn keep_session_wallet(
+    config: &mut ScillaConfig,
+    ctx: &ScillaContext,
+    edited: Option<&ConfigField>,
+) {
+    if !matches!(edited, Some(ConfigField::RpcUrl)) {
+        config.rpc_url = ctx.rpc().url();
+    }
+    if !matches!(
+        edited,
+        Some(ConfigField::KeypairPath | ConfigField::WatchAddress)
+    ) {
+        config.keypair_path = ctx.keypair_path().clone();
+        if !ctx.is_watch_only() {
+            config.watch_address = None;
+        }
+    }
+}
+
+/// Reloads the session with one of the wallets listed in the config's
+/// `[wallets]` table. The config file itself is left unchanged.
+pub fn switch_wallet(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+    let mut config = ScillaConfig::load()?;
+
+    if config.wallets.is_empty() {
+        println!(
+            "{}",
+            theme::warning(
+                "No wallets configured. Add a [wallets] table mapping names to keypair paths."
+            )
+        );
+        return Ok(());
+    }
+
+    let names: Vec<String> = config.wallets.keys().cloned().collect();
+    let name = Select::new("Select wallet:", names).prompt()?;
+
+    // only the keypair changes; the session stays on its current cluster
+    config.keypair_path = config.wallets[&name].clone();
+    config.watch_address = None;
+    config.rpc_url = ctx.rpc().url();
+    ctx.reload(config)?;
+
+    println!(
+        "{} {}",
+        theme::success(format!("Switched to wallet {name}:")).bold(),
+        theme::info(ctx.pubkey())
+    );
+
+    Ok(())
+}
+
+/// Writes an encrypted copy of the keypair at `source` to `output`. The
+/// plaintext file is left in place until the user has checked the copy.
+fn encrypt_keypair_file(source: &Path, output: &Path) -> anyhow::Result<()> {
+    if output.exists() {
+        bail!(
+            "{} already exists, refusing to overwrite it",
+            output.display()
+        );
+    }
+    if is_encrypted_keypair_file(source) {
+        bail!("{} is already encrypted", source.display());
+    }
+
+    let keypair = read_keypair_with_passphrase(source, None)?;
+    let passphrase = prompt_passphrase("New passphrase:", true)?;
+    if passphrase.is_empty() {
+        bail!("Passphrase cannot be empty");
+    }
+
+    fs::write(output, encrypt_keypair(&keypair, &passphrase)?)?;
+
+    println!(
+        "{} {}",
+        theme::success(format!("Encrypted keypair {} saved to", keypair.pubkey())).bold(),
+        theme::info(output.display())
+    );
+    println!(
+        "{}",
+        theme::muted(format!(
+            "Point keypair-path at it, and delete {} once you no longer need the plaintext copy.",
+            source.display()
+        ))
+    );
+
+    Ok(())
+}
+
+/// Renames the active wallet's entry in the config's `[wallets]` table. The
+/// keypair file is not touched; only the name it is listed under changes.
+pub fn rename_wallet(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+    let Some(current) = ctx.wallet_name().map(str::to_string) else {
+        bail!("The active keypair is not listed in the [wallets] config table");
+    };
+
+    let mut config = ScillaConfig::load()?;
+    let new_name: String = prompt_input_data(&format!("Enter a new name for wallet {current}:"));
+    let new_name = new_name.trim();
+    rename_wallet_entry(&mut config.wallets, &current, new_name)?;
+
+    let config_path = scilla_config_path();
+    let toml_string = toml::to_string_pretty(&config)?;
+    fs::write(&config_path, toml_string)?;
+
+    keep_session_wallet(&mut config, ctx, None);
+    ctx.reload(config)?;
+
+    println!(
+        "{}",
+        theme::success(format!("Renamed wallet {current} to {new_name}")).bold()
+    );
+
+    Ok(())
+}
+
+fn rename_wallet_entry(
+    wallets: &mut BTreeMap<String, PathBuf>,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<()> {
+    if to.is_empty() {
+        bail!("Wallet name cannot be empty");
+    }
+    if wallets.contains_key(to) {
+        bail!("A wallet named {to} already exists");
+    }
+
+    let path = wallets
+        .remove(from)
+        .ok_or_else(|| anyhow!("No wallet named {from} in the [wallets] config table"))?;
+    wallets.insert(to.to_string(), path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_wallet_entry_guards_collisions() {
+        let mut wallets = BTreeMap::from([
+            ("hot".to_string(), PathBuf::from("hot.json")),
+            ("cold".to_string(), PathBuf::from("cold.json")),
+        ]);
+
+        assert!(rename_wallet_entry(&mut wallets, "hot", "cold").is_err());
+        assert!(rename_wallet_entry(&mut wallets, "hot", "").is_err());
+
+        rename_wallet_entry(&mut wallets, "hot", "daily").unwrap();
+        assert_eq!(wallets.get("daily"), Some(&PathBuf::from("hot.json")));
+        assert!(!wallets.contains_key("hot"));
+    }
+}
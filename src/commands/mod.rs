@@ -2,11 +2,12 @@ use {
     crate::{
         commands::{
             account::AccountCommand, cluster::ClusterCommand, config::ConfigCommand,
-            stake::StakeCommand, transaction::TransactionCommand, vote::VoteCommand,
+            stake::StakeCommand, token::TokenCommand, transaction::TransactionCommand,
+            vote::VoteCommand,
         },
         context::ScillaContext,
+        ui::{print_error, theme},
     },
-    console::style,
     std::{
         fmt,
         process::{ExitCode, Termination},
@@ -17,6 +18,7 @@ pub mod account;
 pub mod cluster;
 pub mod config;
 pub mod stake;
+pub mod token;
 pub mod transaction;
 pub mod vote;
 
@@ -28,60 +30,260 @@ pub enum CommandFlow<T> {
 
 impl<T> Termination for CommandFlow<T> {
     fn report(self) -> std::process::ExitCode {
-        println!("{}", style("Goodbye 👋").dim());
+        println!("{}", theme::muted("Goodbye 👋"));
         ExitCode::SUCCESS
     }
 }
 
+/// The menus opened on the way to the current prompt, outermost first. It
+/// drives the breadcrumb header and decides where `GoBack` lands.
+#[derive(Debug, Default)]
+pub struct NavStack {
+    menus: Vec<CommandGroup>,
+}
+
+impl NavStack {
+    pub fn current(&self) -> Option<&CommandGroup> {
+        self.menus.last()
+    }
+
+    pub fn enter(&mut self, group: CommandGroup) {
+        self.menus.push(group);
+    }
+
+    /// Leaves the current menu for its parent
+    pub fn back(&mut self) {
+        self.menus.pop();
+    }
+
+    pub fn breadcrumb(&self) -> String {
+        std::iter::once("Scilla".to_string())
+            .chain(self.menus.iter().map(CommandGroup::to_string))
+            .collect::<Vec<_>>()
+            .join(" › ")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Command {
     Cluster(ClusterCommand),
     Stake(StakeCommand),
     Account(AccountCommand),
     Vote(VoteCommand),
+    Token(TokenCommand),
     Transaction(TransactionCommand),
     ScillaConfig(ConfigCommand),
+    SwitchWallet,
+    ToggleReadCommitment,
+    ToggleDryRun,
     Exit,
 }
 
 impl Command {
-    pub async fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
+    /// Whether the command needs the session keypair to sign
+    pub fn requires_signing(&self) -> bool {
         match self {
+            Command::Stake(command) => command.requires_signing(),
+            Command::Account(command) => command.requires_signing(),
+            Command::Vote(command) => command.requires_signing(),
+            Command::Token(command) => command.requires_signing(),
+            Command::Transaction(command) => command.requires_signing(),
+            Command::Cluster(_)
+            | Command::ScillaConfig(_)
+            | Command::SwitchWallet
+            | Command::ToggleReadCommitment
+            | Command::ToggleDryRun
+            | Command::Exit => false,
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            Command::Cluster(command) => command.help(),
+            Command::Stake(command) => command.help(),
+            Command::Account(command) => command.help(),
+            Command::Vote(command) => command.help(),
+            Command::Token(command) => command.help(),
+            Command::Transaction(command) => command.help(),
+            Command::ScillaConfig(command) => command.help(),
+            Command::SwitchWallet => CommandGroup::SwitchWallet.help(),
+            Command::ToggleReadCommitment => CommandGroup::ToggleReadCommitment.help(),
+            Command::ToggleDryRun => CommandGroup::ToggleDryRun.help(),
+            Command::Exit => CommandGroup::Exit.help(),
+        }
+    }
+
+    /// Runs the command. A `GoBack` result also moves `nav` up to the parent
+    /// menu; otherwise the next prompt reopens the menu the command came from.
+    pub async fn process_command(
+        &self,
+        ctx: &mut ScillaContext,
+        nav: &mut NavStack,
+    ) -> CommandFlow<()> {
+        if ctx.is_watch_only() && self.requires_signing() {
+            print_error(format!(
+                "{self} needs a keypair to sign. Scilla is watching {} without one; remove \
+                 watch-address from the config to use this command.",
+                ctx.pubkey()
+            ));
+            return CommandFlow::Process(());
+        }
+
+        let flow = match self {
             Command::Cluster(cluster_command) => cluster_command.process_command(ctx).await,
             Command::Stake(stake_command) => stake_command.process_command(ctx).await,
             Command::Account(account_command) => account_command.process_command(ctx).await,
             Command::Vote(vote_command) => vote_command.process_command(ctx).await,
+            Command::Token(token_command) => token_command.process_command(ctx).await,
             Command::Transaction(transaction_command) => {
                 transaction_command.process_command(ctx).await
             }
             Command::ScillaConfig(config_command) => config_command.process_command(ctx),
+            Command::SwitchWallet => {
+                if let Err(e) = config::switch_wallet(ctx) {
+                    print_error(e.to_string());
+                }
+                CommandFlow::Process(())
+            }
+            Command::ToggleReadCommitment => {
+                let commitment = ctx.toggle_read_commitment();
+                println!(
+                    "{}",
+                    theme::success(format!(
+                        "Read commitment is now {:?} for this session",
+                        commitment.commitment
+                    ))
+                );
+                CommandFlow::Process(())
+            }
+            Command::ToggleDryRun => {
+                let state = if ctx.toggle_dry_run() { "on" } else { "off" };
+                println!(
+                    "{}",
+                    theme::success(format!("Dry run is now {state} for this session"))
+                );
+                CommandFlow::Process(())
+            }
             Command::Exit => CommandFlow::Exit,
+        };
+
+        if matches!(flow, CommandFlow::GoBack) {
+            nav.back();
+        }
+
+        flow
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Cluster(command) => write!(f, "{} › {command}", CommandGroup::Cluster),
+            Command::Stake(command) => write!(f, "{} › {command}", CommandGroup::Stake),
+            Command::Account(command) => write!(f, "{} › {command}", CommandGroup::Account),
+            Command::Vote(command) => write!(f, "{} › {command}", CommandGroup::Vote),
+            Command::Token(command) => write!(f, "{} › {command}", CommandGroup::Token),
+            Command::Transaction(command) => {
+                write!(f, "{} › {command}", CommandGroup::Transaction)
+            }
+            Command::ScillaConfig(command) => {
+                write!(f, "{} › {command}", CommandGroup::ScillaConfig)
+            }
+            Command::SwitchWallet => write!(f, "{}", CommandGroup::SwitchWallet),
+            Command::ToggleReadCommitment => write!(f, "{}", CommandGroup::ToggleReadCommitment),
+            Command::ToggleDryRun => write!(f, "{}", CommandGroup::ToggleDryRun),
+            Command::Exit => write!(f, "{}", CommandGroup::Exit),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum CommandGroup {
+    Search,
     Account,
     Cluster,
     Stake,
     Vote,
+    Token,
     Transaction,
     ScillaConfig,
+    SwitchWallet,
+    ToggleReadCommitment,
+    ToggleDryRun,
     Exit,
 }
 
+impl CommandGroup {
+    /// Whether the group opens its own menu rather than acting straight away
+    pub fn has_menu(&self) -> bool {
+        !matches!(
+            self,
+            CommandGroup::Search
+                | CommandGroup::SwitchWallet
+                | CommandGroup::ToggleReadCommitment
+                | CommandGroup::ToggleDryRun
+                | CommandGroup::Exit
+        )
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            CommandGroup::Search => "Type to find any command without opening its group",
+            CommandGroup::Account => "Balances, transfers, airdrops and nonce accounts",
+            CommandGroup::Cluster => "Epoch, slot, supply and validator information",
+            CommandGroup::Stake => "Create, delegate, withdraw and inspect stake accounts",
+            CommandGroup::Vote => "Create and manage validator vote accounts",
+            CommandGroup::Token => "SPL token addresses and accounts",
+            CommandGroup::Transaction => "Inspect, send, sponsor and simulate transactions",
+            CommandGroup::ScillaConfig => "View or edit the Scilla configuration",
+            CommandGroup::SwitchWallet => "Use another wallet from the [wallets] config table",
+            CommandGroup::ToggleReadCommitment => {
+                "Flip lookups between processed (fast) and finalized (safe)"
+            }
+            CommandGroup::ToggleDryRun => "Simulate transactions instead of sending them",
+            CommandGroup::Exit => "Quit Scilla",
+        }
+    }
+}
+
 impl fmt::Display for CommandGroup {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
+            CommandGroup::Search => "Search",
             CommandGroup::Account => "Account",
             CommandGroup::Cluster => "Cluster",
             CommandGroup::Stake => "Stake",
             CommandGroup::Vote => "Vote",
+            CommandGroup::Token => "Token",
             CommandGroup::Transaction => "Transaction",
             CommandGroup::ScillaConfig => "ScillaConfig",
+            CommandGroup::SwitchWallet => "Switch wallet",
+            CommandGroup::ToggleReadCommitment => "Toggle read commitment",
+            CommandGroup::ToggleDryRun => "Toggle dry run",
             CommandGroup::Exit => "Exit",
         };
         write!(f, "{command}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nav_stack_breadcrumb_and_back() {
+        let mut nav = NavStack::default();
+        assert_eq!(nav.breadcrumb(), "Scilla");
+        assert!(nav.current().is_none());
+
+        nav.enter(CommandGroup::Stake);
+        assert_eq!(nav.breadcrumb(), "Scilla › Stake");
+
+        nav.back();
+        assert_eq!(nav.breadcrumb(), "Scilla");
+
+        // backing out of the top menu stays there
+        nav.back();
+        assert!(nav.current().is_none());
+    }
+}
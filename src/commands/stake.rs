@@ -2,37 +2,62 @@ use {
     crate::{
         commands::CommandFlow,
         constants::{
-            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, LAMPORTS_PER_SOL,
-            STAKE_HISTORY_SYSVAR_ADDR,
+            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, DEFAULT_MS_PER_SLOT, LAMPORTS_PER_SOL,
+            MS_PER_YEAR, STAKE_CREATE_FEE_BUFFER_LAMPORTS, STAKE_HISTORY_SYSVAR_ADDR,
+            STAKE_MERGES_PER_TX, STAKE_REWARD_HISTORY_DEFAULT_EPOCHS,
+            STAKE_REWARD_HISTORY_MAX_EPOCHS,
         },
         context::ScillaContext,
-        misc::helpers::{
-            SolAmount, bincode_deserialize, bincode_deserialize_with_limit, build_and_send_tx,
-            check_minimum_balance, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path, sol_to_lamports,
+        misc::{
+            authority::{AuthorityChange, process_authority_change},
+            helpers::{
+                BatchBlockhash, DurableNonce, SolAmount, WithdrawAmount, bincode_deserialize,
+                bincode_deserialize_with_limit, build_and_send_batch_tx, build_and_send_tx,
+                build_and_send_tx_with, build_and_send_tx_with_nonce, check_minimum_balance,
+                estimate_epoch_start, fetch_account_with_epoch, fetch_stake_accounts_by_authority,
+                lamports_to_sol, read_account,
+            },
+            rpc_provider::RpcProvider,
+            signers::SignerSet,
+        },
+        prompt::{
+            confirm_send_summary, prompt_commitment, prompt_confirmation, prompt_durable_nonce,
+            prompt_input_data, prompt_input_data_with_default, prompt_keypair_from_path,
+            prompt_keypair_path, prompt_optional_data, prompt_select_data, prompt_signer_paths,
+            prompt_transfer_amount, prompt_withdraw_amount,
+        },
+        ui::{
+            print_error, print_explorer_link, print_list_table, print_record_table, show_spinner,
+            theme,
         },
-        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
     },
     anyhow::{anyhow, bail},
+    chrono::Utc,
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
     solana_clock::Clock,
-    solana_keypair::Signer,
+    solana_commitment_config::{CommitmentConfig, CommitmentLevel},
+    solana_keypair::{Keypair, Signer},
     solana_pubkey::Pubkey,
     solana_rpc_client_api::{
         config::RpcGetVoteAccountsConfig, request::DELINQUENT_VALIDATOR_SLOT_DISTANCE,
         response::RpcVoteAccountStatus,
     },
     solana_sdk_ids::sysvar::stake_history,
+    solana_signature::Signature,
     solana_stake_interface::{
         instruction::{self, deactivate_stake, merge, withdraw},
         program::id as stake_program_id,
         stake_history::{StakeHistory, StakeHistoryEntry},
-        state::{Authorized, Lockup, Meta, StakeActivationStatus, StakeStateV2},
+        state::{Authorized, Lockup, Meta, StakeActivationStatus, StakeAuthorize, StakeStateV2},
     },
     solana_sysvar::clock,
-    std::{fmt, ops::Div, path::PathBuf},
+    std::{
+        fmt,
+        ops::Div,
+        path::{Path, PathBuf},
+    },
+    tokio::try_join,
 };
 
 /// Commands related to staking operations
@@ -42,10 +67,15 @@ pub enum StakeCommand {
     Delegate,
     Deactivate,
     Withdraw,
+    Authorize,
     Merge,
+    Consolidate,
     Split,
     Show,
     History,
+    Rewards,
+    RewardHistory,
+    EstimateRewards,
     GoBack,
 }
 
@@ -56,13 +86,52 @@ impl StakeCommand {
             StakeCommand::Delegate => "Delegating stake to validator…",
             StakeCommand::Deactivate => "Deactivating stake (cooldown starting)…",
             StakeCommand::Withdraw => "Withdrawing SOL from deactivated stake…",
+            StakeCommand::Authorize => "Changing stake authority…",
             StakeCommand::Merge => "Merging stake accounts…",
+            StakeCommand::Consolidate => "Merging wallet stake accounts…",
             StakeCommand::Split => "Splitting stake into multiple accounts…",
             StakeCommand::Show => "Fetching stake account details…",
             StakeCommand::History => "Fetching stake account history…",
+            StakeCommand::Rewards => "Fetching epoch rewards for wallet stakes…",
+            StakeCommand::RewardHistory => "Fetching rewards of recent epochs…",
+            StakeCommand::EstimateRewards => "Estimating staking rewards…",
             StakeCommand::GoBack => "Going back…",
         }
     }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            StakeCommand::Create => "Fund a new stake account from a fresh keypair",
+            StakeCommand::Delegate => "Delegate a stake account; requires the stake authority",
+            StakeCommand::Deactivate => "Start cooldown; your wallet must be the stake authority",
+            StakeCommand::Withdraw => "Withdraw inactive stake; your wallet must be the withdrawer",
+            StakeCommand::Authorize => "Change the staker or withdrawer after a dry run",
+            StakeCommand::Merge => "Merge a source stake into a destination stake account",
+            StakeCommand::Consolidate => "Merge every compatible stake account of your wallet",
+            StakeCommand::Split => "Move part of a stake into another stake account",
+            StakeCommand::Show => "Show state, authorities and activation of a stake account",
+            StakeCommand::History => "Show cluster-wide stake history for recent epochs",
+            StakeCommand::Rewards => "Show an epoch's rewards for all of your wallet's stakes",
+            StakeCommand::RewardHistory => "Rewards and effective APR of one stake, epoch by epoch",
+            StakeCommand::EstimateRewards => "Project yearly rewards for staking with a validator",
+            StakeCommand::GoBack => "Return to the command groups",
+        }
+    }
+
+    /// Stake changes are all signed by the session keypair as fee payer
+    pub fn requires_signing(&self) -> bool {
+        matches!(
+            self,
+            StakeCommand::Create
+                | StakeCommand::Delegate
+                | StakeCommand::Deactivate
+                | StakeCommand::Withdraw
+                | StakeCommand::Authorize
+                | StakeCommand::Merge
+                | StakeCommand::Consolidate
+                | StakeCommand::Split
+        )
+    }
 }
 
 impl fmt::Display for StakeCommand {
@@ -72,10 +141,15 @@ impl fmt::Display for StakeCommand {
             StakeCommand::Delegate => "Delegate stake",
             StakeCommand::Deactivate => "Deactivate stake",
             StakeCommand::Withdraw => "Withdraw stake",
+            StakeCommand::Authorize => "Change stake authority",
             StakeCommand::Merge => "Merge stake accounts",
+            StakeCommand::Consolidate => "Consolidate stakes",
             StakeCommand::Split => "Split stake account",
             StakeCommand::Show => "Show stake",
             StakeCommand::History => "View stake history",
+            StakeCommand::Rewards => "View wallet stake rewards",
+            StakeCommand::RewardHistory => "Stake reward history",
+            StakeCommand::EstimateRewards => "Estimate staking rewards",
             StakeCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -86,11 +160,39 @@ impl StakeCommand {
     pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
         match self {
             StakeCommand::Create => {
+                let _ = show_spinner(
+                    "Working out how much you can stake…",
+                    show_staking_budget(ctx),
+                )
+                .await;
+
                 let stake_account_keypair_path: PathBuf =
                     prompt_keypair_path("Enter Stake Account Keypair Path: ", ctx);
-                let amount_sol: SolAmount = prompt_input_data("Enter amount to stake (in SOL):");
-                let withdraw_authority_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path: ", ctx);
+                // The new stake account's rent comes out of the wallet too
+                let rent_reserve = ctx
+                    .rpc()
+                    .get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
+                    .await
+                    .unwrap_or_default();
+                let amount_sol = prompt_transfer_amount(
+                    ctx,
+                    "Enter amount to stake (in SOL):",
+                    None,
+                    &[
+                        ("fees", STAKE_CREATE_FEE_BUFFER_LAMPORTS),
+                        ("the stake account's rent", rent_reserve),
+                    ],
+                )
+                .await;
+                // Neither authority signs the creation, so pubkeys are enough
+                let staker: Pubkey = prompt_input_data_with_default(
+                    "Enter Stake Authority Pubkey:",
+                    Some(*ctx.pubkey()),
+                );
+                let withdrawer: Pubkey = prompt_input_data_with_default(
+                    "Enter Withdraw Authority Pubkey:",
+                    Some(*ctx.pubkey()),
+                );
                 let configure_lockup: bool =
                     prompt_input_data("Would you like to set up lockup configuration? (y/n): ");
 
@@ -109,13 +211,13 @@ impl StakeCommand {
                     Lockup::default()
                 };
 
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_create_stake_account(
                         ctx,
                         stake_account_keypair_path,
                         amount_sol,
-                        withdraw_authority_keypair_path,
+                        Authorized { staker, withdrawer },
                         lockup,
                     ),
                 )
@@ -127,50 +229,115 @@ impl StakeCommand {
                 let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Pubkey: ");
                 let stake_authority_keypair_path: PathBuf =
                     prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
-
-                show_spinner(
+                let nonce = prompt_durable_nonce(ctx);
+
+                // The reward estimate is confirmed before sending, so the spinner
+                // only wraps the transaction
+                if let Err(e) = delegate_stake_account(
+                    ctx,
+                    &stake_account_pubkey,
+                    &vote_account_pubkey,
+                    stake_authority_keypair_path,
+                    nonce.as_ref(),
                     self.spinner_msg(),
-                    delegate_stake_account(
-                        ctx,
-                        &stake_account_pubkey,
-                        &vote_account_pubkey,
-                        stake_authority_keypair_path,
-                    ),
                 )
-                .await;
+                .await
+                {
+                    print_error(e.to_string());
+                }
             }
             StakeCommand::Deactivate => {
                 let stake_pubkey: Pubkey =
                     prompt_input_data("Enter Stake Account Pubkey to Deactivate:");
+                let commitment =
+                    prompt_commitment("Wait for commitment:", ctx.rpc().commitment().commitment);
 
                 if !prompt_confirmation("Are you sure you want to deactivate this stake?") {
-                    println!("{}", style("Deactivation cancelled.").yellow());
+                    println!("{}", theme::warning("Deactivation cancelled."));
                     return CommandFlow::Process(());
                 }
 
-                show_spinner(
-                    self.spinner_msg(),
-                    process_deactivate_stake_account(ctx, &stake_pubkey),
-                )
+                let _ = show_spinner(self.spinner_msg(), async {
+                    process_deactivate_stake_account(ctx, &stake_pubkey, commitment)
+                        .await
+                        .map(|result| result.render(ctx))
+                })
                 .await;
             }
             StakeCommand::Withdraw => {
                 let stake_pubkey: Pubkey =
                     prompt_input_data("Enter Stake Account Pubkey to Withdraw from:");
                 let recipient: Pubkey = prompt_input_data("Enter Recipient Address:");
-                let amount: SolAmount = prompt_input_data("Enter Amount to Withdraw (SOL):");
+                let amount = prompt_withdraw_amount();
+                let custodian_keypair_path: Option<PathBuf> = prompt_optional_data(
+                    "Enter Lockup Custodian Keypair Path (leave empty if the stake is not locked \
+                     up):",
+                );
+                let nonce = prompt_durable_nonce(ctx);
+
+                if matches!(amount, WithdrawAmount::Max) {
+                    println!(
+                        "{}",
+                        theme::warning(
+                            "This will close the stake account and return its rent reserve along \
+                             with the balance."
+                        )
+                    );
+                }
 
-                if !prompt_confirmation(&format!(
-                    "Are you sure you want to withdraw {} SOL?",
-                    amount.value()
-                )) {
-                    println!("{}", style("Withdrawal cancelled.").yellow());
-                    return CommandFlow::Process(());
+                let mut rows = vec![
+                    ("From", stake_pubkey.to_string()),
+                    ("To", recipient.to_string()),
+                    ("Amount", amount.to_string()),
+                ];
+                if let Some(nonce) = &nonce {
+                    rows.push(("Durable Nonce", nonce.account.to_string()));
                 }
 
-                show_spinner(
+                if !confirm_send_summary(ctx, "STAKE WITHDRAWAL SUMMARY", &rows) {
+                    println!("{}", theme::warning("Withdrawal cancelled."));
+                    return CommandFlow::GoBack;
+                }
+
+                let _ = show_spinner(self.spinner_msg(), async {
+                    process_withdraw_stake(
+                        ctx,
+                        &stake_pubkey,
+                        &recipient,
+                        amount,
+                        custodian_keypair_path.as_deref(),
+                        nonce.as_ref(),
+                    )
+                    .await
+                    .map(|result| result.render(ctx))
+                })
+                .await;
+            }
+            StakeCommand::Authorize => {
+                let stake_account_pubkey: Pubkey =
+                    prompt_input_data("Enter Stake Account Pubkey: ");
+                let stake_authorize = match prompt_select_data(
+                    "Select Authority to Change:",
+                    vec!["Staker", "Withdrawer"],
+                ) {
+                    "Staker" => StakeAuthorize::Staker,
+                    _ => StakeAuthorize::Withdrawer,
+                };
+                let authority_keypair_path =
+                    prompt_keypair_path("Enter Current Authority Keypair Path: ", ctx);
+                let new_authority_pubkey: Pubkey =
+                    prompt_input_data("Enter New Authority Pubkey: ");
+
+                process_authority_change(
+                    ctx,
                     self.spinner_msg(),
-                    process_withdraw_stake(ctx, &stake_pubkey, &recipient, amount.value()),
+                    prepare_authorize_stake(
+                        ctx,
+                        &stake_account_pubkey,
+                        stake_authorize,
+                        &authority_keypair_path,
+                        &new_authority_pubkey,
+                    ),
                 )
                 .await;
             }
@@ -182,7 +349,7 @@ impl StakeCommand {
                 let stake_authority_keypair_path =
                     prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
 
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_merge_stake(
                         ctx,
@@ -193,21 +360,29 @@ impl StakeCommand {
                 )
                 .await;
             }
+            StakeCommand::Consolidate => {
+                // Confirmation happens between the preview and the merges, so the
+                // spinner only wraps the sending part
+                if let Err(e) = process_consolidate_stakes(ctx, self.spinner_msg()).await {
+                    print_error(e.to_string());
+                }
+            }
             StakeCommand::Split => {
                 let stake_account_pubkey: Pubkey =
                     prompt_input_data("Enter Stake Account Pubkey: ");
-                let split_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Split Stake Account Pubkey: ");
-                let stake_authority_keypair_path =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
-                let amount_to_split: f64 = prompt_input_data("Enter Stake Amount (SOL) to Split: ");
-
-                show_spinner(
+                let [
+                    split_stake_account_keypair_path,
+                    stake_authority_keypair_path,
+                ] = prompt_signer_paths(["New Split Stake Account", "Stake Authority"], ctx);
+                let amount_to_split: SolAmount =
+                    prompt_input_data("Enter Stake Amount (SOL) to Split: ");
+
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_split_stake(
                         ctx,
                         &stake_account_pubkey,
-                        &split_stake_account_pubkey,
+                        &split_stake_account_keypair_path,
                         &stake_authority_keypair_path,
                         amount_to_split,
                     ),
@@ -216,14 +391,50 @@ impl StakeCommand {
             }
             StakeCommand::Show => {
                 let stake_acc_pubkey: Pubkey = prompt_input_data("Enter Stake Account Pubkey:");
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     show_stake_account(ctx, &stake_acc_pubkey),
                 )
                 .await;
             }
             StakeCommand::History => {
-                show_spinner(self.spinner_msg(), process_stake_history(ctx)).await;
+                let _ = show_spinner(self.spinner_msg(), process_stake_history(ctx)).await;
+            }
+            StakeCommand::Rewards => {
+                let epoch: Option<u64> =
+                    prompt_optional_data("Enter Epoch (leave empty for the last completed epoch):");
+
+                let _ = show_spinner(self.spinner_msg(), process_wallet_stake_rewards(ctx, epoch))
+                    .await;
+            }
+            StakeCommand::RewardHistory => {
+                let stake_account_pubkey: Pubkey = prompt_input_data("Enter Stake Account Pubkey:");
+                let epochs = loop {
+                    let epochs: u64 = prompt_input_data_with_default(
+                        "Number of recent epochs:",
+                        Some(STAKE_REWARD_HISTORY_DEFAULT_EPOCHS),
+                    );
+                    match reward_history_epochs_error(epochs) {
+                        Some(error) => print_error(error),
+                        None => break epochs,
+                    }
+                };
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    process_stake_reward_history(ctx, &stake_account_pubkey, epochs),
+                )
+                .await;
+            }
+            StakeCommand::EstimateRewards => {
+                let amount: SolAmount = prompt_input_data("Enter amount to stake (in SOL):");
+                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Pubkey: ");
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    show_reward_estimate(ctx, &vote_account_pubkey, amount.to_lamports()),
+                )
+                .await;
             }
 
             StakeCommand::GoBack => return CommandFlow::GoBack,
@@ -233,16 +444,113 @@ impl StakeCommand {
     }
 }
 
+/// Stake commands that run once from the command line and exit
+#[derive(Debug, Clone, Subcommand)]
+pub enum StakeArgs {
+    /// State, delegation and activation of a stake account
+    Show { pubkey: Pubkey },
+}
+
+impl StakeArgs {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            StakeArgs::Show { pubkey } => show_stake_account(ctx, pubkey).await,
+        }
+    }
+}
+
+/// How much of a balance can go into a new stake account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StakingBudget {
+    balance: u64,
+    fee_buffer: u64,
+    rent_reserve: u64,
+    minimum_delegation: u64,
+    /// Balance left after the fee buffer and the stake account's rent reserve
+    max_stake: u64,
+}
+
+impl StakingBudget {
+    fn new(balance: u64, rent_reserve: u64, minimum_delegation: u64) -> Self {
+        Self {
+            balance,
+            fee_buffer: STAKE_CREATE_FEE_BUFFER_LAMPORTS,
+            rent_reserve,
+            minimum_delegation,
+            max_stake: balance
+                .saturating_sub(STAKE_CREATE_FEE_BUFFER_LAMPORTS)
+                .saturating_sub(rent_reserve),
+        }
+    }
+
+    /// Lamports still missing before the minimum delegation can be staked
+    fn shortfall(&self) -> u64 {
+        self.minimum_delegation.saturating_sub(self.max_stake)
+    }
+}
+
+async fn show_staking_budget(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let (balance, rent_reserve, minimum_delegation) = try_join!(
+        ctx.rpc().get_balance(ctx.pubkey()),
+        ctx.rpc()
+            .get_minimum_balance_for_rent_exemption(StakeStateV2::size_of()),
+        ctx.rpc().get_stake_minimum_delegation(),
+    )?;
+    let budget = StakingBudget::new(balance, rent_reserve, minimum_delegation);
+
+    let sol = |lamports: u64| format!("{} SOL", lamports_to_sol(lamports));
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Wallet Balance"),
+            Cell::new(sol(budget.balance)),
+        ])
+        .add_row(vec![
+            Cell::new("Fee Buffer"),
+            Cell::new(sol(budget.fee_buffer)),
+        ])
+        .add_row(vec![
+            Cell::new("Stake Rent-Exempt Reserve"),
+            Cell::new(sol(budget.rent_reserve)),
+        ])
+        .add_row(vec![
+            Cell::new("Max You Can Stake"),
+            theme::success_cell(sol(budget.max_stake)),
+        ])
+        .add_row(vec![
+            Cell::new("Minimum Delegation"),
+            Cell::new(sol(budget.minimum_delegation)),
+        ]);
+
+    println!("\n{}", theme::success("STAKING BUDGET").bold());
+    println!("{table}");
+
+    if budget.shortfall() > 0 {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "You need {} more to create a stake account that can be delegated",
+                sol(budget.shortfall())
+            ))
+        );
+    }
+
+    Ok(())
+}
+
 async fn process_create_stake_account(
     ctx: &ScillaContext,
     stake_account_keypair_path: PathBuf,
     amount_sol: SolAmount,
-    withdraw_authority_keypair_path: PathBuf,
+    authorized: Authorized,
     lockup: Lockup,
 ) -> anyhow::Result<()> {
-    let stake_account_keypair = read_keypair_from_path(stake_account_keypair_path)?;
-    let withdraw_authority_pubkey =
-        read_keypair_from_path(withdraw_authority_keypair_path)?.pubkey();
+    let stake_account_keypair = prompt_keypair_from_path(stake_account_keypair_path)?;
 
     let lamports = amount_sol.to_lamports();
 
@@ -263,10 +571,18 @@ async fn process_create_stake_account(
         ));
     }
 
-    let authorized = Authorized {
-        staker: *ctx.pubkey(),
-        withdrawer: withdraw_authority_pubkey,
-    };
+    if ctx
+        .rpc()
+        .get_account_with_commitment(&stake_account_keypair.pubkey(), ctx.rpc().commitment())
+        .await?
+        .value
+        .is_some()
+    {
+        bail!(
+            "Account {} already exists. Use a new keypair for the stake account",
+            stake_account_keypair.pubkey()
+        );
+    }
 
     let ix = instruction::create_account(
         ctx.pubkey(),
@@ -276,13 +592,15 @@ async fn process_create_stake_account(
         total_lamports,
     );
 
-    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_account_keypair]).await?;
+    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair()?, &stake_account_keypair]).await?;
 
     println!(
-        "{}\n{}",
-        style("Stake Account created successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
+        "{}\n{}\n{}",
+        theme::warning("Stake Account created successfully!").bold(),
+        theme::info(format!("Stake Account: {}", stake_account_keypair.pubkey())),
+        theme::success(format!("Signature: {signature}"))
     );
+    print_explorer_link(ctx, &signature);
 
     let accounts = ctx
         .rpc()
@@ -319,12 +637,8 @@ async fn process_create_stake_account(
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Stake Account Pubkey"),
@@ -438,7 +752,7 @@ async fn process_create_stake_account(
 
     println!(
         "\n{}",
-        style("NEW STAKE ACCOUNT INFORMATION").green().bold()
+        theme::success("NEW STAKE ACCOUNT INFORMATION").bold()
     );
     println!("{table}");
 
@@ -450,15 +764,47 @@ async fn delegate_stake_account(
     stake_account_pubkey: &Pubkey,
     vote_account_pubkey: &Pubkey,
     stake_authority_keypair_path: PathBuf,
+    nonce: Option<&DurableNonce>,
+    spinner_msg: &str,
 ) -> anyhow::Result<()> {
     let stake_account = ctx.rpc().get_account(stake_account_pubkey).await?;
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+    let stake_authority_keypair = prompt_keypair_from_path(stake_authority_keypair_path)?;
 
     if stake_account.owner != stake_program_id() {
         bail!("Account {} is not a stake account", stake_account_pubkey);
     }
 
+    let delegated_lamports =
+        match bincode_deserialize::<StakeStateV2>(&stake_account.data, "stake account data")? {
+            StakeStateV2::Stake(_, stake, _)
+                if stake.delegation.deactivation_epoch == ACTIVE_STAKE_EPOCH_BOUND =>
+            {
+                bail!(
+                    "Stake account {stake_account_pubkey} is already delegated to {}. Deactivate \
+                     it before delegating elsewhere",
+                    stake.delegation.voter_pubkey
+                )
+            }
+            StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _) => {
+                if meta.authorized.staker != stake_authority_keypair.pubkey() {
+                    bail!(
+                        "{} is not the authorized staker. Authorized staker: {}",
+                        stake_authority_keypair.pubkey(),
+                        meta.authorized.staker
+                    );
+                }
+                stake_account
+                    .lamports
+                    .saturating_sub(meta.rent_exempt_reserve)
+            }
+            _ => bail!("Stake account {stake_account_pubkey} is not initialized"),
+        };
+
+    let vote_account_owner = ctx.rpc().get_account(vote_account_pubkey).await?.owner;
+    if vote_account_owner != solana_vote_interface::program::id() {
+        bail!("Account {vote_account_pubkey} is not a vote account (owner {vote_account_owner})");
+    }
+
     let get_vote_account_config = RpcGetVoteAccountsConfig {
         vote_pubkey: Some(vote_account_pubkey.to_string()),
         commitment: Some(ctx.rpc().commitment()),
@@ -499,20 +845,63 @@ async fn delegate_stake_account(
         );
     }
 
-    let ix = instruction::delegate_stake(
+    match fetch_reward_estimate_inputs(ctx, vote_account_pubkey).await {
+        Ok(inputs) => print_reward_estimate(
+            vote_account_pubkey,
+            &inputs,
+            &estimate_staking_rewards(delegated_lamports, &inputs),
+        ),
+        Err(e) => print_error(format!("Could not estimate rewards: {e}")),
+    }
+
+    if !prompt_confirmation(&format!(
+        "Delegate {} SOL to {vote_account_pubkey}?",
+        lamports_to_sol(delegated_lamports)
+    )) {
+        println!("{}", theme::warning("Delegation cancelled."));
+        return Ok(());
+    }
+
+    let _ = show_spinner(
+        spinner_msg,
+        send_delegation(
+            ctx,
+            stake_account_pubkey,
+            vote_account_pubkey,
+            &stake_authority_keypair,
+            nonce,
+        ),
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn send_delegation(
+    ctx: &ScillaContext,
+    stake_account_pubkey: &Pubkey,
+    vote_account_pubkey: &Pubkey,
+    stake_authority_keypair: &Keypair,
+    nonce: Option<&DurableNonce>,
+) -> anyhow::Result<()> {
+    let ix = [instruction::delegate_stake(
         stake_account_pubkey,
-        &stake_authority_pubkey,
+        &stake_authority_keypair.pubkey(),
         vote_account_pubkey,
-    );
+    )];
+    let signers: [&dyn Signer; 2] = [ctx.keypair()?, stake_authority_keypair];
 
-    let signature =
-        build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let signature = match nonce {
+        Some(nonce) => build_and_send_tx_with_nonce(ctx, &ix, &signers, nonce).await?,
+        None => build_and_send_tx(ctx, &ix, &signers).await?,
+    };
 
     println!(
         "{}\n{}",
-        style("Stake Delegated successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
+        theme::warning("Stake Delegated successfully!").bold(),
+        theme::success(format!("Signature: {signature}"))
     );
+    print_explorer_link(ctx, &signature);
 
     let accounts = ctx
         .rpc()
@@ -546,12 +935,8 @@ async fn delegate_stake_account(
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Stake Account Pubkey"),
@@ -694,16 +1079,238 @@ async fn delegate_stake_account(
 
     println!(
         "\n{}",
-        style("DELEGATE STAKE ACCOUNT INFORMATION").green().bold()
+        theme::success("DELEGATE STAKE ACCOUNT INFORMATION").bold()
+    );
+    println!("{table}");
+    println!(
+        "{}",
+        theme::info(format!(
+            "Stake becomes active next epoch ({}) and may take a few epochs to fully warm up",
+            current_epoch + 1
+        ))
     );
+    Ok(())
+}
+
+/// Outcome of a confirmed stake deactivation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeactivateStakeResult {
+    pub signature: Signature,
+    pub stake_account: Pubkey,
+    pub commitment: CommitmentLevel,
+}
+
+impl DeactivateStakeResult {
+    fn render(&self, ctx: &ScillaContext) {
+        println!(
+            "{} {}\n{}\n{}\n{}",
+            theme::success("Stake Deactivated Successfully!").bold(),
+            theme::warning("(Cooldown will take 1-2 epochs ≈ 2-4 days)"),
+            theme::warning(format!("Stake Account: {}", self.stake_account)),
+            theme::warning(format!("Commitment: {}", self.commitment)),
+            theme::info(format!("Signature: {}", self.signature))
+        );
+        print_explorer_link(ctx, &self.signature);
+    }
+}
+
+/// Cluster and validator figures behind a staking reward estimate
+struct RewardEstimateInputs {
+    /// Annual inflation paid out to stakers, as a fraction of supply
+    validator_inflation: f64,
+    total_supply: u64,
+    total_active_stake: u64,
+    commission: u8,
+    /// Vote credits the validator earned in the last completed epoch
+    validator_credits: u64,
+    /// Stake-weighted mean of the same across all current validators
+    cluster_credits: f64,
+    slots_in_epoch: u64,
+}
+
+#[derive(Debug, PartialEq)]
+struct RewardEstimate {
+    /// Yield before commission for a validator with average vote performance
+    cluster_apr: f64,
+    /// Validator credits relative to the cluster, 1.0 being average
+    performance: f64,
+    net_apr: f64,
+    yearly_lamports: u64,
+    epoch_lamports: u64,
+}
+
+/// Rewards are split by stake times vote credits, so a validator earns the
+/// cluster yield scaled by how its credits compare, minus its commission
+fn estimate_staking_rewards(lamports: u64, inputs: &RewardEstimateInputs) -> RewardEstimate {
+    let cluster_apr = if inputs.total_active_stake == 0 {
+        0.0
+    } else {
+        inputs.validator_inflation * inputs.total_supply as f64 / inputs.total_active_stake as f64
+    };
+    let performance = if inputs.cluster_credits > 0.0 {
+        inputs.validator_credits as f64 / inputs.cluster_credits
+    } else {
+        0.0
+    };
+    let commission = f64::from(inputs.commission.min(100)) / 100.0;
+    let net_apr = cluster_apr * performance * (1.0 - commission);
+
+    let yearly = lamports as f64 * net_apr;
+    let epoch_ms = inputs.slots_in_epoch.saturating_mul(DEFAULT_MS_PER_SLOT) as f64;
+    let epoch = if epoch_ms > 0.0 {
+        yearly * epoch_ms / MS_PER_YEAR
+    } else {
+        0.0
+    };
+
+    RewardEstimate {
+        cluster_apr,
+        performance,
+        net_apr,
+        yearly_lamports: yearly as u64,
+        epoch_lamports: epoch as u64,
+    }
+}
+
+/// Credits a vote account earned during `epoch`, from its `epoch_credits`
+/// entries of `(epoch, credits, previous_credits)`
+fn credits_earned_in(epoch_credits: &[(u64, u64, u64)], epoch: u64) -> u64 {
+    epoch_credits
+        .iter()
+        .find(|(credits_epoch, ..)| *credits_epoch == epoch)
+        .map_or(0, |(_, credits, previous)| {
+            credits.saturating_sub(*previous)
+        })
+}
+
+async fn fetch_reward_estimate_inputs(
+    ctx: &ScillaContext,
+    vote_account_pubkey: &Pubkey,
+) -> anyhow::Result<RewardEstimateInputs> {
+    let (inflation, supply, vote_accounts, epoch_info) = try_join!(
+        ctx.rpc().get_inflation_rate(),
+        ctx.rpc().supply(),
+        ctx.rpc().get_vote_accounts(),
+        ctx.rpc().get_epoch_info(),
+    )?;
+
+    let last_epoch = epoch_info.epoch.saturating_sub(1);
+    let vote_pubkey = vote_account_pubkey.to_string();
+    let validator = vote_accounts
+        .current
+        .iter()
+        .chain(&vote_accounts.delinquent)
+        .find(|account| account.vote_pubkey == vote_pubkey)
+        .ok_or_else(|| anyhow!("Vote account not found: {vote_account_pubkey}"))?;
+
+    let (weighted_credits, current_stake) =
+        vote_accounts
+            .current
+            .iter()
+            .fold((0u128, 0u128), |(weighted, stake), account| {
+                let credits = credits_earned_in(&account.epoch_credits, last_epoch);
+                (
+                    weighted + u128::from(account.activated_stake) * u128::from(credits),
+                    stake + u128::from(account.activated_stake),
+                )
+            });
+    let cluster_credits = if current_stake == 0 {
+        0.0
+    } else {
+        weighted_credits as f64 / current_stake as f64
+    };
+
+    Ok(RewardEstimateInputs {
+        validator_inflation: inflation.validator,
+        total_supply: supply.value.total,
+        total_active_stake: vote_accounts
+            .current
+            .iter()
+            .chain(&vote_accounts.delinquent)
+            .map(|account| account.activated_stake)
+            .sum(),
+        commission: validator.commission,
+        validator_credits: credits_earned_in(&validator.epoch_credits, last_epoch),
+        cluster_credits,
+        slots_in_epoch: epoch_info.slots_in_epoch,
+    })
+}
+
+fn print_reward_estimate(
+    vote_account_pubkey: &Pubkey,
+    inputs: &RewardEstimateInputs,
+    estimate: &RewardEstimate,
+) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Vote Account"),
+            Cell::new(vote_account_pubkey),
+        ])
+        .add_row(vec![
+            Cell::new("Commission"),
+            Cell::new(format!("{}%", inputs.commission)),
+        ])
+        .add_row(vec![
+            Cell::new("Vote Performance"),
+            Cell::new(format!(
+                "{:.1}% of cluster average",
+                estimate.performance * 100.0
+            )),
+        ])
+        .add_row(vec![
+            Cell::new("Cluster Staking APR"),
+            Cell::new(format!("{:.2}%", estimate.cluster_apr * 100.0)),
+        ])
+        .add_row(vec![
+            Cell::new("Estimated Net APR"),
+            Cell::new(format!("{:.2}%", estimate.net_apr * 100.0)),
+        ])
+        .add_row(vec![
+            Cell::new("Estimated Yearly Reward"),
+            Cell::new(format!("{} SOL", lamports_to_sol(estimate.yearly_lamports))),
+        ])
+        .add_row(vec![
+            Cell::new("Estimated Reward per Epoch"),
+            Cell::new(format!("{} SOL", lamports_to_sol(estimate.epoch_lamports))),
+        ]);
+
+    println!("\n{}", theme::success("STAKING REWARD ESTIMATE").bold());
     println!("{table}");
+    println!(
+        "{}",
+        theme::muted(
+            "Assumes today's inflation, total stake, commission and vote performance hold for the \
+             year."
+        )
+    );
+}
+
+async fn show_reward_estimate(
+    ctx: &ScillaContext,
+    vote_account_pubkey: &Pubkey,
+    lamports: u64,
+) -> anyhow::Result<()> {
+    let inputs = fetch_reward_estimate_inputs(ctx, vote_account_pubkey).await?;
+    print_reward_estimate(
+        vote_account_pubkey,
+        &inputs,
+        &estimate_staking_rewards(lamports, &inputs),
+    );
+
     Ok(())
 }
 
 async fn process_deactivate_stake_account(
     ctx: &ScillaContext,
     stake_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
+    commitment: CommitmentLevel,
+) -> anyhow::Result<DeactivateStakeResult> {
     let account = ctx.rpc().get_account(stake_pubkey).await?;
 
     if account.owner != stake_program_id() {
@@ -739,28 +1346,132 @@ async fn process_deactivate_stake_account(
     let authorized_pubkey = ctx.pubkey();
     let instruction = deactivate_stake(stake_pubkey, authorized_pubkey);
 
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+    let signature = build_and_send_tx_with(
+        ctx,
+        &[instruction],
+        &[ctx.keypair()?],
+        ctx.send_pipeline(),
+        CommitmentConfig { commitment },
+    )
+    .await?;
+
+    Ok(DeactivateStakeResult {
+        signature,
+        stake_account: *stake_pubkey,
+        commitment,
+    })
+}
 
-    println!(
-        "{} {}\n{}\n{}",
-        style("Stake Deactivated Successfully!").green().bold(),
-        style("(Cooldown will take 1-2 epochs ≈ 2-4 days)").yellow(),
-        style(format!("Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+/// Outcome of a confirmed withdrawal from a stake account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawStakeResult {
+    pub signature: Signature,
+    pub stake_account: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+    /// Rent reserve returned when the withdrawal emptied and closed the account
+    pub returned_rent_reserve: Option<u64>,
+}
 
-    Ok(())
+impl WithdrawStakeResult {
+    fn render(&self, ctx: &ScillaContext) {
+        println!(
+            "{} {}\n{}\n{}\n{}",
+            theme::success("Stake Withdrawn Successfully!").bold(),
+            theme::warning(format!("From Stake Account: {}", self.stake_account)),
+            theme::warning(format!("To Recipient: {}", self.recipient)),
+            theme::info(format!("Amount: {} SOL", lamports_to_sol(self.lamports))),
+            theme::info(format!("Signature: {}", self.signature))
+        );
+        if let Some(rent_reserve) = self.returned_rent_reserve {
+            println!(
+                "{}",
+                theme::warning(format!(
+                    "Stake account closed; its rent reserve of {} SOL is included in the amount",
+                    lamports_to_sol(rent_reserve)
+                ))
+            );
+        }
+        print_explorer_link(ctx, &self.signature);
+    }
+}
+
+/// Lamports a stake withdrawal moves, and whether it empties the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StakeWithdrawal {
+    lamports: u64,
+    rent_exempt_reserve: u64,
+    closes_account: bool,
 }
 
 async fn process_withdraw_stake(
     ctx: &ScillaContext,
     stake_pubkey: &Pubkey,
     recipient: &Pubkey,
-    amount_sol: f64,
-) -> anyhow::Result<()> {
-    let amount_lamports = sol_to_lamports(amount_sol);
+    amount: WithdrawAmount,
+    custodian_keypair_path: Option<&Path>,
+    nonce: Option<&DurableNonce>,
+) -> anyhow::Result<WithdrawStakeResult> {
+    let mut signers = SignerSet::default();
+    let custodian_pubkey = custodian_keypair_path
+        .map(|path| signers.read(path))
+        .transpose()?;
+
+    let withdrawer_pubkey = ctx.pubkey();
+    let withdrawal = validate_withdraw_stake(
+        ctx.provider(),
+        withdrawer_pubkey,
+        stake_pubkey,
+        recipient,
+        amount,
+        custodian_pubkey.as_ref(),
+    )
+    .await?;
+
+    let instruction = withdraw(
+        stake_pubkey,
+        withdrawer_pubkey,
+        recipient,
+        withdrawal.lamports,
+        custodian_pubkey.as_ref(),
+    );
+
+    let instructions = [instruction];
+    let tx_signers = signers.signers_for(ctx.keypair()?, &instructions)?;
+    let signature = match nonce {
+        Some(nonce) => build_and_send_tx_with_nonce(ctx, &instructions, &tx_signers, nonce).await?,
+        None => build_and_send_tx(ctx, &instructions, &tx_signers).await?,
+    };
+
+    Ok(WithdrawStakeResult {
+        signature,
+        stake_account: *stake_pubkey,
+        recipient: *recipient,
+        lamports: withdrawal.lamports,
+        returned_rent_reserve: withdrawal
+            .closes_account
+            .then_some(withdrawal.rent_exempt_reserve),
+    })
+}
+
+/// Checks that `withdrawer` may withdraw `amount` from the stake account right
+/// now and returns the amount in lamports.
+async fn validate_withdraw_stake(
+    rpc: &dyn RpcProvider,
+    withdrawer: &Pubkey,
+    stake_pubkey: &Pubkey,
+    recipient: &Pubkey,
+    amount: WithdrawAmount,
+    custodian: Option<&Pubkey>,
+) -> anyhow::Result<StakeWithdrawal> {
+    if recipient == stake_pubkey {
+        bail!(
+            "Recipient {recipient} cannot be the stake account being withdrawn from. Enter the \
+             address that should receive the SOL"
+        );
+    }
 
-    let (account, epoch_info) = fetch_account_with_epoch(ctx, stake_pubkey).await?;
+    let (account, epoch_info) = fetch_account_with_epoch(rpc, stake_pubkey).await?;
 
     if account.owner != stake_program_id() {
         bail!("Account is not owned by the stake program");
@@ -768,9 +1479,9 @@ async fn process_withdraw_stake(
 
     let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
 
-    match stake_state {
+    let meta = match stake_state {
         StakeStateV2::Stake(meta, stake, _) => {
-            if &meta.authorized.withdrawer != ctx.pubkey() {
+            if &meta.authorized.withdrawer != withdrawer {
                 bail!(
                     "You are not the authorized withdrawer. Authorized withdrawer: {}",
                     meta.authorized.withdrawer
@@ -786,22 +1497,33 @@ async fn process_withdraw_stake(
 
             if epoch_info.epoch <= stake.delegation.deactivation_epoch {
                 let epochs_remaining = stake.delegation.deactivation_epoch - epoch_info.epoch;
+                let withdrawable_at = estimate_epoch_start(
+                    &epoch_info,
+                    stake.delegation.deactivation_epoch + 1,
+                    Utc::now(),
+                );
                 bail!(
                     "Stake is still cooling down. Current epoch: {}, deactivation epoch: {}, \
-                     epochs remaining: {}",
+                     epochs remaining: {}. Withdrawable from epoch {} (≈ {})",
                     epoch_info.epoch,
                     stake.delegation.deactivation_epoch,
-                    epochs_remaining
+                    epochs_remaining,
+                    stake.delegation.deactivation_epoch + 1,
+                    withdrawable_at.format("%Y-%m-%d %H:%M UTC")
                 );
             }
+
+            meta
         }
         StakeStateV2::Initialized(meta) => {
-            if &meta.authorized.withdrawer != ctx.pubkey() {
+            if &meta.authorized.withdrawer != withdrawer {
                 bail!(
                     "You are not the authorized withdrawer. Authorized withdrawer: {}",
                     meta.authorized.withdrawer
                 );
             }
+
+            meta
         }
         StakeStateV2::Uninitialized => {
             bail!("Stake account is uninitialized");
@@ -809,38 +1531,153 @@ async fn process_withdraw_stake(
         StakeStateV2::RewardsPool => {
             bail!("Cannot withdraw from rewards pool");
         }
+    };
+
+    check_withdraw_lockup(rpc, &meta.lockup, epoch_info.epoch, custodian).await?;
+
+    // percentages apply to what is left above the rent-exempt reserve, while
+    // the maximum empties the account and so also returns the reserve
+    let withdrawable = account.lamports.saturating_sub(meta.rent_exempt_reserve);
+    let amount_lamports = match amount {
+        WithdrawAmount::Max => account.lamports,
+        _ => amount.to_lamports(withdrawable),
+    };
+
+    if amount_lamports == 0 {
+        bail!("Nothing to withdraw: {amount} is 0 SOL");
     }
 
     if amount_lamports > account.lamports {
         bail!(
             "Insufficient balance. Have {:.6} SOL, trying to withdraw {:.6} SOL",
             lamports_to_sol(account.lamports),
-            amount_sol
+            lamports_to_sol(amount_lamports)
         );
     }
 
-    let withdrawer_pubkey = ctx.pubkey();
+    let closes_account = amount_lamports == account.lamports;
 
-    let instruction = withdraw(
-        stake_pubkey,
-        withdrawer_pubkey,
-        recipient,
-        amount_lamports,
-        None,
-    );
+    if !closes_account && amount_lamports > withdrawable {
+        bail!(
+            "Withdrawing {:.6} SOL would dip into the {:.6} SOL rent reserve. Withdraw at most \
+             {:.6} SOL, or choose Maximum to close the account and return the reserve",
+            lamports_to_sol(amount_lamports),
+            lamports_to_sol(meta.rent_exempt_reserve),
+            lamports_to_sol(withdrawable)
+        );
+    }
 
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+    Ok(StakeWithdrawal {
+        lamports: amount_lamports,
+        rent_exempt_reserve: meta.rent_exempt_reserve,
+        closes_account,
+    })
+}
 
-    println!(
-        "{} {}\n{}\n{}\n{}",
-        style("Stake Withdrawn Successfully!").green().bold(),
-        style(format!("From Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("To Recipient: {recipient}")).yellow(),
-        style(format!("Amount: {amount_sol} SOL")).cyan(),
-        style(format!("Signature: {signature}")).cyan()
+/// Fails when the lockup is still in force and `custodian` cannot lift it. The
+/// clock sysvar is only fetched for lockups that carry a timestamp.
+async fn check_withdraw_lockup(
+    rpc: &dyn RpcProvider,
+    lockup: &Lockup,
+    epoch: u64,
+    custodian: Option<&Pubkey>,
+) -> anyhow::Result<()> {
+    let clock = if lockup.unix_timestamp > 0 {
+        let clock_account = rpc.get_account(&clock::id()).await?;
+        bincode_deserialize::<Clock>(&clock_account.data, "clock sysvar")?
+    } else {
+        Clock {
+            epoch,
+            ..Clock::default()
+        }
+    };
+
+    if !lockup.is_in_force(&clock, None) {
+        return Ok(());
+    }
+
+    match custodian {
+        None => bail!(
+            "Stake account is locked up until epoch {} / unix timestamp {}. Provide the custodian \
+             keypair ({}) to withdraw",
+            lockup.epoch,
+            lockup.unix_timestamp,
+            lockup.custodian
+        ),
+        Some(custodian) if *custodian != lockup.custodian => bail!(
+            "{custodian} is not the lockup custodian. Lockup custodian: {}",
+            lockup.custodian
+        ),
+        Some(_) => Ok(()),
+    }
+}
+
+async fn prepare_authorize_stake(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+    stake_authorize: StakeAuthorize,
+    authority_keypair_path: &PathBuf,
+    new_authority_pubkey: &Pubkey,
+) -> anyhow::Result<AuthorityChange> {
+    let authority = prompt_keypair_from_path(authority_keypair_path)?;
+    let authority_pubkey = authority.pubkey();
+
+    let account = ctx.rpc().get_account(stake_pubkey).await?;
+
+    if account.owner != stake_program_id() {
+        bail!("Account is not owned by the stake program");
+    }
+
+    let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+
+    let meta = match stake_state {
+        StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _) => meta,
+        _ => bail!("Stake account has no authorities to change"),
+    };
+
+    // the withdrawer may also reassign the staker, but not the other way round
+    let (authority_type, current) = match stake_authorize {
+        StakeAuthorize::Staker => {
+            if authority_pubkey != meta.authorized.staker
+                && authority_pubkey != meta.authorized.withdrawer
+            {
+                bail!(
+                    "Keypair {} is not the current staker ({}) or withdrawer ({})",
+                    authority_pubkey,
+                    meta.authorized.staker,
+                    meta.authorized.withdrawer
+                );
+            }
+            ("Staker", meta.authorized.staker)
+        }
+        StakeAuthorize::Withdrawer => {
+            if authority_pubkey != meta.authorized.withdrawer {
+                bail!(
+                    "Keypair {} is not the current withdrawer ({})",
+                    authority_pubkey,
+                    meta.authorized.withdrawer
+                );
+            }
+            ("Withdrawer", meta.authorized.withdrawer)
+        }
+    };
+
+    let instruction = instruction::authorize(
+        stake_pubkey,
+        &authority_pubkey,
+        new_authority_pubkey,
+        stake_authorize,
+        None,
     );
 
-    Ok(())
+    Ok(AuthorityChange {
+        account: *stake_pubkey,
+        authority_type,
+        current,
+        new: *new_authority_pubkey,
+        signer: authority,
+        instruction,
+    })
 }
 
 async fn process_merge_stake(
@@ -849,7 +1686,7 @@ async fn process_merge_stake(
     source_stake_account_pubkey: &Pubkey,
     stake_authority_keypair_path: &PathBuf,
 ) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority_keypair = prompt_keypair_from_path(stake_authority_keypair_path)?;
 
     // checks for unique pubkeys
     if destination_stake_account_pubkey == source_stake_account_pubkey {
@@ -860,72 +1697,76 @@ async fn process_merge_stake(
         );
     }
 
-    let stake_accounts = ctx
+    let accounts = ctx
         .rpc()
         .get_multiple_accounts(&[
             *destination_stake_account_pubkey,
             *source_stake_account_pubkey,
+            stake_history::id(),
+            clock::id(),
         ])
         .await?;
 
-    let Some(destination_stake_account) = stake_accounts[0].as_ref() else {
-        bail!("Failed to get stake account");
+    let Some(Some(destination_stake_account)) = accounts.first() else {
+        bail!("Destination stake account {destination_stake_account_pubkey} not found");
     };
 
-    let Some(source_stake_account) = stake_accounts[1].as_ref() else {
-        bail!("Failed to get stake account");
+    let Some(Some(source_stake_account)) = accounts.get(1) else {
+        bail!("Source stake account {source_stake_account_pubkey} not found");
     };
 
-    let destination_stake_state: StakeStateV2 = bincode_deserialize(
-        &destination_stake_account.data,
-        "destination stake account data",
-    )?;
+    let Some(Some(stake_history_account)) = accounts.get(2) else {
+        bail!("Failed to get stake history account");
+    };
 
-    let source_stake_state: StakeStateV2 =
-        bincode_deserialize(&source_stake_account.data, "source stake account data")?;
+    let Some(Some(clock_account)) = accounts.get(3) else {
+        bail!("Failed to get clock account");
+    };
 
-    match &destination_stake_state {
-        StakeStateV2::Initialized(_) => {
-            // Initialized destination is valid
-        }
-        StakeStateV2::Stake(_, _, _) => {
-            // Delegated destination is valid
-        }
-        _ => bail!("Destination stake account is not in a valid state"),
-    }
+    let stake_history: StakeHistory =
+        bincode_deserialize(&stake_history_account.data, "stake history account data")?;
+    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
 
-    match &source_stake_state {
-        StakeStateV2::Initialized(meta) => {
-            // CHECK: Verify authority for initialized source
-            if meta.authorized.staker != stake_authority_keypair.pubkey() {
-                bail!(
-                    "Provided keypair is not the stake authority for source account\nExpected: \
-                     {}\nProvided: {}",
-                    meta.authorized.staker,
-                    stake_authority_keypair.pubkey()
-                );
-            }
+    let mut classes = Vec::with_capacity(2);
+    for (role, pubkey, account) in [
+        (
+            "destination",
+            destination_stake_account_pubkey,
+            destination_stake_account,
+        ),
+        ("source", source_stake_account_pubkey, source_stake_account),
+    ] {
+        if account.owner != stake_program_id() {
+            bail!("The {role} account {pubkey} is not a stake account");
         }
-        StakeStateV2::Stake(meta, stake, _) => {
-            // CHECK: Verify authority for delegated source
-            if meta.authorized.staker != stake_authority_keypair.pubkey() {
-                bail!(
-                    "Provided keypair is not the stake authority for source account\nExpected: \
-                     {}\nProvided: {}",
-                    meta.authorized.staker,
-                    stake_authority_keypair.pubkey()
-                );
-            }
 
-            // CHECK: Source must not be deactivating
-            if stake.delegation.deactivation_epoch != u64::MAX {
-                bail!(
-                    "Cannot merge: source stake account is deactivating at epoch {}",
-                    stake.delegation.deactivation_epoch
-                );
-            }
+        let stake_state: StakeStateV2 =
+            bincode_deserialize(&account.data, &format!("{role} stake account data"))?;
+        let Some(meta) = stake_state.meta() else {
+            bail!("The {role} stake account {pubkey} is not initialized");
+        };
+        if meta.authorized.staker != stake_authority_keypair.pubkey() {
+            bail!(
+                "Provided keypair is not the stake authority for the {role} account\nExpected: \
+                 {}\nProvided: {}",
+                meta.authorized.staker,
+                stake_authority_keypair.pubkey()
+            );
         }
-        _ => bail!("Source stake account is not in a valid state"),
+
+        let Some(class) = MergeClass::classify(&stake_state, clock.epoch, &stake_history) else {
+            bail!(
+                "The {role} stake account {pubkey} is partly activating or deactivating and can't \
+                 be merged until that finishes"
+            );
+        };
+        classes.push(class);
+    }
+
+    if let [destination, source] = classes.as_slice()
+        && let Some(reason) = merge_incompatibility(destination, source, &clock)
+    {
+        bail!("Cannot merge: {reason}");
     }
 
     let stake_authority_pubkey = stake_authority_keypair.pubkey();
@@ -936,30 +1777,312 @@ async fn process_merge_stake(
         &stake_authority_pubkey,
     );
 
-    let signature =
-        build_and_send_tx(ctx, &ixs, &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let signature = build_and_send_tx(ctx, &ixs, &[ctx.keypair()?, &stake_authority_keypair])
+        .await
+        .map_err(|e| anyhow!("The stake program rejected the merge: {e:#}"))?;
+
+    let merged_lamports = ctx
+        .rpc()
+        .get_balance(destination_stake_account_pubkey)
+        .await?;
 
     println!(
         "{}\n{}\n{}\n{}\n{}\n{}",
-        style("Stake Merged successfully!").yellow().bold(),
-        style(format!(
+        theme::warning("Stake Merged successfully!").bold(),
+        theme::warning(format!(
             "Destination Stake Account: {}",
             destination_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!(
+        )),
+        theme::warning(format!(
             "Source Stake Account: {}",
             source_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {}", stake_authority_pubkey)).yellow(),
-        style(format!(
+        )),
+        theme::warning(format!("Stake Authority: {}", stake_authority_pubkey)),
+        theme::info(format!(
             "After Merge: {} SOL",
-            lamports_to_sol(destination_stake_account.lamports)
-        ))
-        .cyan(),
-        style(format!("Signature: {}", signature)).green()
+            lamports_to_sol(merged_lamports)
+        )),
+        theme::success(format!("Signature: {}", signature))
     );
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+/// How the stake program would treat an account when merging, see its
+/// `MergeKind`. Transient stakes have no class and can't be merged this epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeClass {
+    Inactive,
+    Activating(Pubkey),
+    Active(Pubkey),
+}
+
+impl MergeClass {
+    fn classify(
+        stake_state: &StakeStateV2,
+        current_epoch: u64,
+        stake_history: &StakeHistory,
+    ) -> Option<(Meta, Self)> {
+        match stake_state {
+            StakeStateV2::Initialized(meta) => Some((*meta, Self::Inactive)),
+            StakeStateV2::Stake(meta, stake, _) => {
+                let voter = stake.delegation.voter_pubkey;
+                let StakeActivationStatus {
+                    effective,
+                    activating,
+                    deactivating,
+                } = stake.delegation.stake_activating_and_deactivating(
+                    current_epoch,
+                    stake_history,
+                    None,
+                );
+
+                match (effective, activating, deactivating) {
+                    (0, 0, 0) => Some((*meta, Self::Inactive)),
+                    (0, _, _) => Some((*meta, Self::Activating(voter))),
+                    (_, 0, 0) => Some((*meta, Self::Active(voter))),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn voter(&self) -> Option<Pubkey> {
+        match self {
+            Self::Inactive => None,
+            Self::Activating(voter) | Self::Active(voter) => Some(*voter),
+        }
+    }
+}
+
+impl fmt::Display for MergeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            Self::Inactive => "Inactive",
+            Self::Activating(_) => "Activating",
+            Self::Active(_) => "Active",
+        };
+        write!(f, "{state}")
+    }
+}
+
+/// Why the stake program would refuse to merge `source` into `destination`,
+/// following its `MergeKind` rules: matching authorities, matching lockups
+/// unless neither is in force at `clock`, then inactive/activating stakes
+/// together or active stakes on the same validator
+fn merge_incompatibility(
+    (destination_meta, destination): &(Meta, MergeClass),
+    (source_meta, source): &(Meta, MergeClass),
+    clock: &Clock,
+) -> Option<String> {
+    if destination_meta.authorized != source_meta.authorized {
+        return Some("the accounts have different stake or withdraw authorities".to_string());
+    }
+    if lockup_in_force(&destination_meta.lockup, clock)
+        != lockup_in_force(&source_meta.lockup, clock)
+    {
+        return Some("the accounts have different lockups".to_string());
+    }
+
+    let compatible = match (destination, source) {
+        (MergeClass::Inactive, MergeClass::Inactive | MergeClass::Activating(_))
+        | (MergeClass::Activating(_), MergeClass::Inactive) => true,
+        (MergeClass::Activating(a), MergeClass::Activating(b))
+        | (MergeClass::Active(a), MergeClass::Active(b)) => a == b,
+        _ => false,
+    };
+    if !compatible {
+        let describe = |class: &MergeClass| match class.voter() {
+            Some(voter) => format!("{} on {voter}", class.to_string().to_lowercase()),
+            None => class.to_string().to_lowercase(),
+        };
+        return Some(format!(
+            "the destination is {} but the source is {}. Both must be inactive, or active on the \
+             same vote account",
+            describe(destination),
+            describe(source)
+        ));
+    }
+
+    None
+}
+
+/// `lockup` while it is in force at `clock`. The stake program merges
+/// accounts whose lockups have both expired even when they differ.
+fn lockup_in_force(lockup: &Lockup, clock: &Clock) -> Option<Lockup> {
+    lockup.is_in_force(clock, None).then_some(*lockup)
+}
+
+/// Stake accounts that can all be merged into the largest one
+struct MergeGroup {
+    class: MergeClass,
+    destination: (Pubkey, u64),
+    sources: Vec<(Pubkey, u64)>,
+}
+
+async fn process_consolidate_stakes(ctx: &ScillaContext, spinner_msg: &str) -> anyhow::Result<()> {
+    let stake_accounts = fetch_stake_accounts_by_authority(ctx, ctx.pubkey()).await?;
+
+    let sysvars = ctx
+        .rpc()
+        .get_multiple_accounts(&[stake_history::id(), clock::id()])
+        .await?;
+
+    let Some(Some(stake_history_account)) = sysvars.first() else {
+        bail!("Failed to get stake history account");
+    };
+
+    let Some(Some(clock_account)) = sysvars.get(1) else {
+        bail!("Failed to get clock account");
+    };
+
+    let stake_history: StakeHistory =
+        bincode_deserialize(&stake_history_account.data, "stake history account data")?;
+    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
+
+    // accounts can only merge when authorities match, and lockups too while
+    // either is in force
+    let mut candidates: Vec<(Authorized, Option<Lockup>, MergeClass, Vec<(Pubkey, u64)>)> =
+        Vec::new();
+
+    for (pubkey, account) in stake_accounts {
+        let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+
+        let Some((meta, class)) = MergeClass::classify(&stake_state, clock.epoch, &stake_history)
+        else {
+            continue;
+        };
+
+        // merging needs the stake authority, which is the wallet here
+        if &meta.authorized.staker != ctx.pubkey() {
+            continue;
+        }
+
+        let lockup = lockup_in_force(&meta.lockup, &clock);
+        match candidates
+            .iter_mut()
+            .find(|(authorized, candidate_lockup, candidate_class, _)| {
+                *authorized == meta.authorized
+                    && *candidate_lockup == lockup
+                    && *candidate_class == class
+            }) {
+            Some((_, _, _, accounts)) => accounts.push((pubkey, account.lamports)),
+            None => candidates.push((
+                meta.authorized,
+                lockup,
+                class,
+                vec![(pubkey, account.lamports)],
+            )),
+        }
+    }
+
+    let groups: Vec<MergeGroup> = candidates
+        .into_iter()
+        .filter(|(_, _, _, accounts)| accounts.len() > 1)
+        .map(|(_, _, class, mut accounts)| {
+            accounts.sort_by(|a, b| b.1.cmp(&a.1));
+            let destination = accounts.remove(0);
+            MergeGroup {
+                class,
+                destination,
+                sources: accounts,
+            }
+        })
+        .collect();
+
+    if groups.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "No mergeable stake accounts found for {}",
+                ctx.pubkey()
+            ))
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Destination"),
+        theme::bold_cell("State"),
+        theme::bold_cell("Vote Account"),
+        theme::bold_cell("Merged Accounts"),
+        theme::bold_cell("Balance After (SOL)"),
+    ]);
+
+    for group in &groups {
+        let total: u64 = group
+            .sources
+            .iter()
+            .fold(group.destination.1, |total, (_, lamports)| {
+                total.saturating_add(*lamports)
+            });
+
+        table.add_row(vec![
+            Cell::new(group.destination.0),
+            Cell::new(group.class),
+            Cell::new(
+                group
+                    .class
+                    .voter()
+                    .map(|voter| voter.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::new(
+                group
+                    .sources
+                    .iter()
+                    .map(|(pubkey, _)| pubkey.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Cell::new(format!("{:.9}", lamports_to_sol(total))),
+        ]);
+    }
+
+    let merge_count: usize = groups.iter().map(|group| group.sources.len()).sum();
+
+    println!("\n{}", theme::success("STAKE CONSOLIDATION PREVIEW").bold());
+    println!("{}", table);
+
+    if !prompt_confirmation(&format!(
+        "Merge {merge_count} stake account(s) into {} destination(s)?",
+        groups.len()
+    )) {
+        println!("{}", theme::warning("Consolidation cancelled."));
+        return Ok(());
+    }
+
+    let _ = show_spinner(spinner_msg, send_stake_merges(ctx, &groups)).await;
+
+    Ok(())
+}
+
+async fn send_stake_merges(ctx: &ScillaContext, groups: &[MergeGroup]) -> anyhow::Result<()> {
+    let instructions: Vec<_> = groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .sources
+                .iter()
+                .flat_map(move |(source, _)| merge(&group.destination.0, source, ctx.pubkey()))
+        })
+        .collect();
+
+    let mut blockhash = BatchBlockhash::new();
+    for batch in instructions.chunks(STAKE_MERGES_PER_TX) {
+        let signature =
+            build_and_send_batch_tx(ctx, &mut blockhash, batch, &[ctx.keypair()?]).await?;
+
+        println!(
+            "{} {}",
+            theme::success(format!("Merged {} stake account(s)!", batch.len())).bold(),
+            theme::info(format!("Signature: {signature}"))
+        );
+        print_explorer_link(ctx, &signature);
+    }
 
     Ok(())
 }
@@ -967,13 +2090,14 @@ async fn process_merge_stake(
 async fn process_split_stake(
     ctx: &ScillaContext,
     stake_account_pubkey: &Pubkey,
-    split_stake_account_pubkey: &Pubkey,
+    split_stake_account_keypair_path: &PathBuf,
     stake_authority_keypair_path: &PathBuf,
-    amount_to_split: f64,
+    amount_to_split: SolAmount,
 ) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
-    let lamports: u64 = sol_to_lamports(amount_to_split);
+    let mut signers = SignerSet::default();
+    let split_stake_account_pubkey = &signers.read(split_stake_account_keypair_path)?;
+    let stake_authority_pubkey = signers.read(stake_authority_keypair_path)?;
+    let lamports = amount_to_split.to_lamports();
 
     if stake_account_pubkey == split_stake_account_pubkey {
         bail!(
@@ -983,6 +2107,23 @@ async fn process_split_stake(
         );
     }
 
+    let source = ctx.rpc().get_account(stake_account_pubkey).await?;
+    if source.owner != stake_program_id() {
+        bail!("Account {stake_account_pubkey} is not a stake account");
+    }
+
+    let meta = match bincode_deserialize::<StakeStateV2>(&source.data, "stake account data")? {
+        StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _) => meta,
+        _ => bail!("Stake account {stake_account_pubkey} is not initialized"),
+    };
+    if meta.authorized.staker != stake_authority_pubkey {
+        bail!(
+            "{stake_authority_pubkey} is not the authorized staker. Authorized staker: {}",
+            meta.authorized.staker
+        );
+    }
+    check_split_remainder(source.lamports, lamports, meta.rent_exempt_reserve)?;
+
     let stake_minimum_delegation = ctx.rpc().get_stake_minimum_delegation().await?;
 
     if lamports < stake_minimum_delegation {
@@ -1000,20 +2141,46 @@ async fn process_split_stake(
         split_stake_account_pubkey,
     );
 
-    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let signature = build_and_send_tx(ctx, &ix, &signers.signers_for(ctx.keypair()?, &ix)?).await?;
 
     println!(
         "{}\n{}\n{}\n{}\n{}",
-        style("Split Stake successfully!").yellow().bold(),
-        style(format!("Stake Account: {}", stake_account_pubkey)).yellow(),
-        style(format!(
+        theme::warning("Split Stake successfully!").bold(),
+        theme::warning(format!("Stake Account: {}", stake_account_pubkey)),
+        theme::warning(format!(
             "Split Stake Account: {}",
             split_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {}", stake_authority_pubkey)).yellow(),
-        style(format!("Signature: {}", signature)).green()
+        )),
+        theme::warning(format!("Stake Authority: {}", stake_authority_pubkey)),
+        theme::success(format!("Signature: {}", signature))
     );
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+/// Checks that splitting `split` lamports off a stake account holding
+/// `balance` either empties it or leaves at least its rent-exempt reserve
+fn check_split_remainder(balance: u64, split: u64, rent_exempt_reserve: u64) -> anyhow::Result<()> {
+    let Some(remaining) = balance.checked_sub(split) else {
+        bail!(
+            "Cannot split {} SOL from a stake account holding {} SOL",
+            lamports_to_sol(split),
+            lamports_to_sol(balance)
+        );
+    };
+
+    if remaining != 0 && remaining < rent_exempt_reserve {
+        bail!(
+            "Splitting {} SOL would leave {} SOL, below the {} SOL rent-exempt reserve. Split at \
+             most {} SOL, or all {} SOL",
+            lamports_to_sol(split),
+            lamports_to_sol(remaining),
+            lamports_to_sol(rent_exempt_reserve),
+            lamports_to_sol(balance.saturating_sub(rent_exempt_reserve)),
+            lamports_to_sol(balance)
+        );
+    }
 
     Ok(())
 }
@@ -1027,16 +2194,16 @@ async fn process_stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
         bincode_deserialize_with_limit(account.data.len() as u64, &account.data, "stake history")?;
 
     if stake_history.is_empty() {
-        println!("{}", style("No stake history available").yellow());
+        println!("{}", theme::warning("No stake history available"));
         return Ok(());
     }
 
     let mut table = Table::new();
     table.load_preset(UTF8_FULL).set_header(vec![
-        Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Effective Stake").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Activating Stake").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Deactivating Stake").add_attribute(comfy_table::Attribute::Bold),
+        theme::bold_cell("Epoch"),
+        theme::bold_cell("Effective Stake"),
+        theme::bold_cell("Activating Stake"),
+        theme::bold_cell("Deactivating Stake"),
     ]);
 
     for (epoch, entry) in stake_history.iter().take(DEFAULT_EPOCH_LIMIT) {
@@ -1054,8 +2221,181 @@ async fn process_stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
         ]);
     }
 
-    println!("\n{}", style("CLUSTER STAKE HISTORY").green().bold());
-    println!("{}", table);
+    print_list_table(ctx, "CLUSTER STAKE HISTORY", &table);
+
+    Ok(())
+}
+
+async fn process_wallet_stake_rewards(
+    ctx: &ScillaContext,
+    epoch: Option<u64>,
+) -> anyhow::Result<()> {
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => ctx.rpc().get_epoch_info().await?.epoch.saturating_sub(1),
+    };
+
+    let stake_accounts = fetch_stake_accounts_by_authority(ctx, ctx.pubkey()).await?;
+
+    if stake_accounts.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!("No stake accounts found for {}", ctx.pubkey()))
+        );
+        return Ok(());
+    }
+
+    let stake_pubkeys: Vec<Pubkey> = stake_accounts.iter().map(|(pubkey, _)| *pubkey).collect();
+    let rewards = ctx
+        .rpc()
+        .get_inflation_reward(&stake_pubkeys, Some(epoch))
+        .await?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Stake Account"),
+        theme::bold_cell("Vote Account"),
+        theme::bold_cell("Reward (SOL)"),
+        theme::bold_cell("Post Balance (SOL)"),
+    ]);
+
+    let mut total_reward: u64 = 0;
+
+    for ((stake_pubkey, stake_account), reward) in stake_accounts.iter().zip(rewards) {
+        let vote_account =
+            match bincode_deserialize::<StakeStateV2>(&stake_account.data, "stake account data")? {
+                StakeStateV2::Stake(_, stake, _) => stake.delegation.voter_pubkey.to_string(),
+                _ => "-".to_string(),
+            };
+
+        let (reward_cell, post_balance_cell) = match reward {
+            Some(reward) => {
+                total_reward = total_reward.saturating_add(reward.amount);
+                (
+                    format!("{:.9}", lamports_to_sol(reward.amount)),
+                    format!("{:.9}", lamports_to_sol(reward.post_balance)),
+                )
+            }
+            None => ("-".to_string(), "-".to_string()),
+        };
+
+        table.add_row(vec![
+            Cell::new(stake_pubkey),
+            Cell::new(vote_account),
+            Cell::new(reward_cell),
+            Cell::new(post_balance_cell),
+        ]);
+    }
+
+    table.add_row(vec![
+        theme::bold_cell("Total"),
+        Cell::new(""),
+        theme::bold_cell(format!("{:.9}", lamports_to_sol(total_reward))),
+        Cell::new(""),
+    ]);
+
+    print_list_table(ctx, &format!("STAKE REWARDS FOR EPOCH {epoch}"), &table);
+
+    Ok(())
+}
+
+/// Yearly rate an epoch's reward amounts to, without compounding, or `None`
+/// when there was no balance before the reward to measure it against
+fn epoch_reward_apr(reward: u64, post_balance: u64, slots_in_epoch: u64) -> Option<f64> {
+    let pre_balance = post_balance
+        .checked_sub(reward)
+        .filter(|balance| *balance > 0)?;
+    let epoch_ms = slots_in_epoch.saturating_mul(DEFAULT_MS_PER_SLOT) as f64;
+    if epoch_ms == 0.0 {
+        return None;
+    }
+
+    Some(reward as f64 / pre_balance as f64 * MS_PER_YEAR / epoch_ms)
+}
+
+/// Why `epochs` is not a lookback the reward history accepts, if it isn't.
+/// Each epoch is one RPC call, so the lookback is capped.
+fn reward_history_epochs_error(epochs: u64) -> Option<String> {
+    (!(1..=STAKE_REWARD_HISTORY_MAX_EPOCHS).contains(&epochs))
+        .then(|| format!("Enter between 1 and {STAKE_REWARD_HISTORY_MAX_EPOCHS} epochs"))
+}
+
+/// Inflation rewards of one stake account over the last `epochs` completed
+/// epochs, oldest first; epochs without a reward keep their row
+async fn process_stake_reward_history(
+    ctx: &ScillaContext,
+    stake_account_pubkey: &Pubkey,
+    epochs: u64,
+) -> anyhow::Result<()> {
+    if let Some(error) = reward_history_epochs_error(epochs) {
+        bail!(error);
+    }
+
+    let account = read_account(ctx, stake_account_pubkey).await?;
+    if account.owner != stake_program_id() {
+        bail!("Account {stake_account_pubkey} is not a stake account");
+    }
+
+    let epoch_info = ctx.rpc().get_epoch_info().await?;
+    let last_epoch = epoch_info.epoch.saturating_sub(1);
+    let first_epoch = last_epoch.saturating_sub(epochs - 1);
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Epoch"),
+        theme::bold_cell("Reward (SOL)"),
+        theme::bold_cell("Post Balance (SOL)"),
+        theme::bold_cell("Effective APR"),
+    ]);
+
+    let mut total_reward: u64 = 0;
+
+    for epoch in first_epoch..=last_epoch {
+        let reward = ctx
+            .rpc()
+            .get_inflation_reward(&[*stake_account_pubkey], Some(epoch))
+            .await?
+            .into_iter()
+            .next()
+            .flatten();
+
+        let row = match reward {
+            Some(reward) => {
+                total_reward = total_reward.saturating_add(reward.amount);
+                [
+                    format!("{:.9}", lamports_to_sol(reward.amount)),
+                    format!("{:.9}", lamports_to_sol(reward.post_balance)),
+                    epoch_reward_apr(
+                        reward.amount,
+                        reward.post_balance,
+                        epoch_info.slots_in_epoch,
+                    )
+                    .map(|apr| format!("{:.2}%", apr * 100.0))
+                    .unwrap_or_else(|| "-".to_string()),
+                ]
+            }
+            None => ["-".to_string(), "-".to_string(), "-".to_string()],
+        };
+
+        table.add_row(
+            std::iter::once(Cell::new(epoch))
+                .chain(row.into_iter().map(Cell::new))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    table.add_row(vec![
+        theme::bold_cell("Total"),
+        theme::bold_cell(format!("{:.9}", lamports_to_sol(total_reward))),
+        Cell::new(""),
+        Cell::new(""),
+    ]);
+
+    print_list_table(
+        ctx,
+        &format!("STAKE REWARDS FOR {stake_account_pubkey}"),
+        &table,
+    );
 
     Ok(())
 }
@@ -1063,13 +2403,21 @@ async fn process_stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
 async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
     let accounts = ctx
         .rpc()
-        .get_multiple_accounts(&[*pubkey, stake_history::id(), clock::id()])
-        .await?;
+        .get_multiple_accounts_with_commitment(
+            &[*pubkey, stake_history::id(), clock::id()],
+            ctx.read_commitment(),
+        )
+        .await?
+        .value;
 
     let Some(Some(stake_account)) = accounts.first() else {
-        anyhow::bail!("Failed to get stake account");
+        anyhow::bail!("Account {pubkey} not found");
     };
 
+    if stake_account.owner != stake_program_id() {
+        bail!("Account {pubkey} is not a stake account");
+    }
+
     let Some(Some(stake_history_account)) = accounts.get(1) else {
         anyhow::bail!("Failed to get stake history account");
     };
@@ -1090,10 +2438,7 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
+        .set_header(vec![theme::bold_cell("Field"), theme::bold_cell("Value")])
         .add_row(vec![Cell::new("Stake Account Pubkey"), Cell::new(pubkey)])
         .add_row(vec![
             Cell::new("Account Balance (SOL)"),
@@ -1192,11 +2537,14 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
                 ])
                 .add_row(vec![
                     Cell::new("Deactivation Epoch"),
-                    Cell::new(if stake.delegation.deactivation_epoch < u64::MAX {
-                        stake.delegation.deactivation_epoch.to_string()
-                    } else {
-                        "N/A".to_string()
-                    }),
+                    // ~ while the stake is not deactivating
+                    Cell::new(
+                        if stake.delegation.deactivation_epoch == ACTIVE_STAKE_EPOCH_BOUND {
+                            "~".to_string()
+                        } else {
+                            stake.delegation.deactivation_epoch.to_string()
+                        },
+                    ),
                 ])
                 .add_row(vec![
                     Cell::new("Active Stake (SOL)"),
@@ -1233,8 +2581,382 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
         }
     }
 
-    println!("{}\n", style("STAKE ACCOUNT INFORMATION").green().bold());
-    println!("{table}");
+    print_record_table(ctx, "STAKE ACCOUNT INFORMATION", &table);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::misc::rpc_provider::MockRpcProvider,
+        solana_account::Account,
+        solana_stake_interface::{
+            stake_flags::StakeFlags,
+            state::{Delegation, Stake},
+        },
+    };
+
+    const RENT_EXEMPT_RESERVE: u64 = 2_282_880;
+
+    #[test]
+    fn test_epoch_reward_apr() {
+        // 432,000 slots of 400ms is 2 days, so ~182.6 epochs a year
+        let apr = epoch_reward_apr(1_000_000, 1_001_000_000, 432_000).unwrap();
+        assert!((apr - 0.1826).abs() < 0.0001, "{apr}");
+
+        assert_eq!(epoch_reward_apr(5, 5, 432_000), None);
+        assert_eq!(epoch_reward_apr(1, 10, 0), None);
+    }
+
+    #[test]
+    fn test_merge_incompatibility() {
+        let meta = Meta {
+            rent_exempt_reserve: RENT_EXEMPT_RESERVE,
+            authorized: Authorized::auto(&Pubkey::new_unique()),
+            lockup: Lockup::default(),
+        };
+        let voter = Pubkey::new_unique();
+        let clock = Clock {
+            epoch: 10,
+            ..Clock::default()
+        };
+
+        assert_eq!(
+            merge_incompatibility(
+                &(meta, MergeClass::Inactive),
+                &(meta, MergeClass::Activating(voter)),
+                &clock
+            ),
+            None
+        );
+        assert_eq!(
+            merge_incompatibility(
+                &(meta, MergeClass::Active(voter)),
+                &(meta, MergeClass::Active(voter)),
+                &clock
+            ),
+            None
+        );
+
+        let reason = merge_incompatibility(
+            &(meta, MergeClass::Active(voter)),
+            &(meta, MergeClass::Active(Pubkey::new_unique())),
+            &clock,
+        )
+        .unwrap();
+        assert!(reason.contains("same vote account"));
+
+        let other_authority = Meta {
+            authorized: Authorized::auto(&Pubkey::new_unique()),
+            ..meta
+        };
+        assert!(
+            merge_incompatibility(
+                &(meta, MergeClass::Inactive),
+                &(other_authority, MergeClass::Inactive),
+                &clock
+            )
+            .unwrap()
+            .contains("authorities")
+        );
+
+        let locked_until = |epoch| Meta {
+            lockup: Lockup {
+                unix_timestamp: 0,
+                epoch,
+                custodian: Pubkey::new_unique(),
+            },
+            ..meta
+        };
+        assert!(
+            merge_incompatibility(
+                &(locked_until(20), MergeClass::Inactive),
+                &(meta, MergeClass::Inactive),
+                &clock
+            )
+            .unwrap()
+            .contains("lockups")
+        );
+        assert_eq!(
+            merge_incompatibility(
+                &(locked_until(5), MergeClass::Inactive),
+                &(locked_until(8), MergeClass::Inactive),
+                &clock
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_split_remainder() {
+        let balance = 3 * LAMPORTS_PER_SOL;
+
+        assert!(check_split_remainder(balance, LAMPORTS_PER_SOL, RENT_EXEMPT_RESERVE).is_ok());
+        assert!(check_split_remainder(balance, balance, RENT_EXEMPT_RESERVE).is_ok());
+        assert!(
+            check_split_remainder(balance, balance - RENT_EXEMPT_RESERVE, RENT_EXEMPT_RESERVE)
+                .is_ok()
+        );
+
+        let err = check_split_remainder(balance, balance - 1, RENT_EXEMPT_RESERVE)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("rent-exempt reserve"));
+        assert!(check_split_remainder(balance, balance + 1, RENT_EXEMPT_RESERVE).is_err());
+    }
+
+    #[test]
+    fn test_staking_budget() {
+        let minimum_delegation = LAMPORTS_PER_SOL;
+
+        let budget = StakingBudget::new(
+            5 * LAMPORTS_PER_SOL,
+            RENT_EXEMPT_RESERVE,
+            minimum_delegation,
+        );
+        assert_eq!(
+            budget.max_stake,
+            5 * LAMPORTS_PER_SOL - STAKE_CREATE_FEE_BUFFER_LAMPORTS - RENT_EXEMPT_RESERVE
+        );
+        assert_eq!(budget.shortfall(), 0);
+
+        let poor = StakingBudget::new(RENT_EXEMPT_RESERVE, RENT_EXEMPT_RESERVE, minimum_delegation);
+        assert_eq!(poor.max_stake, 0);
+        assert_eq!(poor.shortfall(), minimum_delegation);
+    }
+
+    fn stake_account(state: &StakeStateV2, lamports: u64) -> Account {
+        let mut data = vec![0; StakeStateV2::size_of()];
+        bincode::serialize_into(&mut data[..], state).expect("stake state should serialize");
+        Account {
+            lamports,
+            data,
+            owner: stake_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn delegated_stake(withdrawer: Pubkey, deactivation_epoch: u64) -> StakeStateV2 {
+        let meta = Meta {
+            rent_exempt_reserve: RENT_EXEMPT_RESERVE,
+            authorized: Authorized {
+                staker: withdrawer,
+                withdrawer,
+            },
+            lockup: Lockup::default(),
+        };
+        let mut delegation = Delegation::new(&Pubkey::new_unique(), LAMPORTS_PER_SOL, 0);
+        delegation.deactivation_epoch = deactivation_epoch;
+
+        StakeStateV2::Stake(
+            meta,
+            Stake {
+                delegation,
+                credits_observed: 0,
+            },
+            StakeFlags::empty(),
+        )
+    }
+
+    fn locked_up_stake(withdrawer: Pubkey, custodian: Pubkey, lockup_epoch: u64) -> StakeStateV2 {
+        let StakeStateV2::Stake(mut meta, stake, flags) = delegated_stake(withdrawer, 5) else {
+            unreachable!("delegated_stake builds a delegated state");
+        };
+        meta.lockup = Lockup {
+            unix_timestamp: 0,
+            epoch: lockup_epoch,
+            custodian,
+        };
+        StakeStateV2::Stake(meta, stake, flags)
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_rejects_account_not_owned_by_stake_program() {
+        let withdrawer = Pubkey::new_unique();
+        let stake_pubkey = Pubkey::new_unique();
+        let mut account = stake_account(&delegated_stake(withdrawer, 5), LAMPORTS_PER_SOL);
+        account.owner = Pubkey::new_unique();
+        let rpc = MockRpcProvider::default()
+            .with_account(stake_pubkey, account)
+            .at_epoch(10);
+
+        let err = validate_withdraw_stake(
+            &rpc,
+            &withdrawer,
+            &stake_pubkey,
+            &Pubkey::new_unique(),
+            WithdrawAmount::Sol("0.5".parse().unwrap()),
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("not owned by the stake program"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_withdraw_stake() {
+        let withdrawer = Pubkey::new_unique();
+        let custodian = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let sol = |amount: &str| WithdrawAmount::Sol(amount.parse().unwrap());
+
+        // deactivated at epoch 5 and the cluster at epoch 10, so withdrawable
+        let deactivated = delegated_stake(withdrawer, 5);
+        let cooling_down = delegated_stake(withdrawer, 10);
+        let locked_up = locked_up_stake(withdrawer, custodian, 20);
+        let lockup_expired = locked_up_stake(withdrawer, Pubkey::new_unique(), 8);
+
+        // (state, signer, amount, custodian) -> (lamports, closes account) or
+        // part of the error
+        let cases: Vec<(_, _, _, _, Result<(u64, bool), &str>)> = vec![
+            (
+                &deactivated,
+                stranger,
+                sol("0.5"),
+                None,
+                Err("not the authorized withdrawer"),
+            ),
+            (
+                &cooling_down,
+                withdrawer,
+                sol("0.5"),
+                None,
+                Err("Withdrawable from epoch 11"),
+            ),
+            (
+                &deactivated,
+                withdrawer,
+                sol("5"),
+                None,
+                Err("Insufficient balance"),
+            ),
+            (
+                &deactivated,
+                withdrawer,
+                sol("0.999"),
+                None,
+                Err("rent reserve"),
+            ),
+            (
+                &deactivated,
+                withdrawer,
+                WithdrawAmount::Percentage("100".parse().unwrap()),
+                None,
+                Ok((LAMPORTS_PER_SOL - RENT_EXEMPT_RESERVE, false)),
+            ),
+            (
+                &deactivated,
+                withdrawer,
+                WithdrawAmount::Max,
+                None,
+                Ok((LAMPORTS_PER_SOL, true)),
+            ),
+            (
+                &locked_up,
+                withdrawer,
+                sol("0.5"),
+                None,
+                Err("locked up until epoch 20"),
+            ),
+            (
+                &locked_up,
+                withdrawer,
+                sol("0.5"),
+                Some(stranger),
+                Err("not the lockup custodian"),
+            ),
+            (
+                &locked_up,
+                withdrawer,
+                sol("0.5"),
+                Some(custodian),
+                Ok((LAMPORTS_PER_SOL / 2, false)),
+            ),
+            (
+                &lockup_expired,
+                withdrawer,
+                sol("0.5"),
+                None,
+                Ok((LAMPORTS_PER_SOL / 2, false)),
+            ),
+        ];
+
+        for (i, (state, signer, amount, custodian, expected)) in cases.into_iter().enumerate() {
+            let stake_pubkey = Pubkey::new_unique();
+            let rpc = MockRpcProvider::default()
+                .with_account(stake_pubkey, stake_account(state, LAMPORTS_PER_SOL))
+                .at_epoch(10);
+
+            let result = validate_withdraw_stake(
+                &rpc,
+                &signer,
+                &stake_pubkey,
+                &Pubkey::new_unique(),
+                amount,
+                custodian.as_ref(),
+            )
+            .await;
+
+            match (result, expected) {
+                (Ok(withdrawal), Ok(expected)) => assert_eq!(
+                    (withdrawal.lamports, withdrawal.closes_account),
+                    expected,
+                    "case {i}"
+                ),
+                (Err(e), Err(fragment)) => {
+                    assert!(e.to_string().contains(fragment), "case {i}: {e}")
+                }
+                (result, expected) => panic!(
+                    "case {i}: got {:?}, expected {expected:?}",
+                    result.map(|withdrawal| withdrawal.lamports)
+                ),
+            }
+        }
+    }
+
+    fn reward_inputs(commission: u8, validator_credits: u64) -> RewardEstimateInputs {
+        RewardEstimateInputs {
+            validator_inflation: 0.04,
+            total_supply: 600 * LAMPORTS_PER_SOL,
+            total_active_stake: 400 * LAMPORTS_PER_SOL,
+            commission,
+            validator_credits,
+            cluster_credits: 400_000.0,
+            slots_in_epoch: 432_000,
+        }
+    }
+
+    #[test]
+    fn test_estimate_staking_rewards_nets_commission_and_performance() {
+        let average = estimate_staking_rewards(100 * LAMPORTS_PER_SOL, &reward_inputs(0, 400_000));
+        assert!((average.cluster_apr - 0.06).abs() < 1e-9);
+        assert!((average.performance - 1.0).abs() < 1e-9);
+        assert!((average.net_apr - 0.06).abs() < 1e-9);
+        assert_eq!(average.yearly_lamports, 6 * LAMPORTS_PER_SOL);
+        // ~182.6 two-day epochs a year
+        assert_eq!(
+            average.epoch_lamports,
+            6 * LAMPORTS_PER_SOL * 172_800_000 / 31_557_600_000
+        );
+
+        let costly = estimate_staking_rewards(100 * LAMPORTS_PER_SOL, &reward_inputs(10, 200_000));
+        assert!((costly.performance - 0.5).abs() < 1e-9);
+        assert!((costly.net_apr - 0.027).abs() < 1e-9);
+
+        let greedy = estimate_staking_rewards(100 * LAMPORTS_PER_SOL, &reward_inputs(100, 400_000));
+        assert_eq!(greedy.yearly_lamports, 0);
+    }
+
+    #[test]
+    fn test_credits_earned_in_epoch() {
+        let epoch_credits = vec![(9, 1_000, 600), (10, 1_350, 1_000)];
+
+        assert_eq!(credits_earned_in(&epoch_credits, 9), 400);
+        assert_eq!(credits_earned_in(&epoch_credits, 10), 350);
+        assert_eq!(credits_earned_in(&epoch_credits, 11), 0);
+    }
+}
@@ -0,0 +1,407 @@
+use {
+    crate::{
+        commands::CommandFlow,
+        config::OutputFormat,
+        context::ScillaContext,
+        misc::{
+            helpers::{build_and_send_tx, get_associated_token_address, read_account},
+            token::{
+                TokenProgram, create_associated_token_account_idempotent, fetch_token_holdings,
+                format_token_amount, parse_mint, parse_token_account, parse_token_amount,
+                transfer_checked,
+            },
+        },
+        prompt::{prompt_input_data, prompt_optional_data, prompt_select_data},
+        ui::{print_explorer_link, print_list_table, print_record_table, show_spinner, theme},
+    },
+    anyhow::{anyhow, bail},
+    clap::Subcommand,
+    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    solana_pubkey::Pubkey,
+    std::fmt,
+};
+
+/// Commands related to SPL tokens
+#[derive(Debug, Clone)]
+pub enum TokenCommand {
+    MintInfo,
+    TokenAccounts,
+    Balance,
+    Transfer,
+    AssociatedTokenAddress,
+    GoBack,
+}
+
+impl TokenCommand {
+    pub fn spinner_msg(&self) -> &'static str {
+        match self {
+            TokenCommand::MintInfo => "Fetching mint…",
+            TokenCommand::TokenAccounts => "Fetching token accounts…",
+            TokenCommand::Balance => "Fetching token balance…",
+            TokenCommand::Transfer => "Sending tokens…",
+            TokenCommand::AssociatedTokenAddress => "Deriving associated token address…",
+            TokenCommand::GoBack => "Going back…",
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            TokenCommand::MintInfo => "Supply, decimals, authorities and transfer fee of a mint",
+            TokenCommand::TokenAccounts => "Token and Token-2022 balances held by a wallet",
+            TokenCommand::Balance => "A wallet's balance of one mint, from its associated account",
+            TokenCommand::Transfer => "Send tokens of either token program to a wallet",
+            TokenCommand::AssociatedTokenAddress => {
+                "Derive a wallet's token account for a mint, offline"
+            }
+            TokenCommand::GoBack => "Return to the command groups",
+        }
+    }
+
+    /// Commands unavailable in watch-only mode
+    pub fn requires_signing(&self) -> bool {
+        matches!(self, TokenCommand::Transfer)
+    }
+}
+
+impl fmt::Display for TokenCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            TokenCommand::MintInfo => "Mint Info",
+            TokenCommand::TokenAccounts => "Token Accounts",
+            TokenCommand::Balance => "Token Balance",
+            TokenCommand::Transfer => "Transfer Tokens",
+            TokenCommand::AssociatedTokenAddress => "Associated Token Address",
+            TokenCommand::GoBack => "Go back",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl TokenCommand {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+        match self {
+            TokenCommand::MintInfo => {
+                let mint: Pubkey = prompt_input_data("Enter Mint Pubkey:");
+                let _ = show_spinner(self.spinner_msg(), fetch_mint_info(ctx, &mint)).await;
+            }
+            TokenCommand::TokenAccounts => {
+                let wallet: Pubkey =
+                    prompt_optional_data("Enter Wallet Pubkey (leave empty for your wallet):")
+                        .unwrap_or(*ctx.pubkey());
+                let _ = show_spinner(self.spinner_msg(), fetch_token_accounts(ctx, &wallet)).await;
+            }
+            TokenCommand::Balance => {
+                let wallet: Pubkey =
+                    prompt_optional_data("Enter Wallet Pubkey (leave empty for your wallet):")
+                        .unwrap_or(*ctx.pubkey());
+                let mint: Pubkey = prompt_input_data("Enter Mint Pubkey:");
+                let _ = show_spinner(self.spinner_msg(), fetch_token_balance(ctx, &wallet, &mint))
+                    .await;
+            }
+            TokenCommand::Transfer => {
+                let mint: Pubkey = prompt_input_data("Enter Mint Pubkey:");
+                let recipient: Pubkey = prompt_input_data("Enter Recipient Wallet:");
+                let amount: String = prompt_input_data("Enter amount (in tokens):");
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    transfer_tokens(ctx, &mint, &recipient, &amount),
+                )
+                .await;
+            }
+            TokenCommand::AssociatedTokenAddress => {
+                let wallet: Pubkey =
+                    prompt_optional_data("Enter Wallet Pubkey (leave empty for your wallet):")
+                        .unwrap_or(*ctx.pubkey());
+                let mint: Pubkey = prompt_input_data("Enter Mint Pubkey:");
+                let token_program = match prompt_select_data(
+                    "Token program:",
+                    vec!["Token", "Token-2022", "Custom program id"],
+                ) {
+                    "Token-2022" => TokenProgram::Token2022,
+                    "Custom program id" => {
+                        TokenProgram::Custom(prompt_input_data("Enter Token Program Id:"))
+                    }
+                    _ => TokenProgram::Token,
+                };
+
+                // pure derivation, nothing to wait on
+                show_associated_token_address(&wallet, &mint, token_program);
+            }
+            TokenCommand::GoBack => return CommandFlow::GoBack,
+        }
+
+        CommandFlow::Process(())
+    }
+}
+
+/// Token commands that run once from the command line and exit
+#[derive(Debug, Clone, Subcommand)]
+pub enum TokenArgs {
+    /// Balance of one mint, for your wallet by default
+    Balance {
+        mint: Pubkey,
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Token and Token-2022 balances, for your wallet by default
+    Accounts {
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+}
+
+impl TokenArgs {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            TokenArgs::Balance { mint, wallet } => {
+                fetch_token_balance(ctx, &wallet.unwrap_or(*ctx.pubkey()), mint).await
+            }
+            TokenArgs::Accounts { wallet } => {
+                fetch_token_accounts(ctx, &wallet.unwrap_or(*ctx.pubkey())).await
+            }
+        }
+    }
+}
+
+fn show_associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: TokenProgram) {
+    let address = get_associated_token_address(wallet, mint, &token_program.id());
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Wallet"), Cell::new(wallet)])
+        .add_row(vec![Cell::new("Mint"), Cell::new(mint)])
+        .add_row(vec![Cell::new("Token Program"), Cell::new(token_program)])
+        .add_row(vec![
+            Cell::new("Associated Token Address"),
+            Cell::new(theme::success(address)),
+        ]);
+
+    println!("\n{}", theme::success("ASSOCIATED TOKEN ADDRESS").bold());
+    println!("{table}");
+}
+
+async fn fetch_mint_info(ctx: &ScillaContext, mint: &Pubkey) -> anyhow::Result<()> {
+    let account = read_account(ctx, mint).await?;
+    let mint_info = parse_mint(&account.owner, &account.data)?;
+
+    let authority = |authority: Option<Pubkey>| {
+        authority.map_or_else(|| "None".to_string(), |key| key.to_string())
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Mint"), Cell::new(mint)])
+        .add_row(vec![Cell::new("Program"), Cell::new(mint_info.program)])
+        .add_row(vec![
+            Cell::new("Supply"),
+            Cell::new(format_token_amount(mint_info.supply, mint_info.decimals)),
+        ])
+        .add_row(vec![Cell::new("Decimals"), Cell::new(mint_info.decimals)])
+        .add_row(vec![
+            Cell::new("Mint Authority"),
+            Cell::new(authority(mint_info.mint_authority)),
+        ])
+        .add_row(vec![
+            Cell::new("Freeze Authority"),
+            Cell::new(authority(mint_info.freeze_authority)),
+        ]);
+
+    if let Some(transfer_fee) = mint_info.transfer_fee {
+        let epoch = ctx.rpc().get_epoch_info().await?.epoch;
+        let fee = transfer_fee.active(epoch);
+        table.add_row(vec![
+            Cell::new("Transfer Fee"),
+            Cell::new(format!(
+                "{}% (max {})",
+                fee.basis_points as f64 / 100.0,
+                format_token_amount(fee.maximum_fee, mint_info.decimals)
+            )),
+        ]);
+    }
+
+    println!("\n{}", theme::success("MINT INFO").bold());
+    println!("{table}");
+
+    Ok(())
+}
+
+async fn fetch_token_accounts(ctx: &ScillaContext, wallet: &Pubkey) -> anyhow::Result<()> {
+    let holdings = fetch_token_holdings(ctx, wallet).await?;
+
+    if holdings.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!("No token accounts found for {wallet}"))
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Token Account"),
+        theme::bold_cell("Mint"),
+        theme::bold_cell("Program"),
+        theme::bold_cell("Balance"),
+    ]);
+
+    for holding in &holdings {
+        table.add_row(vec![
+            Cell::new(holding.address),
+            Cell::new(holding.mint),
+            Cell::new(holding.program),
+            Cell::new(&holding.balance),
+        ]);
+    }
+
+    print_list_table(ctx, "TOKEN ACCOUNTS", &table);
+
+    Ok(())
+}
+
+/// Balance of `mint` in the wallet's associated token account. A missing
+/// account is reported as a zero balance rather than an error.
+async fn fetch_token_balance(
+    ctx: &ScillaContext,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> anyhow::Result<()> {
+    let mint_account = read_account(ctx, mint).await?;
+    let mint_info = parse_mint(&mint_account.owner, &mint_account.data)?;
+    let address = get_associated_token_address(wallet, mint, &mint_info.program.id());
+
+    let balance = match ctx
+        .rpc()
+        .get_account_with_commitment(&address, ctx.read_commitment())
+        .await?
+        .value
+    {
+        Some(account) => parse_token_account(&account.data)?.amount,
+        None if ctx.output_format() == OutputFormat::Table => {
+            println!(
+                "{}",
+                theme::warning(format!("No token account found (balance 0) at {address}"))
+            );
+            return Ok(());
+        }
+        // a missing account is a zero balance for scripts
+        None => 0,
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Wallet"), Cell::new(wallet)])
+        .add_row(vec![Cell::new("Mint"), Cell::new(mint)])
+        .add_row(vec![Cell::new("Token Account"), Cell::new(address)])
+        .add_row(vec![
+            Cell::new("Balance"),
+            Cell::new(theme::success(format_token_amount(
+                balance,
+                mint_info.decimals,
+            ))),
+        ]);
+
+    print_record_table(ctx, "TOKEN BALANCE", &table);
+
+    Ok(())
+}
+
+/// Sends tokens from the wallet's associated token account to the recipient's,
+/// creating the recipient's account if needed. The mint's owner decides which
+/// token program is used.
+async fn transfer_tokens(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    recipient: &Pubkey,
+    amount: &str,
+) -> anyhow::Result<()> {
+    let mint_account = read_account(ctx, mint).await?;
+    let mint_info = parse_mint(&mint_account.owner, &mint_account.data)?;
+    let amount = parse_token_amount(amount, mint_info.decimals)?;
+    let program_id = mint_info.program.id();
+
+    let source = get_associated_token_address(ctx.pubkey(), mint, &program_id);
+    let source_account = ctx
+        .rpc()
+        .get_account_with_commitment(&source, ctx.rpc().commitment())
+        .await?
+        .value
+        .ok_or_else(|| anyhow!("Your wallet has no token account for {mint}"))?;
+    let balance = parse_token_account(&source_account.data)?.amount;
+
+    if amount > balance {
+        bail!(
+            "Insufficient balance: have {}, trying to send {}",
+            format_token_amount(balance, mint_info.decimals),
+            format_token_amount(amount, mint_info.decimals)
+        );
+    }
+
+    let fee = match mint_info.transfer_fee {
+        Some(transfer_fee) => {
+            let epoch = ctx.rpc().get_epoch_info().await?.epoch;
+            transfer_fee.active(epoch).fee_for(amount)
+        }
+        None => 0,
+    };
+
+    let destination = get_associated_token_address(recipient, mint, &program_id);
+    let instructions = [
+        create_associated_token_account_idempotent(
+            ctx.pubkey(),
+            recipient,
+            mint,
+            mint_info.program,
+        ),
+        transfer_checked(
+            mint_info.program,
+            &source,
+            mint,
+            &destination,
+            ctx.pubkey(),
+            amount,
+            mint_info.decimals,
+        ),
+    ];
+
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.keypair()?]).await?;
+
+    println!(
+        "{} {}\n{}",
+        theme::success(format!(
+            "Sent {} tokens!",
+            format_token_amount(amount, mint_info.decimals)
+        ))
+        .bold(),
+        theme::info(format!("Signature: {signature}")),
+        theme::warning(format!("To Token Account: {destination}"))
+    );
+    if fee > 0 {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "Transfer fee withheld by the mint: {} (recipient gets {})",
+                format_token_amount(fee, mint_info.decimals),
+                format_token_amount(amount - fee, mint_info.decimals)
+            ))
+        );
+    }
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
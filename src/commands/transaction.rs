@@ -1,26 +1,57 @@
 use {
     crate::{
         commands::CommandFlow,
+        constants::{MONITOR_POLL_INTERVAL_MS, SIGNATURE_STATUSES_BATCH},
         context::ScillaContext,
-        misc::helpers::{bincode_deserialize, decode_base58, decode_base64},
-        prompt::{prompt_input_data, prompt_select_data},
-        ui::show_spinner,
+        misc::helpers::{
+            SolAmount, bincode_deserialize, decode_base58, decode_base64, lamports_to_sol,
+            send_signed_tx, sign_as_fee_payer,
+        },
+        prompt::{prompt_input_data, prompt_optional_data, prompt_select_data},
+        ui::{
+            finish_progress, plain_aware, print_error, print_explorer_link, print_list_table,
+            print_record_table, print_simulation_summary, show_spinner, theme,
+        },
     },
+    anyhow::{anyhow, bail},
+    base64::Engine,
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
-    solana_rpc_client_api::config::RpcTransactionConfig,
+    indicatif::{ProgressBar, ProgressStyle},
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+    },
     solana_signature::Signature,
+    solana_stake_interface::instruction as stake_instruction,
+    solana_system_interface::instruction as system_instruction,
     solana_transaction::versioned::VersionedTransaction,
-    solana_transaction_status::{EncodedTransaction, UiMessage, UiTransactionEncoding},
-    std::fmt,
+    solana_transaction_status::{
+        EncodedTransaction, TransactionConfirmationStatus, UiInstruction, UiMessage,
+        UiParsedInstruction, UiTransactionEncoding, option_serializer::OptionSerializer,
+    },
+    solana_vote_interface::instruction as vote_instruction,
+    std::{
+        fmt, fs,
+        path::PathBuf,
+        time::{Duration, Instant},
+    },
 };
 
 #[derive(Debug, Clone)]
 pub enum TransactionCommand {
     CheckConfirmation,
+    MonitorTransaction,
     FetchStatus,
+    BulkStatus,
     FetchTransaction,
     SendTransaction,
+    SponsorTransaction,
+    SimulateTransaction,
+    PrepareUnsigned,
     GoBack,
 }
 
@@ -28,48 +59,217 @@ impl TransactionCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
             Self::CheckConfirmation => "Checking transaction confirmation…",
+            Self::MonitorTransaction => "Waiting for the transaction to land…",
             Self::FetchStatus => "Fetching transaction status…",
+            Self::BulkStatus => "Fetching signature statuses…",
             Self::FetchTransaction => "Fetching full transaction data…",
             Self::SendTransaction => "Sending transaction…",
+            Self::SponsorTransaction => "Signing as fee payer and sending transaction…",
+            Self::SimulateTransaction => "Simulating transaction…",
+            Self::PrepareUnsigned => "Building unsigned message…",
             Self::GoBack => "Going back…",
         }
     }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            Self::CheckConfirmation => "Check whether a signature has been confirmed",
+            Self::MonitorTransaction => "Follow a signature live until it reaches a commitment",
+            Self::FetchStatus => "Show slot, confirmations and status of a signature",
+            Self::BulkStatus => "Check many signatures at once, from a list or a file",
+            Self::FetchTransaction => "Show fee, status and accounts of a transaction",
+            Self::SendTransaction => "Broadcast an already signed encoded transaction",
+            Self::SponsorTransaction => "Add your fee payer signature to a signed transaction",
+            Self::SimulateTransaction => "Simulate an encoded transaction and print its logs",
+            Self::PrepareUnsigned => "Export an operation's message for review; nothing is signed",
+            Self::GoBack => "Return to the command groups",
+        }
+    }
+
+    /// Sponsoring signs as fee payer; the other commands only read or relay
+    pub fn requires_signing(&self) -> bool {
+        matches!(self, TransactionCommand::SponsorTransaction)
+    }
 }
 
 impl fmt::Display for TransactionCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(match self {
             Self::CheckConfirmation => "Check Transaction Confirmation",
+            Self::MonitorTransaction => "Monitor Transaction",
             Self::FetchStatus => "Fetch Transaction Status",
+            Self::BulkStatus => "Bulk Signature Status",
             Self::FetchTransaction => "Fetch Transaction",
             Self::SendTransaction => "Send Transaction",
+            Self::SponsorTransaction => "Sponsor Transaction (Fee Payer)",
+            Self::SimulateTransaction => "Simulate Transaction",
+            Self::PrepareUnsigned => "Prepare Unsigned Message",
             Self::GoBack => "Go back",
         })
     }
 }
 
+/// Commitment a monitored transaction is followed up to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfirmationTarget {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<&TransactionConfirmationStatus> for ConfirmationTarget {
+    fn from(status: &TransactionConfirmationStatus) -> Self {
+        match status {
+            TransactionConfirmationStatus::Processed => Self::Processed,
+            TransactionConfirmationStatus::Confirmed => Self::Confirmed,
+            TransactionConfirmationStatus::Finalized => Self::Finalized,
+        }
+    }
+}
+
+impl fmt::Display for ConfirmationTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Processed => "Processed",
+            Self::Confirmed => "Confirmed",
+            Self::Finalized => "Finalized",
+        })
+    }
+}
+
+/// Operations whose unsigned message can be prepared for review
+#[derive(Debug, Clone, Copy)]
+enum UnsignedOperation {
+    TransferSol,
+    DelegateStake,
+    DeactivateStake,
+    WithdrawFromVote,
+}
+
+impl fmt::Display for UnsignedOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TransferSol => "Transfer SOL",
+            Self::DelegateStake => "Delegate stake",
+            Self::DeactivateStake => "Deactivate stake",
+            Self::WithdrawFromVote => "Withdraw from vote account",
+        })
+    }
+}
+
+impl UnsignedOperation {
+    /// Prompts for the operation's accounts and amounts. Only public keys are
+    /// asked for, so no keypair is ever loaded.
+    fn prompt_instructions(&self, ctx: &ScillaContext) -> Vec<Instruction> {
+        match self {
+            Self::TransferSol => {
+                let from: Pubkey =
+                    prompt_optional_data("Enter Sender Address (leave empty for your wallet):")
+                        .unwrap_or(*ctx.pubkey());
+                let to: Pubkey = prompt_input_data("Enter Recipient Address:");
+                let amount: SolAmount = prompt_input_data("Enter amount (SOL):");
+                vec![system_instruction::transfer(
+                    &from,
+                    &to,
+                    amount.to_lamports(),
+                )]
+            }
+            Self::DelegateStake => {
+                let stake: Pubkey = prompt_input_data("Enter Stake Account Pubkey:");
+                let vote: Pubkey = prompt_input_data("Enter Vote Account Pubkey:");
+                let staker: Pubkey = prompt_input_data("Enter Stake Authority Pubkey:");
+                vec![stake_instruction::delegate_stake(&stake, &staker, &vote)]
+            }
+            Self::DeactivateStake => {
+                let stake: Pubkey = prompt_input_data("Enter Stake Account Pubkey:");
+                let staker: Pubkey = prompt_input_data("Enter Stake Authority Pubkey:");
+                vec![stake_instruction::deactivate_stake(&stake, &staker)]
+            }
+            Self::WithdrawFromVote => {
+                let vote: Pubkey = prompt_input_data("Enter Vote Account Address:");
+                let withdrawer: Pubkey = prompt_input_data("Enter Withdraw Authority Pubkey:");
+                let to: Pubkey = prompt_input_data("Enter Recipient Address:");
+                let amount: SolAmount = prompt_input_data("Enter amount (SOL):");
+                vec![vote_instruction::withdraw(
+                    &vote,
+                    &withdrawer,
+                    amount.to_lamports(),
+                    &to,
+                )]
+            }
+        }
+    }
+}
+
 impl TransactionCommand {
     pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
         match self {
             TransactionCommand::CheckConfirmation => {
                 let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_check_confirmation(ctx, &signature),
                 )
                 .await;
             }
+            TransactionCommand::MonitorTransaction => {
+                let signature: Signature = prompt_input_data("Enter transaction signature:");
+                let target = prompt_select_data(
+                    "Wait until:",
+                    vec![ConfirmationTarget::Confirmed, ConfirmationTarget::Finalized],
+                );
+
+                // The status line is its own live display, so no spinner here
+                if let Err(e) = monitor_transaction(ctx, &signature, target).await {
+                    print_error(e);
+                }
+            }
             TransactionCommand::FetchStatus => {
                 let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_fetch_transaction_status(ctx, &signature),
                 )
                 .await;
             }
+            TransactionCommand::BulkStatus => {
+                let signatures = loop {
+                    let input = match prompt_select_data(
+                        "Signatures from:",
+                        vec!["Paste a list", "Read a file"],
+                    ) {
+                        "Read a file" => {
+                            let path: PathBuf =
+                                prompt_input_data("Enter file path (one or more per line):");
+                            match fs::read_to_string(&path) {
+                                Ok(contents) => contents,
+                                Err(e) => {
+                                    print_error(format!("Failed to read {}: {e}", path.display()));
+                                    continue;
+                                }
+                            }
+                        }
+                        _ => prompt_input_data("Enter signatures, comma separated:"),
+                    };
+
+                    match parse_signature_list(&input) {
+                        Ok(signatures) if signatures.is_empty() => {
+                            print_error("No signatures given. Please try again.")
+                        }
+                        Ok(signatures) => break signatures,
+                        Err(e) => print_error(e),
+                    }
+                };
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    process_bulk_signature_status(ctx, &signatures),
+                )
+                .await;
+            }
             TransactionCommand::FetchTransaction => {
                 let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_fetch_transaction(ctx, &signature),
                 )
@@ -78,9 +278,7 @@ impl TransactionCommand {
             TransactionCommand::SendTransaction => {
                 println!(
                     "{}",
-                    style("Note: Only VersionedTransaction format is supported")
-                        .yellow()
-                        .dim()
+                    theme::warning("Note: Only VersionedTransaction format is supported").dim()
                 );
 
                 let encoding = prompt_select_data(
@@ -90,12 +288,70 @@ impl TransactionCommand {
 
                 let encoded_tx: String = prompt_input_data("Enter encoded transaction:");
 
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_send_transaction(ctx, encoding, &encoded_tx),
                 )
                 .await;
             }
+            TransactionCommand::SponsorTransaction => {
+                println!(
+                    "{}",
+                    theme::warning(
+                        "Note: The transaction must already list your wallet as fee payer and be \
+                         signed by every other signer"
+                    )
+                    .dim()
+                );
+
+                let encoding = prompt_select_data(
+                    "Select encoding format:",
+                    vec![UiTransactionEncoding::Base64, UiTransactionEncoding::Base58],
+                );
+
+                let encoded_tx: String = prompt_input_data("Enter partially signed transaction:");
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    process_sponsor_transaction(ctx, encoding, &encoded_tx),
+                )
+                .await;
+            }
+            TransactionCommand::SimulateTransaction => {
+                let encoding = prompt_select_data(
+                    "Select encoding format:",
+                    vec![UiTransactionEncoding::Base64, UiTransactionEncoding::Base58],
+                );
+
+                let encoded_tx: String = prompt_input_data("Enter encoded transaction:");
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    process_simulate_transaction(ctx, encoding, &encoded_tx),
+                )
+                .await;
+            }
+            TransactionCommand::PrepareUnsigned => {
+                let operation = prompt_select_data(
+                    "Select operation:",
+                    vec![
+                        UnsignedOperation::TransferSol,
+                        UnsignedOperation::DelegateStake,
+                        UnsignedOperation::DeactivateStake,
+                        UnsignedOperation::WithdrawFromVote,
+                    ],
+                );
+                let fee_payer: Pubkey =
+                    prompt_optional_data("Enter Fee Payer Address (leave empty for your wallet):")
+                        .unwrap_or(*ctx.pubkey());
+                let instructions = operation.prompt_instructions(ctx);
+
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    process_prepare_unsigned(ctx, &fee_payer, &instructions),
+                )
+                .await;
+            }
             TransactionCommand::GoBack => return CommandFlow::GoBack,
         }
 
@@ -103,6 +359,28 @@ impl TransactionCommand {
     }
 }
 
+/// Transaction commands that run once from the command line and exit
+#[derive(Debug, Clone, Subcommand)]
+pub enum TransactionArgs {
+    /// Whether a transaction has confirmed
+    Confirm { signature: Signature },
+    /// Slot, confirmations and error of a transaction
+    Status { signature: Signature },
+}
+
+impl TransactionArgs {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            TransactionArgs::Confirm { signature } => {
+                process_check_confirmation(ctx, signature).await
+            }
+            TransactionArgs::Status { signature } => {
+                process_fetch_transaction_status(ctx, signature).await
+            }
+        }
+    }
+}
+
 async fn process_check_confirmation(
     ctx: &ScillaContext,
     signature: &Signature,
@@ -110,27 +388,184 @@ async fn process_check_confirmation(
     let confirmed = ctx.rpc().confirm_transaction(signature).await?;
 
     let status_styled = if confirmed {
-        style("Confirmed").green()
+        theme::success("Confirmed")
     } else {
-        style("Not Confirmed").yellow()
+        theme::warning("Not Confirmed")
     };
 
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
         .add_row(vec![Cell::new("Status"), Cell::new(status_styled)]);
 
-    println!("\n{}", style("TRANSACTION CONFIRMATION").green().bold());
-    println!("{}", table);
+    print_record_table(ctx, "TRANSACTION CONFIRMATION", &table);
+
+    Ok(())
+}
+
+/// Polls the signature status and keeps one live line updated with the
+/// current commitment and elapsed time until `target` is reached, the
+/// transaction fails, or the configured confirmation timeout passes.
+pub async fn monitor_transaction(
+    ctx: &ScillaContext,
+    signature: &Signature,
+    target: ConfirmationTarget,
+) -> anyhow::Result<()> {
+    let progress = plain_aware(ProgressBar::new_spinner());
+    progress.set_style(
+        ProgressStyle::with_template("{spinner:.cyan} [{elapsed}] {msg}")
+            .unwrap()
+            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+    );
+    progress.enable_steady_tick(Duration::from_millis(100));
+    progress.set_message("Not seen yet");
+
+    let started = Instant::now();
+    let mut interval = tokio::time::interval(Duration::from_millis(MONITOR_POLL_INTERVAL_MS));
+
+    loop {
+        interval.tick().await;
+
+        let statuses = ctx.rpc().get_signature_statuses(&[*signature]).await?;
+        if let Some(Some(status)) = statuses.value.first() {
+            if let Some(err) = &status.err {
+                finish_progress(
+                    &progress,
+                    theme::error(format!("Failed: {err}")).to_string(),
+                );
+                print_explorer_link(ctx, signature);
+                return Ok(());
+            }
+
+            let reached = status
+                .confirmation_status
+                .as_ref()
+                .map(ConfirmationTarget::from)
+                // nodes that omit the status only report rooted transactions
+                .unwrap_or(ConfirmationTarget::Finalized);
+            progress.set_message(format!("{reached} · slot {}", status.slot));
+
+            if reached >= target {
+                finish_progress(
+                    &progress,
+                    theme::success(format!(
+                        "{reached} in {:.1}s · slot {}",
+                        started.elapsed().as_secs_f64(),
+                        status.slot
+                    ))
+                    .to_string(),
+                );
+                print_explorer_link(ctx, signature);
+                return Ok(());
+            }
+        }
+
+        if started.elapsed() >= ctx.confirm_timeout() {
+            progress.abandon_with_message(theme::warning("Timed out").to_string());
+            bail!(
+                "{signature} did not reach {target} within {}s",
+                ctx.confirm_timeout().as_secs()
+            );
+        }
+    }
+}
+
+/// Signatures separated by commas or whitespace, duplicates dropped
+fn parse_signature_list(input: &str) -> anyhow::Result<Vec<Signature>> {
+    let mut signatures: Vec<Signature> = Vec::new();
+
+    for part in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+    {
+        let signature: Signature = part
+            .parse()
+            .map_err(|_| anyhow!("Invalid signature: {part}"))?;
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+
+    Ok(signatures)
+}
+
+async fn process_bulk_signature_status(
+    ctx: &ScillaContext,
+    signatures: &[Signature],
+) -> anyhow::Result<()> {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::header_cell("Signature"),
+        theme::header_cell("Status"),
+        theme::header_cell("Slot"),
+        theme::header_cell("Error"),
+    ]);
+
+    let (mut succeeded, mut failed, mut missing) = (0, 0, 0);
+
+    for batch in signatures.chunks(SIGNATURE_STATUSES_BATCH) {
+        let statuses = ctx
+            .rpc()
+            .get_signature_statuses_with_history(batch)
+            .await?
+            .value;
+
+        for (signature, status) in batch.iter().zip(statuses) {
+            let row = match status {
+                None => {
+                    missing += 1;
+                    vec![
+                        Cell::new(signature),
+                        theme::warning_cell("Not found"),
+                        Cell::new("-"),
+                        Cell::new("-"),
+                    ]
+                }
+                Some(status) => {
+                    let commitment = status.confirmation_status.as_ref().map_or_else(
+                        || "Unknown".to_string(),
+                        |c| ConfirmationTarget::from(c).to_string(),
+                    );
+
+                    match &status.err {
+                        None => {
+                            succeeded += 1;
+                            vec![
+                                Cell::new(signature),
+                                theme::success_cell(commitment),
+                                Cell::new(status.slot),
+                                Cell::new("-"),
+                            ]
+                        }
+                        Some(err) => {
+                            failed += 1;
+                            vec![
+                                theme::error_cell(signature),
+                                theme::error_cell(format!("Failed ({commitment})")),
+                                Cell::new(status.slot),
+                                theme::error_cell(err),
+                            ]
+                        }
+                    }
+                }
+            };
+            table.add_row(row);
+        }
+    }
+
+    print_list_table(ctx, "SIGNATURE STATUSES", &table);
+    println!(
+        "{}",
+        theme::muted(format!(
+            "{} signatures: {succeeded} succeeded, {failed} failed, {missing} not found",
+            signatures.len()
+        ))
+    );
 
     Ok(())
 }
@@ -152,12 +587,8 @@ async fn process_fetch_transaction_status(
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
         .add_row(vec![Cell::new("Slot"), Cell::new(tx_status.slot)]);
@@ -167,7 +598,7 @@ async fn process_fetch_transaction_status(
     } else {
         table.add_row(vec![
             Cell::new("Confirmations"),
-            Cell::new(style("Finalized").green()),
+            Cell::new(theme::success("Finalized")),
         ]);
     }
 
@@ -176,13 +607,13 @@ async fn process_fetch_transaction_status(
             Cell::new("Confirmation Status"),
             Cell::new(match confirmation_status {
                 solana_transaction_status::TransactionConfirmationStatus::Processed => {
-                    style("Processed").yellow().to_string()
+                    theme::warning("Processed").to_string()
                 }
                 solana_transaction_status::TransactionConfirmationStatus::Confirmed => {
-                    style("Confirmed").cyan().to_string()
+                    theme::info("Confirmed").to_string()
                 }
                 solana_transaction_status::TransactionConfirmationStatus::Finalized => {
-                    style("Finalized").green().to_string()
+                    theme::success("Finalized").to_string()
                 }
             }),
         ]);
@@ -191,16 +622,13 @@ async fn process_fetch_transaction_status(
     table.add_row(vec![
         Cell::new("Status"),
         Cell::new(if tx_status.err.is_none() {
-            style("Success").green().to_string()
+            theme::success("Success").to_string()
         } else {
-            style(format!("Error: {:?}", tx_status.err))
-                .red()
-                .to_string()
+            theme::error(format!("Error: {:?}", tx_status.err)).to_string()
         }),
     ]);
 
-    println!("\n{}", style("TRANSACTION STATUS").green().bold());
-    println!("{}", table);
+    print_record_table(ctx, "TRANSACTION STATUS", &table);
 
     Ok(())
 }
@@ -225,12 +653,8 @@ async fn process_fetch_transaction(
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
         .add_row(vec![Cell::new("Slot"), Cell::new(tx.slot)]);
@@ -239,19 +663,44 @@ async fn process_fetch_transaction(
         table.add_row(vec![Cell::new("Block Time"), Cell::new(block_time)]);
     }
 
-    if let Some(meta) = &tx.transaction.meta {
+    if let Some(block_time) = tx.block_time
+        && let Some(datetime) = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
+    {
+        table.add_row(vec![
+            Cell::new("Date/Time"),
+            Cell::new(datetime.format("%Y-%m-%d %H:%M:%S UTC")),
+        ]);
+    }
+
+    let meta = tx.transaction.meta.as_ref();
+
+    if let Some(meta) = meta {
         table.add_row(vec![Cell::new("Fee (lamports)"), Cell::new(meta.fee)]);
+        if let OptionSerializer::Some(units) = &meta.compute_units_consumed {
+            table.add_row(vec![Cell::new("Compute Units Consumed"), Cell::new(units)]);
+        }
         table.add_row(vec![
             Cell::new("Status"),
             Cell::new(if meta.err.is_none() {
-                style("Success").green().to_string()
+                theme::success("Success").to_string()
             } else {
-                style(format!("Error: {:?}", meta.err)).red().to_string()
+                theme::error(format!("Error: {:?}", meta.err)).to_string()
             }),
         ]);
     }
 
-    println!("\n{}", style("TRANSACTION DETAILS").green().bold());
+    // Balances are indexed like the message's account keys
+    let balance_change = |idx: usize| -> (String, String) {
+        meta.and_then(|meta| {
+            Some((
+                format!("{:.9}", lamports_to_sol(*meta.pre_balances.get(idx)?)),
+                format!("{:.9}", lamports_to_sol(*meta.post_balances.get(idx)?)),
+            ))
+        })
+        .unwrap_or_else(|| ("-".to_string(), "-".to_string()))
+    };
+
+    println!("\n{}", theme::success("TRANSACTION DETAILS").bold());
     println!("{}", table);
 
     let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
@@ -260,18 +709,14 @@ async fn process_fetch_transaction(
 
     match &ui_tx.message {
         UiMessage::Parsed(parsed_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE").cyan().bold());
+            println!("\n{}", theme::info("TRANSACTION MESSAGE").bold());
 
             let mut msg_table = Table::new();
             msg_table
                 .load_preset(UTF8_FULL)
                 .set_header(vec![
-                    Cell::new("Field")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                    Cell::new("Value")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
+                    theme::header_cell("Field"),
+                    theme::header_cell("Value"),
                 ])
                 .add_row(vec![
                     Cell::new("Account Keys"),
@@ -285,39 +730,47 @@ async fn process_fetch_transaction(
             println!("{}", msg_table);
 
             if !parsed_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
+                println!("\n{}", theme::info("ACCOUNT KEYS").bold());
                 let mut accounts_table = Table::new();
                 accounts_table.load_preset(UTF8_FULL).set_header(vec![
-                    Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Pubkey").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Signer").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Writable").add_attribute(comfy_table::Attribute::Bold),
+                    theme::bold_cell("Index"),
+                    theme::bold_cell("Pubkey"),
+                    theme::bold_cell("Signer"),
+                    theme::bold_cell("Writable"),
+                    theme::bold_cell("Pre Balance (SOL)"),
+                    theme::bold_cell("Post Balance (SOL)"),
                 ]);
 
                 for (idx, account) in parsed_msg.account_keys.iter().enumerate() {
+                    let (pre_balance, post_balance) = balance_change(idx);
                     accounts_table.add_row(vec![
                         Cell::new(idx),
                         Cell::new(&account.pubkey),
                         Cell::new(if account.signer { "✓" } else { "" }),
                         Cell::new(if account.writable { "✓" } else { "" }),
+                        Cell::new(pre_balance),
+                        Cell::new(post_balance),
                     ]);
                 }
                 println!("{}", accounts_table);
             }
+
+            let account_keys: Vec<&str> = parsed_msg
+                .account_keys
+                .iter()
+                .map(|account| account.pubkey.as_str())
+                .collect();
+            print_instructions(&parsed_msg.instructions, &account_keys);
         }
         UiMessage::Raw(raw_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE (Raw)").cyan().bold());
+            println!("\n{}", theme::info("TRANSACTION MESSAGE (Raw)").bold());
 
             let mut msg_table = Table::new();
             msg_table
                 .load_preset(UTF8_FULL)
                 .set_header(vec![
-                    Cell::new("Field")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                    Cell::new("Value")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
+                    theme::header_cell("Field"),
+                    theme::header_cell("Value"),
                 ])
                 .add_row(vec![
                     Cell::new("Account Keys"),
@@ -331,38 +784,287 @@ async fn process_fetch_transaction(
             println!("{}", msg_table);
 
             if !raw_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
+                println!("\n{}", theme::info("ACCOUNT KEYS").bold());
                 for (idx, key) in raw_msg.account_keys.iter().enumerate() {
-                    println!("  {}. {}", idx, key);
+                    let (pre_balance, post_balance) = balance_change(idx);
+                    println!(
+                        "  {}. {} ({} → {} SOL)",
+                        idx, key, pre_balance, post_balance
+                    );
                 }
             }
+
+            let account_keys: Vec<&str> = raw_msg.account_keys.iter().map(String::as_str).collect();
+            let instructions: Vec<UiInstruction> = raw_msg
+                .instructions
+                .iter()
+                .cloned()
+                .map(UiInstruction::Compiled)
+                .collect();
+            print_instructions(&instructions, &account_keys);
         }
     }
 
     Ok(())
 }
 
-async fn process_send_transaction(
-    ctx: &ScillaContext,
+fn print_instructions(instructions: &[UiInstruction], account_keys: &[&str]) {
+    if instructions.is_empty() {
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("#"),
+        theme::bold_cell("Program"),
+        theme::bold_cell("Instruction"),
+    ]);
+
+    for (idx, instruction) in instructions.iter().enumerate() {
+        let (program, details) = match instruction {
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(ix)) => (
+                ix.program.clone(),
+                ix.parsed
+                    .get("type")
+                    .and_then(|kind| kind.as_str())
+                    .unwrap_or("-")
+                    .to_string(),
+            ),
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(ix)) => (
+                ix.program_id.clone(),
+                format!("{} accounts, data: {}", ix.accounts.len(), ix.data),
+            ),
+            UiInstruction::Compiled(ix) => (
+                account_keys
+                    .get(ix.program_id_index as usize)
+                    .map(|key| key.to_string())
+                    .unwrap_or_else(|| format!("index {}", ix.program_id_index)),
+                format!("{} accounts, data: {}", ix.accounts.len(), ix.data),
+            ),
+        };
+
+        table.add_row(vec![Cell::new(idx), Cell::new(program), Cell::new(details)]);
+    }
+
+    println!("\n{}", theme::info("INSTRUCTIONS").bold());
+    println!("{}", table);
+}
+
+fn decode_versioned_transaction(
     encoding: UiTransactionEncoding,
     encoded_tx: &str,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<VersionedTransaction> {
     let tx_bytes = match encoding {
         UiTransactionEncoding::Base64 => decode_base64(encoded_tx)?,
         UiTransactionEncoding::Base58 => decode_base58(encoded_tx)?,
         _ => unreachable!("The available encoding options are Base64 and Base58"),
     };
 
-    let tx: VersionedTransaction =
-        bincode_deserialize(&tx_bytes, "encoded transaction to VersionedTransaction")?;
+    bincode_deserialize(&tx_bytes, "encoded transaction to VersionedTransaction")
+}
+
+async fn process_send_transaction(
+    ctx: &ScillaContext,
+    encoding: UiTransactionEncoding,
+    encoded_tx: &str,
+) -> anyhow::Result<()> {
+    let tx = decode_versioned_transaction(encoding, encoded_tx)?;
 
     let signature = ctx.rpc().send_transaction(&tx).await?;
 
     println!(
         "{} {}",
-        style("Transaction sent successfully!").green().bold(),
-        style(signature).cyan()
+        theme::success("Transaction sent successfully!").bold(),
+        theme::info(signature)
+    );
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+async fn process_sponsor_transaction(
+    ctx: &ScillaContext,
+    encoding: UiTransactionEncoding,
+    encoded_tx: &str,
+) -> anyhow::Result<()> {
+    let mut tx = decode_versioned_transaction(encoding, encoded_tx)?;
+
+    sign_as_fee_payer(&mut tx, ctx.keypair()?)?;
+
+    let signature = send_signed_tx(ctx, &tx).await?;
+
+    println!(
+        "{} {}",
+        theme::success("Sponsored transaction confirmed!").bold(),
+        theme::info(format!("Signature: {signature}"))
+    );
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+async fn process_simulate_transaction(
+    ctx: &ScillaContext,
+    encoding: UiTransactionEncoding,
+    encoded_tx: &str,
+) -> anyhow::Result<()> {
+    let tx = decode_versioned_transaction(encoding, encoded_tx)?;
+
+    let addresses: Vec<String> = tx
+        .message
+        .static_account_keys()
+        .iter()
+        .map(|pubkey| pubkey.to_string())
+        .collect();
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(ctx.rpc().commitment()),
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: addresses.clone(),
+        }),
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let result = ctx
+        .rpc()
+        .simulate_transaction_with_config(&tx, config)
+        .await?
+        .value;
+
+    print_simulation_summary(&result);
+
+    if let Some(accounts) = &result.accounts {
+        let mut accounts_table = Table::new();
+        accounts_table.load_preset(UTF8_FULL).set_header(vec![
+            theme::bold_cell("Address"),
+            theme::bold_cell("Balance (SOL)"),
+            theme::bold_cell("Owner"),
+            theme::bold_cell("Executable"),
+        ]);
+
+        for (address, account) in addresses.iter().zip(accounts) {
+            match account {
+                Some(account) => accounts_table.add_row(vec![
+                    Cell::new(address),
+                    Cell::new(format!("{:.9}", lamports_to_sol(account.lamports))),
+                    Cell::new(&account.owner),
+                    Cell::new(if account.executable { "✓" } else { "" }),
+                ]),
+                None => accounts_table.add_row(vec![
+                    Cell::new(address),
+                    Cell::new("-"),
+                    Cell::new("-"),
+                    Cell::new(""),
+                ]),
+            };
+        }
+
+        println!("\n{}", theme::info("POST-SIMULATION ACCOUNTS").bold());
+        println!("{}", accounts_table);
+    }
+
+    Ok(())
+}
+
+async fn process_prepare_unsigned(
+    ctx: &ScillaContext,
+    fee_payer: &Pubkey,
+    instructions: &[Instruction],
+) -> anyhow::Result<()> {
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let message = Message::new_with_blockhash(instructions, Some(fee_payer), &blockhash);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(message.serialize());
+
+    let header = &message.header;
+    let num_signers = header.num_required_signatures as usize;
+    let num_writable_signers = num_signers - header.num_readonly_signed_accounts as usize;
+    let num_writable_unsigned =
+        message.account_keys.len() - num_signers - header.num_readonly_unsigned_accounts as usize;
+
+    let mut accounts_table = Table::new();
+    accounts_table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("#"),
+        theme::bold_cell("Address"),
+        theme::bold_cell("Signer"),
+        theme::bold_cell("Writable"),
+    ]);
+
+    for (idx, key) in message.account_keys.iter().enumerate() {
+        let is_signer = idx < num_signers;
+        let is_writable = if is_signer {
+            idx < num_writable_signers
+        } else {
+            idx - num_signers < num_writable_unsigned
+        };
+
+        accounts_table.add_row(vec![
+            Cell::new(idx),
+            Cell::new(key),
+            Cell::new(if is_signer { "✓" } else { "" }),
+            Cell::new(if is_writable { "✓" } else { "" }),
+        ]);
+    }
+
+    let mut instructions_table = Table::new();
+    instructions_table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("#"),
+        theme::bold_cell("Program"),
+        theme::bold_cell("Accounts"),
+        theme::bold_cell("Data (base58)"),
+    ]);
+
+    for (idx, ix) in message.instructions.iter().enumerate() {
+        instructions_table.add_row(vec![
+            Cell::new(idx),
+            Cell::new(message.account_keys[ix.program_id_index as usize]),
+            Cell::new(
+                ix.accounts
+                    .iter()
+                    .map(|account| account.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Cell::new(bs58::encode(&ix.data).into_string()),
+        ]);
+    }
+
+    println!("\n{}", theme::success("UNSIGNED MESSAGE (BASE64)").bold());
+    println!("{encoded}");
+    println!(
+        "\n{}",
+        theme::muted(format!(
+            "Fee payer: {fee_payer} · Recent blockhash: {blockhash} · Signatures required: \
+             {num_signers}"
+        ))
+    );
+    println!("\n{}", theme::info("ACCOUNTS").bold());
+    println!("{}", accounts_table);
+    println!("\n{}", theme::info("INSTRUCTIONS").bold());
+    println!("{}", instructions_table);
+    println!(
+        "{}",
+        theme::warning("Nothing was signed or sent. The blockhash expires in about a minute.")
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signature_list() {
+        let first = Signature::from([1u8; 64]);
+        let second = Signature::from([2u8; 64]);
+
+        let parsed = parse_signature_list(&format!("{first}, {second}\n{first}\n\n")).unwrap();
+        assert_eq!(parsed, vec![first, second]);
+
+        assert!(parse_signature_list("").unwrap().is_empty());
+        assert!(parse_signature_list(&format!("{first},not-a-signature")).is_err());
+    }
+}
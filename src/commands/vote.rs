@@ -2,19 +2,30 @@ use {
     crate::{
         ScillaContext,
         commands::CommandFlow,
-        misc::helpers::{
-            Commission, SolAmount, build_and_send_tx, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path,
+        config::OutputFormat,
+        misc::{
+            authority::{AuthorityChange, process_authority_change},
+            helpers::{
+                Commission, WithdrawAmount, build_and_send_tx, fetch_account_with_epoch,
+                lamports_to_sol, read_account,
+            },
+            signers::SignerSet,
         },
-        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
+        prompt::{
+            confirm_send_summary, prompt_confirmation, prompt_input_data, prompt_keypair_from_path,
+            prompt_keypair_path, prompt_optional_data, prompt_signer_paths, prompt_withdraw_amount,
+        },
+        ui::{print_explorer_link, print_json, show_spinner, theme},
     },
     anyhow::{anyhow, bail},
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    serde_json::json,
     solana_keypair::Signer,
+    solana_message::Message,
     solana_pubkey::Pubkey,
     solana_rpc_client_api::config::RpcGetVoteAccountsConfig,
+    solana_signature::Signature,
     solana_vote_interface::{
         instruction::{CreateVoteAccountConfig, authorize, create_account_with_config, withdraw},
         state::{VoteAuthorize, VoteInit, VoteStateV4},
@@ -29,6 +40,7 @@ pub enum VoteCommand {
     AuthorizeVoter,
     WithdrawFromVoteAccount,
     ShowVoteAccount,
+    VerifyIdentity,
     CloseVoteAccount,
     GoBack,
 }
@@ -40,10 +52,40 @@ impl VoteCommand {
             VoteCommand::AuthorizeVoter => "Authorizing voter…",
             VoteCommand::WithdrawFromVoteAccount => "Withdrawing SOL from vote account…",
             VoteCommand::ShowVoteAccount => "Fetching vote account details…",
+            VoteCommand::VerifyIdentity => "Checking the vote account's identity on the cluster…",
             VoteCommand::CloseVoteAccount => "Closing vote account…",
             VoteCommand::GoBack => "Going back…",
         }
     }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            VoteCommand::CreateVoteAccount => {
+                "Needs vote account, identity and withdrawer keypairs"
+            }
+            VoteCommand::AuthorizeVoter => {
+                "Change the voter after a dry run; requires the voter or withdrawer"
+            }
+            VoteCommand::WithdrawFromVoteAccount => "Withdraw SOL; requires the withdraw authority",
+            VoteCommand::ShowVoteAccount => "Show authorities, credits and commission",
+            VoteCommand::VerifyIdentity => "Check the identity is in gossip and voting",
+            VoteCommand::CloseVoteAccount => {
+                "Withdraw everything from a vote account with no stake"
+            }
+            VoteCommand::GoBack => "Return to the command groups",
+        }
+    }
+
+    /// Vote account changes pay fees from the session keypair
+    pub fn requires_signing(&self) -> bool {
+        matches!(
+            self,
+            VoteCommand::CreateVoteAccount
+                | VoteCommand::AuthorizeVoter
+                | VoteCommand::WithdrawFromVoteAccount
+                | VoteCommand::CloseVoteAccount
+        )
+    }
 }
 
 impl fmt::Display for VoteCommand {
@@ -53,6 +95,7 @@ impl fmt::Display for VoteCommand {
             VoteCommand::AuthorizeVoter => "Authorize voter",
             VoteCommand::WithdrawFromVoteAccount => "Withdraw from vote account",
             VoteCommand::ShowVoteAccount => "Show vote account",
+            VoteCommand::VerifyIdentity => "Verify vote account identity",
             VoteCommand::CloseVoteAccount => "Close vote account",
             VoteCommand::GoBack => "Go back",
         };
@@ -64,23 +107,21 @@ impl VoteCommand {
     pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
         match self {
             VoteCommand::CreateVoteAccount => {
-                let vote_account_keypair_path =
-                    prompt_keypair_path("Enter Vote Account Keypair Path:", ctx);
-                let identity_keypair_path =
-                    prompt_keypair_path("Enter Identity Keypair Path:", ctx);
+                let [vote_account_keypair_path, identity_keypair_path] =
+                    prompt_signer_paths(["Vote Account", "Identity"], ctx);
                 let withdraw_keypair_path =
                     prompt_keypair_path("Enter Withdraw Keypair Path:", ctx);
-                let commission: Commission =
-                    prompt_input_data("Enter Commission 0-100 (default 0):");
+                let commission: Option<Commission> =
+                    prompt_optional_data("Enter Commission 0-100 (default 0):");
 
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_create_vote_account(
                         ctx,
                         &vote_account_keypair_path,
                         &identity_keypair_path,
                         &withdraw_keypair_path,
-                        commission.value(),
+                        commission.map_or(0, |commission| commission.value()),
                     ),
                 )
                 .await;
@@ -92,9 +133,10 @@ impl VoteCommand {
                 let new_authorized_pubkey: Pubkey =
                     prompt_input_data("Enter New Authorized Address:");
 
-                show_spinner(
+                process_authority_change(
+                    ctx,
                     self.spinner_msg(),
-                    process_authorize_voter(
+                    prepare_authorize_voter(
                         ctx,
                         &vote_account_pubkey,
                         &authorized_keypair_path,
@@ -109,28 +151,50 @@ impl VoteCommand {
                     prompt_keypair_path("Enter Authorized Withdraw Keypair Path:", ctx);
                 let recipient_address: Pubkey = prompt_input_data("Enter Recipient Address:");
 
-                let amount: SolAmount = prompt_input_data("Enter withdraw amount in SOL:");
+                let amount = prompt_withdraw_amount();
+
+                if !confirm_send_summary(
+                    ctx,
+                    "VOTE WITHDRAWAL SUMMARY",
+                    &[
+                        ("From", vote_account_pubkey.to_string()),
+                        ("To", recipient_address.to_string()),
+                        ("Amount", amount.to_string()),
+                    ],
+                ) {
+                    println!("{}", theme::warning("Withdrawal cancelled."));
+                    return CommandFlow::GoBack;
+                }
 
-                show_spinner(
-                    self.spinner_msg(),
+                let _ = show_spinner(self.spinner_msg(), async {
                     process_sol_withdraw_from_vote_account(
                         ctx,
                         &vote_account_pubkey,
                         &authorized_withdrawer_keypair_path,
                         &recipient_address,
-                        amount.to_lamports(),
-                    ),
-                )
+                        amount,
+                    )
+                    .await
+                    .map(|result| result.render(ctx))
+                })
                 .await;
             }
             VoteCommand::ShowVoteAccount => {
                 let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     process_fetch_vote_account(ctx, &vote_account_pubkey),
                 )
                 .await;
             }
+            VoteCommand::VerifyIdentity => {
+                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
+                let _ = show_spinner(
+                    self.spinner_msg(),
+                    process_verify_identity(ctx, &vote_account_pubkey),
+                )
+                .await;
+            }
             VoteCommand::CloseVoteAccount => {
                 let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
                 let withdraw_authority_keypair_path =
@@ -138,11 +202,11 @@ impl VoteCommand {
                 let destination_pubkey: Pubkey = prompt_input_data("Enter Destination Address:");
 
                 if !prompt_confirmation("Are you sure you want to close this vote account?") {
-                    println!("{}", style("Close vote account cancelled.").yellow());
+                    println!("{}", theme::warning("Close vote account cancelled."));
                     return CommandFlow::Process(());
                 }
 
-                show_spinner(
+                let _ = show_spinner(
                     self.spinner_msg(),
                     close_vote_account(
                         ctx,
@@ -160,6 +224,62 @@ impl VoteCommand {
     }
 }
 
+/// Vote commands that run once from the command line and exit
+#[derive(Debug, Clone, Subcommand)]
+pub enum VoteArgs {
+    /// Authorities, credits and commission of a vote account
+    Show { pubkey: Pubkey },
+}
+
+impl VoteArgs {
+    pub async fn run(&self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            VoteArgs::Show { pubkey } => process_fetch_vote_account(ctx, pubkey).await,
+        }
+    }
+}
+
+/// The fee payer funds the new account, so it cannot be the account itself,
+/// and the vote program refuses a vote account that is its own identity
+fn check_vote_account_keys(
+    fee_payer: &Pubkey,
+    vote_account: &Pubkey,
+    identity: &Pubkey,
+) -> anyhow::Result<()> {
+    if fee_payer == vote_account {
+        bail!("Fee payer {fee_payer} cannot be the same as vote account {vote_account}");
+    }
+    if vote_account == identity {
+        bail!("Vote account {vote_account} cannot be the same as identity {identity}");
+    }
+
+    Ok(())
+}
+
+/// Fails before sending when the fee payer cannot cover the vote account's
+/// rent-exempt reserve plus the transaction fee
+fn check_vote_account_funding(
+    fee_payer: &Pubkey,
+    balance: u64,
+    rent_exempt_reserve: u64,
+    fee: u64,
+) -> anyhow::Result<()> {
+    let required = rent_exempt_reserve.saturating_add(fee);
+    if balance < required {
+        bail!(
+            "Fee payer {fee_payer} has {} SOL but creating a vote account needs {} SOL: {} SOL \
+             rent-exempt reserve for {} bytes plus a {} SOL fee",
+            lamports_to_sol(balance),
+            lamports_to_sol(required),
+            lamports_to_sol(rent_exempt_reserve),
+            VoteStateV4::size_of(),
+            lamports_to_sol(fee)
+        );
+    }
+
+    Ok(())
+}
+
 async fn process_create_vote_account(
     ctx: &ScillaContext,
     vote_account_keypair_path: &PathBuf,
@@ -167,24 +287,13 @@ async fn process_create_vote_account(
     withdraw_keypair_path: &PathBuf,
     commission: u8,
 ) -> anyhow::Result<()> {
-    let vote_account_keypair = read_keypair_from_path(vote_account_keypair_path)?;
-    let identity_keypair = read_keypair_from_path(identity_keypair_path)?;
-    let withdraw_keypair = read_keypair_from_path(withdraw_keypair_path)?;
-    let vote_account_pubkey = vote_account_keypair.pubkey();
-    let identity_pubkey = identity_keypair.pubkey();
-    let withdrawer_pubkey = withdraw_keypair.pubkey();
+    let mut signers = SignerSet::default();
+    let vote_account_pubkey = signers.read(vote_account_keypair_path)?;
+    let identity_pubkey = signers.read(identity_keypair_path)?;
+    let withdrawer_pubkey = signers.read(withdraw_keypair_path)?;
     let fee_payer_pubkey = ctx.pubkey();
 
-    if fee_payer_pubkey == &vote_account_pubkey {
-        bail!(
-            "Fee payer {fee_payer_pubkey} cannot be the same as vote account {vote_account_pubkey}"
-        );
-    }
-    if vote_account_pubkey == identity_pubkey {
-        bail!(
-            "Vote account {vote_account_pubkey} cannot be the same as identity {identity_pubkey}"
-        );
-    }
+    check_vote_account_keys(fee_payer_pubkey, &vote_account_pubkey, &identity_pubkey)?;
 
     // checking if vote account already exists
     if let Ok(response) = ctx.rpc().get_account(&vote_account_pubkey).await {
@@ -217,37 +326,51 @@ async fn process_create_vote_account(
         CreateVoteAccountConfig::default(),
     );
 
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let fee = ctx
+        .rpc()
+        .get_fee_for_message(&Message::new_with_blockhash(
+            &instructions,
+            Some(fee_payer_pubkey),
+            &blockhash,
+        ))
+        .await?;
+    let balance = ctx.rpc().get_balance(fee_payer_pubkey).await?;
+    check_vote_account_funding(fee_payer_pubkey, balance, required_balance, fee)?;
+
     let signature = build_and_send_tx(
         ctx,
         &instructions,
-        &[ctx.keypair(), &vote_account_keypair, &identity_keypair],
+        &signers.signers_for(ctx.keypair()?, &instructions)?,
     )
     .await?;
 
     println!(
         "{} {}",
-        style("Vote account created successfully!").green().bold(),
-        style(format!("Signature: {signature}")).cyan()
+        theme::success("Vote account created successfully!").bold(),
+        theme::info(format!("Signature: {signature}"))
     );
+    print_explorer_link(ctx, &signature);
     println!(
         "{} {}",
-        style("Vote account address:").green(),
-        style(vote_account_pubkey).cyan()
+        theme::success("Vote account address:"),
+        theme::info(vote_account_pubkey)
     );
 
     Ok(())
 }
 
-async fn process_authorize_voter(
+async fn prepare_authorize_voter(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
     authorized_keypair_path: &PathBuf,
     new_authorized_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
-    let authorized = read_keypair_from_path(authorized_keypair_path)?;
+) -> anyhow::Result<AuthorityChange> {
+    let authorized = prompt_keypair_from_path(authorized_keypair_path)?;
     let authorized_pubkey = authorized.pubkey();
 
-    let (vote_account, epoch_info) = fetch_account_with_epoch(ctx, vote_account_pubkey).await?;
+    let (vote_account, epoch_info) =
+        fetch_account_with_epoch(ctx.provider(), vote_account_pubkey).await?;
 
     if vote_account.owner != solana_vote_interface::program::id() {
         bail!("{vote_account_pubkey} is not a vote account");
@@ -281,15 +404,37 @@ async fn process_authorize_voter(
         VoteAuthorize::Voter,
     );
 
-    let signature = build_and_send_tx(ctx, &[vote_ix], &[ctx.keypair(), &authorized]).await?;
+    Ok(AuthorityChange {
+        account: *vote_account_pubkey,
+        authority_type: "Voter",
+        current: current_authorized_voter,
+        new: *new_authorized_pubkey,
+        signer: authorized,
+        instruction: vote_ix,
+    })
+}
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
+/// Outcome of a confirmed withdrawal from a vote account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawVoteResult {
+    pub signature: Signature,
+    pub vote_account: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+}
 
-    Ok(())
+impl WithdrawVoteResult {
+    fn render(&self, ctx: &ScillaContext) {
+        println!(
+            "{} {}\n{}\n{}\n{}",
+            theme::success("Withdrawn from vote account!").bold(),
+            theme::warning(format!("Vote Account: {}", self.vote_account)),
+            theme::warning(format!("To Recipient: {}", self.recipient)),
+            theme::info(format!("Amount: {} SOL", lamports_to_sol(self.lamports))),
+            theme::info(format!("Signature: {}", self.signature))
+        );
+        print_explorer_link(ctx, &self.signature);
+    }
 }
 
 async fn process_sol_withdraw_from_vote_account(
@@ -297,9 +442,16 @@ async fn process_sol_withdraw_from_vote_account(
     vote_account_pubkey: &Pubkey,
     authorized_withdrawer_keypair_path: &PathBuf,
     recipient_address: &Pubkey,
-    amount: u64,
-) -> anyhow::Result<()> {
-    let authorized_withdrawer = read_keypair_from_path(authorized_withdrawer_keypair_path)?;
+    amount: WithdrawAmount,
+) -> anyhow::Result<WithdrawVoteResult> {
+    if recipient_address == vote_account_pubkey {
+        bail!(
+            "Recipient {recipient_address} cannot be the vote account being withdrawn from. Enter \
+             the address that should receive the SOL"
+        );
+    }
+
+    let authorized_withdrawer = prompt_keypair_from_path(authorized_withdrawer_keypair_path)?;
     let withdrawer_pubkey = authorized_withdrawer.pubkey();
 
     let vote_account = ctx
@@ -323,27 +475,38 @@ async fn process_sol_withdraw_from_vote_account(
         );
     }
 
+    // percentages apply to what is left above the rent-exempt reserve
+    let rent_reserve = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(vote_account.data.len())
+        .await?;
+    let withdrawable = vote_account.lamports.saturating_sub(rent_reserve);
+    let amount_lamports = amount.to_lamports(withdrawable);
+
+    if amount_lamports == 0 {
+        bail!("Nothing to withdraw: {amount} is 0 SOL");
+    }
+
     let withdraw_ix = withdraw(
         vote_account_pubkey,
         &withdrawer_pubkey,
-        amount,
+        amount_lamports,
         recipient_address,
     );
 
     let signature = build_and_send_tx(
         ctx,
         &[withdraw_ix],
-        &[ctx.keypair(), &authorized_withdrawer],
+        &[ctx.keypair()?, &authorized_withdrawer],
     )
     .await?;
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
-
-    Ok(())
+    Ok(WithdrawVoteResult {
+        signature,
+        vote_account: *vote_account_pubkey,
+        recipient: *recipient_address,
+        lamports: amount_lamports,
+    })
 }
 
 async fn close_vote_account(
@@ -352,7 +515,14 @@ async fn close_vote_account(
     withdraw_authority_keypair_path: &PathBuf,
     destination_pubkey: &Pubkey,
 ) -> anyhow::Result<()> {
-    let withdraw_authority = read_keypair_from_path(withdraw_authority_keypair_path)?;
+    if destination_pubkey == vote_account_pubkey {
+        bail!(
+            "Destination {destination_pubkey} cannot be the vote account being closed. Enter the \
+             address that should receive the remaining SOL"
+        );
+    }
+
+    let withdraw_authority = prompt_keypair_from_path(withdraw_authority_keypair_path)?;
     let vote_account_status = ctx
         .rpc()
         .get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
@@ -388,13 +558,14 @@ async fn close_vote_account(
     );
 
     let signature =
-        build_and_send_tx(ctx, &[withdraw_ix], &[ctx.keypair(), &withdraw_authority]).await?;
+        build_and_send_tx(ctx, &[withdraw_ix], &[ctx.keypair()?, &withdraw_authority]).await?;
 
     println!(
         "{} {}",
-        style("Vote account closed! Signature:").green().bold(),
-        style(signature).cyan()
+        theme::success("Vote account closed! Signature:").bold(),
+        theme::info(signature)
     );
+    print_explorer_link(ctx, &signature);
 
     Ok(())
 }
@@ -403,9 +574,7 @@ async fn process_fetch_vote_account(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
 ) -> anyhow::Result<()> {
-    let vote_account = ctx
-        .rpc()
-        .get_account(vote_account_pubkey)
+    let vote_account = read_account(ctx, vote_account_pubkey)
         .await
         .map_err(|_| anyhow!("{vote_account_pubkey} account does not exist"))?;
 
@@ -434,16 +603,27 @@ async fn process_fetch_vote_account(
         .map(|(_, v)| v.to_string())
         .unwrap_or_else(|| vote_state.node_pubkey.to_string());
 
+    if ctx.output_format() == OutputFormat::Json {
+        return print_json(&json!({
+            "address": vote_account_pubkey.to_string(),
+            "lamports": vote_account.lamports,
+            "validatorIdentity": vote_state.node_pubkey.to_string(),
+            "voteAuthority": vote_authority,
+            "withdrawAuthority": vote_state.authorized_withdrawer.to_string(),
+            "credits": vote_state.credits(),
+            "commissionBps": vote_state.inflation_rewards_commission_bps,
+            "rootSlot": vote_state.root_slot,
+            "lastTimestamp": vote_state.last_timestamp.timestamp,
+            "lastTimestampSlot": vote_state.last_timestamp.slot,
+        }));
+    }
+
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
         ])
         .add_row(vec![
             Cell::new("Account Balance"),
@@ -475,8 +655,161 @@ async fn process_fetch_vote_account(
             )),
         ]);
 
-    println!("\n{}", style("VOTE ACCOUNT INFORMATION").green().bold());
+    println!("\n{}", theme::success("VOTE ACCOUNT INFORMATION").bold());
+    println!("{table}");
+
+    Ok(())
+}
+
+async fn process_verify_identity(
+    ctx: &ScillaContext,
+    vote_account_pubkey: &Pubkey,
+) -> anyhow::Result<()> {
+    let vote_account = read_account(ctx, vote_account_pubkey)
+        .await
+        .map_err(|_| anyhow!("{vote_account_pubkey} account does not exist"))?;
+
+    if vote_account.owner != solana_vote_interface::program::id() {
+        bail!("{vote_account_pubkey} is not a vote account");
+    }
+
+    let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
+        .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
+    let identity = vote_state.node_pubkey.to_string();
+
+    let (cluster_nodes, vote_accounts) = tokio::try_join!(
+        ctx.rpc().get_cluster_nodes(),
+        ctx.rpc()
+            .get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
+                vote_pubkey: Some(vote_account_pubkey.to_string()),
+                ..RpcGetVoteAccountsConfig::default()
+            }),
+    )?;
+
+    let gossip_node = cluster_nodes.iter().find(|node| node.pubkey == identity);
+
+    let voting_status = if let Some(info) = vote_accounts.current.first() {
+        Some(("Current", info))
+    } else {
+        vote_accounts
+            .delinquent
+            .first()
+            .map(|info| ("Delinquent", info))
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Vote Account"),
+            Cell::new(vote_account_pubkey),
+        ])
+        .add_row(vec![Cell::new("Validator Identity"), Cell::new(&identity)])
+        .add_row(vec![
+            Cell::new("In Gossip"),
+            match gossip_node {
+                Some(_) => theme::success_cell("Yes"),
+                None => theme::error_cell("No"),
+            },
+        ]);
+
+    if let Some(node) = gossip_node {
+        table
+            .add_row(vec![
+                Cell::new("Gossip Address"),
+                Cell::new(
+                    node.gossip
+                        .map(|addr| addr.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ])
+            .add_row(vec![
+                Cell::new("Version"),
+                Cell::new(node.version.as_deref().unwrap_or("-")),
+            ]);
+    }
+
+    match voting_status {
+        Some((status, info)) => {
+            table
+                .add_row(vec![
+                    Cell::new("Voting Status"),
+                    if status == "Current" {
+                        theme::success_cell(status)
+                    } else {
+                        theme::warning_cell(status)
+                    },
+                ])
+                .add_row(vec![
+                    Cell::new("Activated Stake"),
+                    Cell::new(format!("{} SOL", lamports_to_sol(info.activated_stake))),
+                ])
+                .add_row(vec![Cell::new("Last Vote"), Cell::new(info.last_vote)]);
+        }
+        None => {
+            table.add_row(vec![
+                Cell::new("Voting Status"),
+                theme::error_cell("Not in vote accounts"),
+            ]);
+        }
+    }
+
+    println!("\n{}", theme::success("VOTE ACCOUNT IDENTITY CHECK").bold());
     println!("{table}");
 
+    if gossip_node.is_none() {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "Identity {identity} is not visible in gossip; the node may be offline or the \
+                 vote account may point at the wrong identity"
+            ))
+        );
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::constants::LAMPORTS_PER_SOL};
+
+    #[test]
+    fn test_check_vote_account_keys() {
+        let fee_payer = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let identity = Pubkey::new_unique();
+
+        assert!(check_vote_account_keys(&fee_payer, &vote_account, &identity).is_ok());
+        // the fee payer may also be the validator identity
+        assert!(check_vote_account_keys(&fee_payer, &vote_account, &fee_payer).is_ok());
+
+        let err = check_vote_account_keys(&vote_account, &vote_account, &identity).unwrap_err();
+        assert!(err.to_string().contains("Fee payer"));
+
+        let err = check_vote_account_keys(&fee_payer, &vote_account, &vote_account).unwrap_err();
+        assert!(err.to_string().contains("identity"));
+    }
+
+    #[test]
+    fn test_check_vote_account_funding_names_rent() {
+        let fee_payer = Pubkey::new_unique();
+        let rent = 27_074_400;
+        let fee = 15_000;
+
+        assert!(check_vote_account_funding(&fee_payer, rent + fee, rent, fee).is_ok());
+
+        let err = check_vote_account_funding(&fee_payer, rent, rent, fee)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("0.0270744 SOL rent-exempt reserve"));
+        assert!(err.contains(&format!("{} bytes", VoteStateV4::size_of())));
+        assert!(err.contains("0.000015 SOL fee"));
+
+        assert!(check_vote_account_funding(&fee_payer, LAMPORTS_PER_SOL, rent, fee).is_ok());
+    }
+}
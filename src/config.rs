@@ -1,13 +1,28 @@
 use {
     crate::{
         commands::config::generate_config,
-        constants::{DEFAULT_KEYPAIR_PATH, DEVNET_RPC, SCILLA_CONFIG_RELATIVE_PATH},
+        constants::{
+            DEFAULT_AIRDROP_RETRIES, DEFAULT_CONFIRM_TIMEOUT_SECS, DEFAULT_KEYPAIR_PATH,
+            DEVNET_GENESIS_HASH, DEVNET_RPC, MAINNET_GENESIS_HASH, SCILLA_CONFIG_RELATIVE_PATH,
+            TESTNET_GENESIS_HASH,
+        },
         error::ScillaError,
+        misc::helpers::SolAmount,
+        ui::theme,
     },
-    console::style,
+    clap::ValueEnum,
     serde::{Deserialize, Serialize},
     solana_commitment_config::CommitmentLevel,
-    std::{env::home_dir, fs, path::PathBuf},
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    std::{
+        collections::BTreeMap,
+        env::home_dir,
+        fmt, fs,
+        io::{self, IsTerminal},
+        path::PathBuf,
+        str::FromStr,
+    },
 };
 
 pub fn scilla_config_path() -> PathBuf {
@@ -35,25 +50,386 @@ where
     Ok(expand_tilde(&s))
 }
 
+fn default_keypair_path() -> PathBuf {
+    home_dir()
+        .expect("Could not determine home directory")
+        .join(DEFAULT_KEYPAIR_PATH)
+}
+
+fn default_confirm_timeout_secs() -> u64 {
+    DEFAULT_CONFIRM_TIMEOUT_SECS
+}
+
+fn default_airdrop_retries() -> u32 {
+    DEFAULT_AIRDROP_RETRIES
+}
+
+fn deserialize_optional_pubkey<'de, D>(deserializer: D) -> Result<Option<Pubkey>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    s.map(|s| {
+        Pubkey::from_str(s.trim())
+            .map_err(|e| serde::de::Error::custom(format!("invalid pubkey {s:?}: {e}")))
+    })
+    .transpose()
+}
+
+fn serialize_optional_pubkey<S>(pubkey: &Option<Pubkey>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match pubkey {
+        Some(pubkey) => serializer.serialize_str(&pubkey.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_wallets_with_tilde<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, PathBuf>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let wallets: BTreeMap<String, String> = Deserialize::deserialize(deserializer)?;
+    Ok(wallets
+        .into_iter()
+        .map(|(name, path)| (name, expand_tilde(&path)))
+        .collect())
+}
+
+/// Cluster the configured RPC URL points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+    Localnet,
+    Custom(String),
+}
+
+impl Cluster {
+    pub fn from_rpc_url(rpc_url: &str) -> Self {
+        let url = rpc_url.to_lowercase();
+        if url.contains("devnet") {
+            Cluster::Devnet
+        } else if url.contains("testnet") {
+            Cluster::Testnet
+        } else if url.contains("mainnet") {
+            Cluster::Mainnet
+        } else if url.contains("localhost") || url.contains("127.0.0.1") {
+            Cluster::Localnet
+        } else {
+            Cluster::Custom(rpc_url.to_string())
+        }
+    }
+
+    /// Public cluster a genesis hash belongs to, `None` for localnets and forks
+    pub fn from_genesis_hash(genesis_hash: &str) -> Option<Self> {
+        match genesis_hash {
+            MAINNET_GENESIS_HASH => Some(Cluster::Mainnet),
+            DEVNET_GENESIS_HASH => Some(Cluster::Devnet),
+            TESTNET_GENESIS_HASH => Some(Cluster::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cluster::Mainnet => write!(f, "mainnet-beta"),
+            Cluster::Devnet => write!(f, "devnet"),
+            Cluster::Testnet => write!(f, "testnet"),
+            Cluster::Localnet => write!(f, "localnet"),
+            Cluster::Custom(url) => write!(f, "custom ({url})"),
+        }
+    }
+}
+
+/// Block explorer used for the links printed after a transaction
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Explorer {
+    #[default]
+    SolanaExplorer,
+    Solscan,
+    #[serde(rename = "solanafm")]
+    SolanaFm,
+}
+
+impl Explorer {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Explorer::SolanaExplorer,
+            Explorer::Solscan,
+            Explorer::SolanaFm,
+        ]
+    }
+
+    pub fn tx_url(&self, signature: &Signature, cluster: &Cluster) -> String {
+        let base = match self {
+            Explorer::SolanaExplorer => "https://explorer.solana.com",
+            Explorer::Solscan => "https://solscan.io",
+            Explorer::SolanaFm => "https://solana.fm",
+        };
+        format!("{base}/tx/{signature}{}", self.cluster_query(cluster))
+    }
+
+    fn cluster_query(&self, cluster: &Cluster) -> String {
+        match (self, cluster) {
+            (Explorer::SolanaFm, Cluster::Mainnet) => "?cluster=mainnet-alpha".to_string(),
+            (Explorer::SolanaFm, Cluster::Devnet) => "?cluster=devnet-solana".to_string(),
+            (Explorer::SolanaFm, Cluster::Testnet) => "?cluster=testnet-solana".to_string(),
+            (Explorer::SolanaFm, Cluster::Localnet) => "?cluster=localnet-solana".to_string(),
+            (Explorer::SolanaFm, Cluster::Custom(url)) => {
+                format!("?cluster=custom&customUrl={url}")
+            }
+            (_, Cluster::Mainnet) => String::new(),
+            (_, Cluster::Devnet) => "?cluster=devnet".to_string(),
+            (_, Cluster::Testnet) => "?cluster=testnet".to_string(),
+            (_, Cluster::Localnet) => "?cluster=custom&customUrl=http://localhost:8899".to_string(),
+            (_, Cluster::Custom(url)) => format!("?cluster=custom&customUrl={url}"),
+        }
+    }
+}
+
+impl fmt::Display for Explorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Explorer::SolanaExplorer => write!(f, "Solana Explorer"),
+            Explorer::Solscan => write!(f, "Solscan"),
+            Explorer::SolanaFm => write!(f, "SolanaFM"),
+        }
+    }
+}
+
+/// How list-style views and lookups print their data
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Csv,
+    /// Lookups print their fields as JSON and list views an array of rows
+    Json,
+}
+
+impl OutputFormat {
+    pub fn all() -> Vec<Self> {
+        vec![OutputFormat::Table, OutputFormat::Csv, OutputFormat::Json]
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Table => write!(f, "Table"),
+            OutputFormat::Csv => write!(f, "CSV"),
+            OutputFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// Color scheme for all output and prompts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    Default,
+    /// Bold text and colors that stay readable on light backgrounds and do not
+    /// rely on telling red from green
+    HighContrast,
+    NoColor,
+}
+
+impl Theme {
+    pub fn all() -> Vec<Self> {
+        vec![Theme::Default, Theme::HighContrast, Theme::NoColor]
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Default => write!(f, "Default"),
+            Theme::HighContrast => write!(f, "High contrast"),
+            Theme::NoColor => write!(f, "No color"),
+        }
+    }
+}
+
+/// Largest amount a single SOL transfer may send. Either one cap for every
+/// cluster, or a table with a cap per cluster where a missing cluster has none.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(untagged)]
+pub enum TransferLimit {
+    Everywhere(SolAmount),
+    PerCluster(ClusterTransferLimits),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ClusterTransferLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mainnet: Option<SolAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub devnet: Option<SolAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub testnet: Option<SolAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localnet: Option<SolAmount>,
+    /// Any RPC URL not recognized as one of the clusters above
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom: Option<SolAmount>,
+}
+
+impl TransferLimit {
+    pub fn for_cluster(&self, cluster: &Cluster) -> Option<SolAmount> {
+        match self {
+            TransferLimit::Everywhere(limit) => Some(*limit),
+            TransferLimit::PerCluster(limits) => match cluster {
+                Cluster::Mainnet => limits.mainnet,
+                Cluster::Devnet => limits.devnet,
+                Cluster::Testnet => limits.testnet,
+                Cluster::Localnet => limits.localnet,
+                Cluster::Custom(_) => limits.custom,
+            },
+        }
+    }
+}
+
+impl fmt::Display for TransferLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferLimit::Everywhere(limit) => write!(f, "{limit} SOL on every cluster"),
+            TransferLimit::PerCluster(limits) => {
+                let caps: Vec<String> = [
+                    ("mainnet", limits.mainnet),
+                    ("devnet", limits.devnet),
+                    ("testnet", limits.testnet),
+                    ("localnet", limits.localnet),
+                    ("custom", limits.custom),
+                ]
+                .into_iter()
+                .filter_map(|(cluster, limit)| limit.map(|limit| format!("{cluster} {limit} SOL")))
+                .collect();
+
+                if caps.is_empty() {
+                    write!(f, "No limit")
+                } else {
+                    write!(f, "{}", caps.join(", "))
+                }
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct ScillaConfig {
     pub rpc_url: String,
     pub commitment_level: CommitmentLevel,
-    #[serde(deserialize_with = "deserialize_path_with_tilde")]
+    /// Commitment for read-only lookups; falls back to `commitment_level`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_commitment: Option<CommitmentLevel>,
+    #[serde(
+        default = "default_keypair_path",
+        deserialize_with = "deserialize_path_with_tilde"
+    )]
     pub keypair_path: PathBuf,
+    /// Address to inspect without a keypair. When set, `keypair_path` is not
+    /// read and commands that sign are disabled.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_pubkey",
+        serialize_with = "serialize_optional_pubkey"
+    )]
+    pub watch_address: Option<Pubkey>,
+    #[serde(default)]
+    pub explorer: Explorer,
+    /// `csv` prints list views as CSV for spreadsheet import
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    /// `no-color` is also forced by a non-empty `NO_COLOR` env var
+    #[serde(default)]
+    pub theme: Theme,
+    /// How long to wait for a sent transaction to confirm
+    #[serde(default = "default_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+    /// How often a refused airdrop is retried, with exponential backoff
+    #[serde(default = "default_airdrop_retries")]
+    pub airdrop_retries: u32,
+    /// Recipient pre-filled at the transfer prompt
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_pubkey",
+        serialize_with = "serialize_optional_pubkey"
+    )]
+    pub default_recipient: Option<Pubkey>,
+    /// Amount pre-filled at the transfer prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_amount: Option<SolAmount>,
+    /// Cap on the SOL a single transfer may send; no cap when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transfer_sol: Option<TransferLimit>,
+    /// Summarize the recipient's account and ask again before sending SOL
+    #[serde(default)]
+    pub preflight_recipient_check: bool,
+    /// Simulate every transaction and show the result before it is sent
+    #[serde(default)]
+    pub simulate_before_send: bool,
+    /// Simulate every transaction and stop there; nothing is sent
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Send a successful simulation without asking again
+    #[serde(default)]
+    pub skip_confirm: bool,
+    /// Compute-unit price added to every transaction; 0 sends without one
+    #[serde(default)]
+    pub priority_fee_micro_lamports: u64,
+    /// Answer every yes/no confirmation with yes, for scripted runs
+    #[serde(default)]
+    pub assume_yes: bool,
+    /// Let `assume_yes` apply on mainnet and unrecognized clusters too
+    #[serde(default)]
+    pub assume_yes_mainnet: bool,
+    /// Named keypairs that can be switched between during a session
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        deserialize_with = "deserialize_wallets_with_tilde"
+    )]
+    pub wallets: BTreeMap<String, PathBuf>,
 }
 
 impl Default for ScillaConfig {
     fn default() -> Self {
-        let default_keypair_path = home_dir()
-            .expect("Could not determine home directory")
-            .join(DEFAULT_KEYPAIR_PATH);
-
         Self {
             rpc_url: DEVNET_RPC.to_string(),
             commitment_level: CommitmentLevel::Confirmed,
-            keypair_path: default_keypair_path,
+            read_commitment: None,
+            keypair_path: default_keypair_path(),
+            watch_address: None,
+            explorer: Explorer::default(),
+            output_format: OutputFormat::default(),
+            theme: Theme::default(),
+            confirm_timeout_secs: DEFAULT_CONFIRM_TIMEOUT_SECS,
+            airdrop_retries: DEFAULT_AIRDROP_RETRIES,
+            default_recipient: None,
+            default_amount: None,
+            max_transfer_sol: None,
+            preflight_recipient_check: false,
+            simulate_before_send: false,
+            dry_run: false,
+            skip_confirm: false,
+            priority_fee_micro_lamports: 0,
+            assume_yes: false,
+            assume_yes_mainnet: false,
+            wallets: BTreeMap::new(),
         }
     }
 }
@@ -63,39 +439,56 @@ impl ScillaConfig {
         let scilla_config_path = scilla_config_path();
 
         if !scilla_config_path.exists() {
-            println!("{}", style("No configuration file found!").yellow().bold());
+            println!("{}", theme::warning("No configuration file found!").bold());
             println!(
                 "{}",
-                style(format!(
+                theme::info(format!(
                     "Creating config at: {}",
                     scilla_config_path.display()
                 ))
-                .cyan()
             );
             println!(
                 "{}",
-                style("Let's set up your configuration to get started.").cyan()
+                theme::info("Let's set up your configuration to get started.")
             );
 
             generate_config()?;
 
             println!(
                 "{}",
-                style("Configuration complete! Starting Scilla...")
-                    .green()
-                    .bold()
+                theme::success("Configuration complete! Starting Scilla...").bold()
             );
         }
 
         println!(
             "{}",
-            style(format!("Using Scilla config path : {scilla_config_path:?}")).dim()
+            theme::muted(format!("Using Scilla config path : {scilla_config_path:?}"))
         );
         let data = fs::read_to_string(scilla_config_path)?;
         let config: ScillaConfig = toml::from_str(&data)?;
         Ok(config)
     }
 
+    /// Loads the config for a one-shot subcommand. The config path goes to
+    /// stderr so stdout carries only the command's output, and without a
+    /// terminal a missing config is an error rather than the setup wizard.
+    pub fn load_for_subcommand() -> Result<ScillaConfig, ScillaError> {
+        let scilla_config_path = scilla_config_path();
+
+        if !scilla_config_path.exists() {
+            if !io::stdin().is_terminal() {
+                return Err(ScillaError::ConfigMissingNonInteractive(scilla_config_path));
+            }
+            return Self::load();
+        }
+
+        eprintln!(
+            "{}",
+            theme::muted(format!("Using Scilla config path : {scilla_config_path:?}"))
+        );
+        Self::load_from_path(&scilla_config_path)
+    }
+
     pub fn load_from_path(path: &std::path::Path) -> Result<ScillaConfig, ScillaError> {
         if !path.exists() {
             return Err(ScillaError::ConfigPathDoesNotExist);
@@ -150,5 +543,300 @@ commitment-level = "confirmed"
         assert_eq!(config.rpc_url, "https://api.mainnet-beta.solana.com");
         assert_eq!(config.commitment_level, CommitmentLevel::Confirmed);
         assert_eq!(config.keypair_path, home.join("my/key.json"));
+        assert_eq!(config.explorer, Explorer::SolanaExplorer);
+        assert_eq!(config.read_commitment, None);
+        assert_eq!(config.confirm_timeout_secs, DEFAULT_CONFIRM_TIMEOUT_SECS);
+        assert_eq!(config.airdrop_retries, DEFAULT_AIRDROP_RETRIES);
+        assert_eq!(config.default_recipient, None);
+        assert!(config.default_amount.is_none());
+        assert_eq!(config.watch_address, None);
+        assert_eq!(config.theme, Theme::Default);
+    }
+
+    #[test]
+    fn test_theme_names_in_toml() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/keys/id.json"
+commitment-level = "confirmed"
+theme = "high-contrast"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Config with a theme should load successfully");
+
+        assert_eq!(config.theme, Theme::HighContrast);
+    }
+
+    #[test]
+    fn test_load_from_path_watch_only_without_keypair_path() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+        let watched = Pubkey::new_unique();
+
+        fs::write(
+            &config_path,
+            format!(
+                r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+commitment-level = "confirmed"
+watch-address = "{watched}"
+"#
+            ),
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Watch-only config should load without a keypair path");
+
+        assert_eq!(config.watch_address, Some(watched));
+        assert_eq!(config.keypair_path, default_keypair_path());
+    }
+
+    #[test]
+    fn test_load_from_path_wallets_with_tilde_expansion() {
+        let home = env::home_dir().expect("HOME should be set");
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "~/hot.json"
+commitment-level = "confirmed"
+
+[wallets]
+hot = "~/hot.json"
+stake-authority = "/keys/stake.json"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Config with wallets should load successfully");
+
+        assert_eq!(config.wallets.len(), 2);
+        assert_eq!(config.wallets["hot"], home.join("hot.json"));
+        assert_eq!(
+            config.wallets["stake-authority"],
+            PathBuf::from("/keys/stake.json")
+        );
+    }
+
+    #[test]
+    fn test_cluster_from_rpc_url() {
+        assert_eq!(
+            Cluster::from_rpc_url("https://api.mainnet-beta.solana.com"),
+            Cluster::Mainnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://api.devnet.solana.com"),
+            Cluster::Devnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("http://127.0.0.1:8899"),
+            Cluster::Localnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://rpc.example.com"),
+            Cluster::Custom("https://rpc.example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cluster_from_genesis_hash() {
+        assert_eq!(
+            Cluster::from_genesis_hash(MAINNET_GENESIS_HASH),
+            Some(Cluster::Mainnet)
+        );
+        assert_eq!(
+            Cluster::from_genesis_hash(DEVNET_GENESIS_HASH),
+            Some(Cluster::Devnet)
+        );
+        assert_eq!(
+            Cluster::from_genesis_hash(TESTNET_GENESIS_HASH),
+            Some(Cluster::Testnet)
+        );
+        assert_eq!(
+            Cluster::from_genesis_hash(&Pubkey::new_unique().to_string()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_explorer_tx_url_per_cluster() {
+        let signature = Signature::default();
+
+        assert_eq!(
+            Explorer::SolanaExplorer.tx_url(&signature, &Cluster::Mainnet),
+            format!("https://explorer.solana.com/tx/{signature}")
+        );
+        assert_eq!(
+            Explorer::Solscan.tx_url(&signature, &Cluster::Devnet),
+            format!("https://solscan.io/tx/{signature}?cluster=devnet")
+        );
+        assert_eq!(
+            Explorer::SolanaFm.tx_url(&signature, &Cluster::Testnet),
+            format!("https://solana.fm/tx/{signature}?cluster=testnet-solana")
+        );
+    }
+
+    #[test]
+    fn test_explorer_parses_from_toml() {
+        let config: ScillaConfig = toml::from_str(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+explorer = "solanafm"
+"#,
+        )
+        .expect("Config with explorer should parse");
+
+        assert_eq!(config.explorer, Explorer::SolanaFm);
+        assert_eq!(config.output_format, OutputFormat::Table);
+    }
+
+    #[test]
+    fn test_output_format_parses_from_toml() {
+        let config: ScillaConfig = toml::from_str(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+output-format = "csv"
+"#,
+        )
+        .expect("Config with output format should parse");
+
+        assert_eq!(config.output_format, OutputFormat::Csv);
+    }
+
+    #[test]
+    fn test_read_commitment_parses_from_toml() {
+        let config: ScillaConfig = toml::from_str(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "finalized"
+read-commitment = "processed"
+"#,
+        )
+        .expect("Config with read commitment should parse");
+
+        assert_eq!(config.commitment_level, CommitmentLevel::Finalized);
+        assert_eq!(config.read_commitment, Some(CommitmentLevel::Processed));
+    }
+
+    #[test]
+    fn test_confirm_timeout_parses_from_toml() {
+        let config: ScillaConfig = toml::from_str(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+confirm-timeout-secs = 15
+"#,
+        )
+        .expect("Config with confirm timeout should parse");
+
+        assert_eq!(config.confirm_timeout_secs, 15);
+    }
+
+    #[test]
+    fn test_transfer_defaults_parse_from_toml() {
+        let recipient = Pubkey::new_unique();
+        let config: ScillaConfig = toml::from_str(&format!(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+default-recipient = "{recipient}"
+default-amount = 0.5
+"#
+        ))
+        .expect("Config with transfer defaults should parse");
+
+        assert_eq!(config.default_recipient, Some(recipient));
+        assert_eq!(config.default_amount.map(|a| a.value()), Some(0.5));
+
+        let serialized = toml::to_string_pretty(&config).expect("Config should serialize");
+        assert!(serialized.contains(&format!("default-recipient = \"{recipient}\"")));
+    }
+
+    #[test]
+    fn test_invalid_transfer_defaults_are_rejected() {
+        let bad_recipient = toml::from_str::<ScillaConfig>(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+default-recipient = "not-a-pubkey"
+"#,
+        );
+        assert!(bad_recipient.is_err());
+
+        let bad_amount = toml::from_str::<ScillaConfig>(
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+default-amount = -1.0
+"#,
+        );
+        assert!(bad_amount.is_err());
+    }
+
+    #[test]
+    fn test_max_transfer_sol_parses_from_toml() {
+        let base = r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+"#;
+
+        let everywhere: ScillaConfig = toml::from_str(&format!("{base}max-transfer-sol = 5\n"))
+            .expect("Config with a single transfer cap should parse");
+        let limit = everywhere.max_transfer_sol.unwrap();
+        assert_eq!(
+            limit.for_cluster(&Cluster::Mainnet).map(|a| a.value()),
+            Some(5.0)
+        );
+        assert_eq!(
+            limit.for_cluster(&Cluster::Devnet).map(|a| a.value()),
+            Some(5.0)
+        );
+
+        let per_cluster: ScillaConfig = toml::from_str(&format!(
+            "{base}\n[max-transfer-sol]\nmainnet = 1.5\ndevnet = 1000\n"
+        ))
+        .expect("Config with per-cluster transfer caps should parse");
+        let limit = per_cluster.max_transfer_sol.unwrap();
+        assert_eq!(
+            limit.for_cluster(&Cluster::Mainnet).map(|a| a.value()),
+            Some(1.5)
+        );
+        assert_eq!(
+            limit.for_cluster(&Cluster::Devnet).map(|a| a.value()),
+            Some(1000.0)
+        );
+        assert!(limit.for_cluster(&Cluster::Testnet).is_none());
+
+        let serialized = toml::to_string_pretty(&per_cluster).expect("Config should serialize");
+        assert!(serialized.contains("[max-transfer-sol]"));
+
+        assert!(
+            toml::from_str::<ScillaConfig>(&format!("{base}\n[max-transfer-sol]\nmainet = 1\n"))
+                .is_err()
+        );
     }
 }
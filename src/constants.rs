@@ -2,6 +2,12 @@ pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
 pub const SCILLA_CONFIG_RELATIVE_PATH: &str = ".config/scilla.toml";
 
+pub const SCILLA_HISTORY_RELATIVE_PATH: &str = ".config/scilla_history";
+
+pub const SCILLA_HISTORY_MAX_ENTRIES: usize = 500;
+
+pub const HISTORY_DISPLAY_LIMIT: usize = 50;
+
 pub const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
 
 pub const ACTIVE_STAKE_EPOCH_BOUND: u64 = u64::MAX;
@@ -12,8 +18,124 @@ pub const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
 
 pub const TESTNET_RPC: &str = "https://api.testnet.solana.com";
 
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+/// Typed to confirm a send on mainnet, where a yes/no answer is too easy to
+/// give by reflex
+pub const MAINNET_CONFIRM_PHRASE: &str = "send on mainnet";
+
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+
+pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+/// Target slot duration, used to turn slot counts into wall-clock estimates
+pub const DEFAULT_MS_PER_SLOT: u64 = 400;
+
+/// Julian year, for turning per-epoch figures into annual ones
+pub const MS_PER_YEAR: f64 = 31_557_600_000.0;
+
 pub const DEFAULT_EPOCH_LIMIT: usize = 10;
 
+pub const DEFAULT_CONFIRM_TIMEOUT_SECS: u64 = 60;
+
+/// How often a sent transaction's status is checked while waiting for it
+pub const CONFIRM_POLL_INTERVAL_MS: u64 = 500;
+
+/// How long a batch keeps signing with one blockhash before fetching another.
+/// Blockhashes stay valid for 150 slots, about a minute at 400ms per slot.
+pub const BATCH_BLOCKHASH_MAX_AGE_SECS: u64 = 30;
+
+pub const MONITOR_POLL_INTERVAL_MS: u64 = 500;
+
+/// JSON-RPC error code for a method the node does not expose
+pub const JSON_RPC_METHOD_NOT_FOUND: i64 = -32601;
+
+pub const LEADER_SCHEDULE_WINDOW: u64 = 20;
+
+pub const STAKE_DISTRIBUTION_TOP_N: usize = 20;
+
+pub const SKIP_RATE_WARN_PERCENT: f64 = 5.0;
+
+pub const SKIP_RATE_CRITICAL_PERCENT: f64 = 15.0;
+
+pub const RPC_SLOTS_BEHIND_WARN: u64 = 50;
+
+/// Rounds of calls the RPC benchmark runs unless told otherwise
+pub const RPC_BENCHMARK_DEFAULT_ROUNDS: u32 = 20;
+
 pub const STAKE_HISTORY_SYSVAR_ADDR: &str = "SysvarStakeHistory1111111111111111111111111";
 
 pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+pub const STAKE_AUTHORIZED_STAKER_OFFSET: usize = 12;
+
+pub const STAKE_AUTHORIZED_WITHDRAWER_OFFSET: usize = 44;
+
+pub const VOTE_NODE_PUBKEY_OFFSET: usize = 4;
+
+pub const VOTE_AUTHORIZED_WITHDRAWER_OFFSET: usize = 36;
+
+pub const NONCE_AUTHORITY_OFFSET: usize = 8;
+
+pub const AIRDROP_MAX_LAMPORTS_PER_REQUEST: u64 = LAMPORTS_PER_SOL;
+
+pub const DEFAULT_AIRDROP_RETRIES: u32 = 3;
+
+/// Amount pre-filled at the airdrop prompt
+pub const DEFAULT_AIRDROP_SOL: f64 = 1.0;
+
+pub const AIRDROP_RETRY_BASE_DELAY_SECS: u64 = 2;
+
+pub const STAKE_MERGES_PER_TX: usize = 8;
+
+pub const KEYPAIR_FILE_BYTES: usize = 64;
+
+/// Word counts of BIP-39 mnemonics
+pub const SEED_PHRASE_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Format version written to encrypted keypair files
+pub const ENCRYPTED_KEYPAIR_VERSION: u8 = 1;
+/// Random salt fed to Argon2id along with the passphrase
+pub const ENCRYPTED_KEYPAIR_SALT_LEN: usize = 16;
+
+/// Largest page `getSignaturesForAddress` returns
+pub const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// Most signatures `getSignatureStatuses` accepts per request
+pub const SIGNATURE_STATUSES_BATCH: usize = 256;
+
+/// Fee assumed for a plain SOL transfer at the amount prompt: one signature
+/// at the base fee
+pub const TRANSFER_FEE_ESTIMATE_LAMPORTS: u64 = 5_000;
+
+/// Compute-unit limit requested along with a priority fee. The priority fee
+/// is charged on this limit, not on the units a transaction ends up using.
+pub const PRIORITY_FEE_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Completed epochs looked back over by the stake reward history by default
+pub const STAKE_REWARD_HISTORY_DEFAULT_EPOCHS: u64 = 5;
+
+/// Longest stake reward history lookback; each epoch is a separate RPC call
+pub const STAKE_REWARD_HISTORY_MAX_EPOCHS: u64 = 50;
+
+/// Fee set aside when working out how much can be staked: two signatures, the
+/// fee payer and the new stake account
+pub const STAKE_CREATE_FEE_BUFFER_LAMPORTS: u64 = 10_000;
+
+/// Recent signatures fetched for the preflight recipient check
+pub const RECIPIENT_CHECK_SIGNATURES: usize = 10;
+
+/// Transactions listed by the account history by default
+pub const ACCOUNT_HISTORY_DEFAULT_LIMIT: usize = 10;
+
+/// How often "Watch for deposits" checks for new signatures
+pub const DEPOSIT_POLL_INTERVAL_SECS: u64 = 2;
+
+/// Most recent transactions listed in a wallet report
+pub const WALLET_REPORT_TRANSACTIONS: usize = 20;
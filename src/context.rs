@@ -1,29 +1,80 @@
 use {
-    crate::config::ScillaConfig,
-    anyhow::anyhow,
+    crate::{
+        config::{Cluster, Explorer, OutputFormat, ScillaConfig},
+        misc::{
+            helpers::{
+                SendPipeline, SolAmount, is_encrypted_keypair_file, read_keypair_with_passphrase,
+            },
+            rpc_metrics::{InstrumentedSender, RpcMetrics},
+            rpc_provider::RpcProvider,
+        },
+        prompt,
+    },
+    anyhow::{anyhow, bail},
     solana_commitment_config::CommitmentConfig,
-    solana_keypair::{EncodableKey, Keypair, Signer},
+    solana_keypair::{Keypair, Signer},
     solana_pubkey::Pubkey,
-    solana_rpc_client::nonblocking::rpc_client::RpcClient,
-    std::path::PathBuf,
+    solana_rpc_client::{nonblocking::rpc_client::RpcClient, rpc_client::RpcClientConfig},
+    std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
 };
 
 pub struct ScillaContext {
     rpc_client: RpcClient,
-    keypair: Keypair,
+    /// `None` in watch-only mode
+    keypair: Option<Keypair>,
     pubkey: Pubkey,
     keypair_path: PathBuf,
+    wallet_name: Option<String>,
+    cluster: Cluster,
+    explorer: Explorer,
+    output_format: OutputFormat,
+    read_commitment: CommitmentConfig,
+    confirm_timeout: Duration,
+    airdrop_retries: u32,
+    default_recipient: Option<Pubkey>,
+    default_amount: Option<SolAmount>,
+    max_transfer: Option<SolAmount>,
+    preflight_recipient_check: bool,
+    dry_run: bool,
+    simulate_before_send: bool,
+    skip_confirm: bool,
+    priority_fee_micro_lamports: u64,
+    rpc_metrics: Arc<RpcMetrics>,
+    last_keypair_dir: Mutex<Option<PathBuf>>,
 }
 
 impl ScillaContext {
-    pub fn keypair(&self) -> &Keypair {
-        &self.keypair
+    /// The session keypair, or an error in watch-only mode where nothing can
+    /// be signed
+    pub fn keypair(&self) -> anyhow::Result<&Keypair> {
+        self.keypair.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Watch-only mode: no keypair is loaded for {}, so nothing can be signed",
+                self.pubkey
+            )
+        })
+    }
+
+    /// Whether the session only watches `watch-address` without a keypair
+    pub fn is_watch_only(&self) -> bool {
+        self.keypair.is_none()
     }
 
     pub fn rpc(&self) -> &RpcClient {
         &self.rpc_client
     }
 
+    /// The RPC client as an `RpcProvider`, for the few reads (stake withdraw
+    /// validation and `fetch_account_with_epoch`) that tests mock. Everything
+    /// else uses [`rpc`](Self::rpc).
+    pub fn provider(&self) -> &dyn RpcProvider {
+        &self.rpc_client
+    }
+
     pub fn pubkey(&self) -> &Pubkey {
         &self.pubkey
     }
@@ -32,38 +83,209 @@ impl ScillaContext {
         &self.keypair_path
     }
 
+    /// Name of the configured wallet whose keypair is active, if any
+    pub fn wallet_name(&self) -> Option<&str> {
+        self.wallet_name.as_deref()
+    }
+
+    pub fn cluster(&self) -> &Cluster {
+        &self.cluster
+    }
+
+    pub fn explorer(&self) -> Explorer {
+        self.explorer
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    /// Overrides the config's output format, as `--output` does, until the
+    /// config is reloaded
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+    }
+
+    /// Commitment used by read-only lookups. Sends keep the client's own
+    /// commitment.
+    pub fn read_commitment(&self) -> CommitmentConfig {
+        self.read_commitment
+    }
+
+    /// Flips read-only lookups between fast (`processed`) and safe
+    /// (`finalized`) for the rest of the session; the config file is left as
+    /// is, so a reload goes back to its `read-commitment`
+    pub fn toggle_read_commitment(&mut self) -> CommitmentConfig {
+        self.read_commitment = if self.read_commitment.is_processed() {
+            CommitmentConfig::finalized()
+        } else {
+            CommitmentConfig::processed()
+        };
+        self.read_commitment
+    }
+
+    pub fn confirm_timeout(&self) -> Duration {
+        self.confirm_timeout
+    }
+
+    pub fn airdrop_retries(&self) -> u32 {
+        self.airdrop_retries
+    }
+
+    /// Recipient pre-filled at the transfer prompt
+    pub fn default_recipient(&self) -> Option<Pubkey> {
+        self.default_recipient
+    }
+
+    /// Amount pre-filled at the transfer prompt
+    pub fn default_amount(&self) -> Option<SolAmount> {
+        self.default_amount
+    }
+
+    /// Most SOL a single transfer may send on this cluster, if capped
+    pub fn max_transfer(&self) -> Option<SolAmount> {
+        self.max_transfer
+    }
+
+    /// Whether SOL transfers first show what the recipient account looks like
+    pub fn preflight_recipient_check(&self) -> bool {
+        self.preflight_recipient_check
+    }
+
+    /// Stages every transaction goes through between build and confirmation
+    pub fn send_pipeline(&self) -> SendPipeline {
+        SendPipeline::new(self.dry_run, self.simulate_before_send, self.skip_confirm)
+    }
+
+    /// Compute-unit price in micro-lamports added to every transaction; 0
+    /// means no priority fee
+    pub fn priority_fee_micro_lamports(&self) -> u64 {
+        self.priority_fee_micro_lamports
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Turns dry run on or off for the rest of the session and returns the
+    /// new state; like the read commitment toggle, a reload goes back to the
+    /// config's `dry-run`
+    pub fn toggle_dry_run(&mut self) -> bool {
+        self.dry_run = !self.dry_run;
+        self.dry_run
+    }
+
+    /// RPC calls made so far in this session, kept across reloads
+    pub fn rpc_metrics(&self) -> &RpcMetrics {
+        &self.rpc_metrics
+    }
+
+    /// Directory of the last keypair entered at a keypair prompt this session
+    pub fn last_keypair_dir(&self) -> Option<PathBuf> {
+        self.last_keypair_dir
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn remember_keypair_dir(&self, keypair_path: &Path) {
+        if let Some(dir) = keypair_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            *self
+                .last_keypair_dir
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(dir.to_path_buf());
+        }
+    }
+
+    /// Context for one-shot subcommands, which only read. It is always
+    /// watch-only, so nothing asks for a passphrase: the wallet address is
+    /// `watch-address`, or the pubkey of a plain keypair file.
+    pub fn for_subcommand(mut config: ScillaConfig) -> anyhow::Result<Self> {
+        if config.watch_address.is_none() {
+            if is_encrypted_keypair_file(&config.keypair_path) {
+                bail!(
+                    "Keypair {} is encrypted; set watch-address in the config to run subcommands \
+                     without a passphrase",
+                    config.keypair_path.display()
+                );
+            }
+            config.watch_address =
+                Some(read_keypair_with_passphrase(&config.keypair_path, None)?.pubkey());
+        }
+
+        ScillaContext::try_from(config)
+    }
+
     pub fn reload(&mut self, new_config: ScillaConfig) -> anyhow::Result<()> {
-        *self = ScillaContext::try_from(new_config)?;
+        let last_keypair_dir = self.last_keypair_dir();
+        *self = ScillaContext::build(new_config, Arc::clone(&self.rpc_metrics))?;
+        self.last_keypair_dir = Mutex::new(last_keypair_dir);
         Ok(())
     }
-}
-
-impl TryFrom<ScillaConfig> for ScillaContext {
-    type Error = anyhow::Error;
 
-    fn try_from(config: ScillaConfig) -> anyhow::Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            config.rpc_url,
-            CommitmentConfig {
+    fn build(config: ScillaConfig, rpc_metrics: Arc<RpcMetrics>) -> anyhow::Result<Self> {
+        let cluster = Cluster::from_rpc_url(&config.rpc_url);
+        let max_transfer = config
+            .max_transfer_sol
+            .and_then(|limit| limit.for_cluster(&cluster));
+        let read_commitment = CommitmentConfig {
+            commitment: config.read_commitment.unwrap_or(config.commitment_level),
+        };
+        let rpc_client = RpcClient::new_sender(
+            InstrumentedSender::new(config.rpc_url, Arc::clone(&rpc_metrics)),
+            RpcClientConfig::with_commitment(CommitmentConfig {
                 commitment: config.commitment_level,
-            },
+            }),
         );
 
-        let keypair = Keypair::read_from_file(&config.keypair_path).map_err(|e| {
-            anyhow!(
-                "Failed to read keypair from {}: {}",
-                config.keypair_path.display(),
-                e
-            )
-        })?;
+        let (keypair, pubkey) = match config.watch_address {
+            Some(address) => (None, address),
+            None => {
+                let keypair = prompt::prompt_keypair_from_path(&config.keypair_path)?;
+                let pubkey = keypair.pubkey();
+                (Some(keypair), pubkey)
+            }
+        };
 
-        let pubkey = keypair.pubkey();
+        let wallet_name = config
+            .wallets
+            .iter()
+            .find(|(_, path)| keypair.is_some() && **path == config.keypair_path)
+            .map(|(name, _)| name.clone());
 
         Ok(Self {
             rpc_client,
             keypair,
             pubkey,
             keypair_path: config.keypair_path,
+            wallet_name,
+            cluster,
+            explorer: config.explorer,
+            output_format: config.output_format,
+            read_commitment,
+            confirm_timeout: Duration::from_secs(config.confirm_timeout_secs),
+            airdrop_retries: config.airdrop_retries,
+            default_recipient: config.default_recipient,
+            default_amount: config.default_amount,
+            max_transfer,
+            preflight_recipient_check: config.preflight_recipient_check,
+            dry_run: config.dry_run,
+            simulate_before_send: config.simulate_before_send,
+            skip_confirm: config.skip_confirm,
+            priority_fee_micro_lamports: config.priority_fee_micro_lamports,
+            rpc_metrics,
+            last_keypair_dir: Mutex::new(None),
         })
     }
 }
+
+impl TryFrom<ScillaConfig> for ScillaContext {
+    type Error = anyhow::Error;
+
+    fn try_from(config: ScillaConfig) -> anyhow::Result<Self> {
+        ScillaContext::build(config, Arc::default())
+    }
+}
@@ -1,4 +1,4 @@
-use {crate::commands::CommandFlow, thiserror::Error};
+use {crate::commands::CommandFlow, std::path::PathBuf, thiserror::Error};
 
 pub type ScillaResult<T> = anyhow::Result<CommandFlow<T>>;
 
@@ -6,10 +6,20 @@ pub type ScillaResult<T> = anyhow::Result<CommandFlow<T>>;
 pub enum ScillaError {
     #[error("Scilla ScillaConfig path doesnt exists")]
     ConfigPathDoesNotExist,
+    #[error(
+        "No config file at {}; run scilla in a terminal once to create it",
+        .0.display()
+    )]
+    ConfigMissingNonInteractive(PathBuf),
     #[error("Io error")]
     IoError(#[from] std::io::Error),
     #[error("Toml Parse error")]
     TomlParseError(#[from] toml::de::Error),
+    #[error(
+        "Keypair file {} is not a valid 64-byte Solana keypair (found {found})",
+        path.display()
+    )]
+    InvalidKeypairFile { path: PathBuf, found: String },
     #[error("Anyhow err")]
     Anyhow(#[from] anyhow::Error),
 }
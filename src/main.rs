@@ -1,11 +1,21 @@
 use {
     crate::{
-        commands::CommandFlow, config::ScillaConfig, context::ScillaContext, error::ScillaResult,
-        prompt::prompt_for_command,
+        cli::Cli,
+        commands::{Command, CommandFlow, NavStack},
+        config::{ScillaConfig, Theme},
+        context::ScillaContext,
+        error::ScillaResult,
+        misc::{
+            helpers::short_pubkey,
+            history::{HistoryEntry, append_history, scilla_history_path},
+        },
+        prompt::{apply_assume_yes, prompt_for_command, set_assume_yes_flag},
+        ui::{print_error, print_rpc_summary, theme},
     },
-    console::style,
+    clap::Parser,
 };
 
+pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod constants;
@@ -17,20 +27,73 @@ pub mod ui;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> ScillaResult<()> {
+    // Honour NO_COLOR for the banner and first-run setup, before the config
+    // and its theme are loaded
+    theme::apply(Theme::default());
+
+    let cli = Cli::parse();
+    if cli.yes {
+        set_assume_yes_flag();
+    }
+
+    // A subcommand runs once with no banner, so its output can be piped
+    if let Some(command) = cli.command {
+        let config = ScillaConfig::load_for_subcommand()?;
+        theme::apply(config.theme);
+        let mut ctx = ScillaContext::for_subcommand(config)?;
+        if let Some(output) = cli.output {
+            ctx.set_output_format(output);
+        }
+        // stdout may be piped into another tool, so errors go to stderr
+        if let Err(e) = command.run(&ctx).await {
+            eprintln!("{}", theme::error(format!("{e:#}")).bold());
+            std::process::exit(1);
+        }
+        return Ok(CommandFlow::Exit);
+    }
+
     println!(
         "{}",
-        style("⚡ Scilla — Hacking Through the Solana Matrix")
-            .bold()
-            .cyan()
+        theme::info("⚡ Scilla — Hacking Through the Solana Matrix").bold()
     );
 
     let config = ScillaConfig::load()?;
+    theme::apply(config.theme);
+    apply_assume_yes(&config);
     let mut ctx = ScillaContext::try_from(config)?;
+    if let Some(output) = cli.output {
+        ctx.set_output_format(output);
+    }
+
+    let mut nav = NavStack::default();
 
     loop {
-        let command = prompt_for_command()?;
+        let wallet = match ctx.wallet_name() {
+            Some(name) => format!("{name} ({})", short_pubkey(ctx.pubkey())),
+            None if ctx.is_watch_only() => format!("{} (watch-only)", short_pubkey(ctx.pubkey())),
+            None => short_pubkey(ctx.pubkey()),
+        };
+        println!(
+            "{}",
+            theme::muted(format!(
+                "Wallet: {wallet} · Reads: {:?}{}",
+                ctx.read_commitment().commitment,
+                if ctx.is_dry_run() { " · Dry run" } else { "" }
+            ))
+        );
+
+        let command = prompt_for_command(&mut nav)?;
 
-        let res = command.process_command(&mut ctx).await;
+        if !matches!(command, Command::Exit)
+            && let Err(e) = append_history(
+                &scilla_history_path(),
+                &HistoryEntry::new(&wallet, &command.to_string()),
+            )
+        {
+            print_error(format!("Failed to record session history: {e}"));
+        }
+
+        let res = command.process_command(&mut ctx, &mut nav).await;
 
         match res {
             CommandFlow::Process(_) => continue,
@@ -39,5 +102,7 @@ async fn main() -> ScillaResult<()> {
         }
     }
 
+    print_rpc_summary(ctx.rpc_metrics());
+
     Ok(CommandFlow::Exit)
 }
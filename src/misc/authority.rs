@@ -0,0 +1,654 @@
+use {
+    crate::{
+        constants::{
+            NONCE_AUTHORITY_OFFSET, VOTE_AUTHORIZED_WITHDRAWER_OFFSET, VOTE_NODE_PUBKEY_OFFSET,
+        },
+        context::ScillaContext,
+        misc::helpers::{
+            bincode_deserialize, build_and_send_tx_with, fetch_stake_accounts_by_authority,
+            simulate_tx,
+        },
+        prompt::{prompt_confirmation, prompt_optional_data},
+        ui::{print_error, print_explorer_link, show_spinner, theme},
+    },
+    anyhow::bail,
+    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    console::style,
+    solana_account::Account,
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_instruction::Instruction,
+    solana_keypair::{Keypair, Signer},
+    solana_nonce::{state::State as NonceState, versions::Versions},
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+    },
+    solana_stake_interface::{
+        instruction as stake_instruction,
+        state::{StakeAuthorize, StakeStateV2},
+    },
+    solana_system_interface::instruction::authorize_nonce_account,
+    solana_vote_interface::{
+        instruction as vote_instruction,
+        state::{VoteAuthorize, VoteStateV4},
+    },
+    std::{fmt, future::Future},
+};
+
+/// Authority a key holds over a stake, vote or nonce account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityRole {
+    Staker,
+    StakeWithdrawer,
+    Voter,
+    VoteWithdrawer,
+    NonceAuthority,
+}
+
+impl AuthorityRole {
+    pub fn account_kind(&self) -> &'static str {
+        match self {
+            AuthorityRole::Staker | AuthorityRole::StakeWithdrawer => "Stake",
+            AuthorityRole::Voter | AuthorityRole::VoteWithdrawer => "Vote",
+            AuthorityRole::NonceAuthority => "Nonce",
+        }
+    }
+}
+
+impl fmt::Display for AuthorityRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthorityRole::Staker => write!(f, "Staker"),
+            AuthorityRole::StakeWithdrawer | AuthorityRole::VoteWithdrawer => {
+                write!(f, "Withdrawer")
+            }
+            AuthorityRole::Voter => write!(f, "Voter"),
+            AuthorityRole::NonceAuthority => write!(f, "Nonce Authority"),
+        }
+    }
+}
+
+/// One authority relationship found on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityRecord {
+    pub account: Pubkey,
+    pub role: AuthorityRole,
+    pub authority: Pubkey,
+}
+
+/// Staker and withdrawer of an initialized or delegated stake account
+pub fn stake_authorities(account: &Pubkey, data: &[u8]) -> Vec<AuthorityRecord> {
+    let meta = match bincode_deserialize::<StakeStateV2>(data, "stake account data") {
+        Ok(StakeStateV2::Initialized(meta) | StakeStateV2::Stake(meta, _, _)) => meta,
+        _ => return Vec::new(),
+    };
+
+    vec![
+        AuthorityRecord {
+            account: *account,
+            role: AuthorityRole::Staker,
+            authority: meta.authorized.staker,
+        },
+        AuthorityRecord {
+            account: *account,
+            role: AuthorityRole::StakeWithdrawer,
+            authority: meta.authorized.withdrawer,
+        },
+    ]
+}
+
+/// Current voter and withdrawer of a vote account
+pub fn vote_authorities(account: &Pubkey, data: &[u8]) -> Vec<AuthorityRecord> {
+    let Ok(vote_state) = VoteStateV4::deserialize(data, account) else {
+        return Vec::new();
+    };
+
+    let voter = vote_state
+        .authorized_voters
+        .last()
+        .map(|(_, voter)| *voter)
+        .unwrap_or(vote_state.node_pubkey);
+
+    vec![
+        AuthorityRecord {
+            account: *account,
+            role: AuthorityRole::Voter,
+            authority: voter,
+        },
+        AuthorityRecord {
+            account: *account,
+            role: AuthorityRole::VoteWithdrawer,
+            authority: vote_state.authorized_withdrawer,
+        },
+    ]
+}
+
+/// Authority of an initialized nonce account
+pub fn nonce_authorities(account: &Pubkey, data: &[u8]) -> Vec<AuthorityRecord> {
+    match bincode_deserialize::<Versions>(data, "nonce account data") {
+        Ok(versions) => match versions.state() {
+            NonceState::Initialized(nonce) => vec![AuthorityRecord {
+                account: *account,
+                role: AuthorityRole::NonceAuthority,
+                authority: nonce.authority,
+            }],
+            NonceState::Uninitialized => Vec::new(),
+        },
+        Err(_) => Vec::new(),
+    }
+}
+
+async fn fetch_program_accounts_at(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    offsets: &[usize],
+    key: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    let mut found: Vec<(Pubkey, Account)> = Vec::new();
+
+    for offset in offsets {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                *offset,
+                key.as_ref(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(ctx.read_commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = ctx
+            .rpc()
+            .get_program_accounts_with_config(program_id, config)
+            .await?;
+
+        for (pubkey, account) in accounts {
+            if !found.iter().any(|(existing, _)| existing == &pubkey) {
+                found.push((pubkey, account));
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// System accounts with `authority` where a nonce keeps its authority. Not all
+/// of them need to be initialized nonce accounts.
+pub async fn fetch_nonce_accounts_by_authority(
+    ctx: &ScillaContext,
+    authority: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    fetch_program_accounts_at(
+        ctx,
+        &solana_system_interface::program::id(),
+        &[NONCE_AUTHORITY_OFFSET],
+        authority,
+    )
+    .await
+}
+
+/// Vote accounts `withdrawer` may withdraw from
+pub async fn fetch_vote_accounts_by_withdrawer(
+    ctx: &ScillaContext,
+    withdrawer: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    fetch_program_accounts_at(
+        ctx,
+        &solana_vote_interface::program::id(),
+        &[VOTE_AUTHORIZED_WITHDRAWER_OFFSET],
+        withdrawer,
+    )
+    .await
+}
+
+/// Every authority on the stake, vote and nonce accounts `wallet` is linked
+/// to, including the ones held by other keys
+pub async fn fetch_wallet_authorities(
+    ctx: &ScillaContext,
+    wallet: &Pubkey,
+) -> anyhow::Result<Vec<AuthorityRecord>> {
+    let mut records = Vec::new();
+
+    for (pubkey, account) in fetch_stake_accounts_by_authority(ctx, wallet).await? {
+        records.extend(stake_authorities(&pubkey, &account.data));
+    }
+
+    let vote_accounts = fetch_program_accounts_at(
+        ctx,
+        &solana_vote_interface::program::id(),
+        &[VOTE_NODE_PUBKEY_OFFSET, VOTE_AUTHORIZED_WITHDRAWER_OFFSET],
+        wallet,
+    )
+    .await?;
+    for (pubkey, account) in vote_accounts {
+        records.extend(vote_authorities(&pubkey, &account.data));
+    }
+
+    for (pubkey, account) in fetch_nonce_accounts_by_authority(ctx, wallet).await? {
+        records.extend(nonce_authorities(&pubkey, &account.data));
+    }
+
+    Ok(records)
+}
+
+/// Authorize instruction moving `record`'s authority to `new`. Must be signed
+/// by the current authority.
+pub fn rotation_instruction(record: &AuthorityRecord, new: &Pubkey) -> Instruction {
+    let AuthorityRecord {
+        account,
+        role,
+        authority,
+    } = record;
+
+    match role {
+        AuthorityRole::Staker => {
+            stake_instruction::authorize(account, authority, new, StakeAuthorize::Staker, None)
+        }
+        AuthorityRole::StakeWithdrawer => {
+            stake_instruction::authorize(account, authority, new, StakeAuthorize::Withdrawer, None)
+        }
+        AuthorityRole::Voter => {
+            vote_instruction::authorize(account, authority, new, VoteAuthorize::Voter)
+        }
+        AuthorityRole::VoteWithdrawer => {
+            vote_instruction::authorize(account, authority, new, VoteAuthorize::Withdrawer)
+        }
+        AuthorityRole::NonceAuthority => authorize_nonce_account(account, authority, new),
+    }
+}
+
+/// Authorities held by `old`, grouped per account in scan order
+pub fn plan_rotation(
+    records: &[AuthorityRecord],
+    old: &Pubkey,
+) -> Vec<(Pubkey, Vec<AuthorityRole>)> {
+    let mut plan: Vec<(Pubkey, Vec<AuthorityRole>)> = Vec::new();
+
+    for record in records.iter().filter(|record| record.authority == *old) {
+        match plan
+            .iter_mut()
+            .find(|(account, _)| *account == record.account)
+        {
+            Some((_, roles)) => roles.push(record.role),
+            None => plan.push((record.account, vec![record.role])),
+        }
+    }
+
+    plan
+}
+
+/// Moves every stake, vote and nonce authority held by `old` to `new`. Shows
+/// the full plan first, then simulates and asks again for each account before
+/// sending its transaction.
+pub async fn rotate_authorities(
+    ctx: &ScillaContext,
+    old: &Keypair,
+    new: &Pubkey,
+) -> anyhow::Result<()> {
+    let old_pubkey = old.pubkey();
+    if old_pubkey == *new {
+        bail!("The old and new keys are the same");
+    }
+
+    let records = fetch_wallet_authorities(ctx, &old_pubkey).await?;
+    let plan = plan_rotation(&records, &old_pubkey);
+
+    if plan.is_empty() {
+        println!(
+            "{}",
+            theme::warning(format!(
+                "{old_pubkey} holds no stake, vote or nonce authorities"
+            ))
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::bold_cell("Account"),
+        theme::bold_cell("Type"),
+        theme::bold_cell("Roles"),
+    ]);
+    for (account, roles) in &plan {
+        table.add_row(vec![
+            Cell::new(account),
+            Cell::new(roles[0].account_kind()),
+            Cell::new(
+                roles
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ]);
+    }
+
+    println!("\n{}", theme::warning("AUTHORITY ROTATION PLAN").bold());
+    println!("{}", theme::warning(format!("From {old_pubkey} to {new}")));
+    println!("{table}");
+    println!(
+        "{}",
+        theme::warning(format!(
+            "Make sure you control {new} before continuing; authorities moved to a key you do not \
+             hold cannot be recovered. Voter changes take effect next epoch."
+        ))
+    );
+
+    let confirmation: Option<String> = prompt_optional_data(
+        "Type ROTATE to review and send each account (anything else cancels):",
+    );
+    if confirmation.as_deref() != Some("ROTATE") {
+        println!(
+            "{}",
+            theme::warning("Rotation cancelled. Nothing was sent.")
+        );
+        return Ok(());
+    }
+
+    let signers: Vec<&dyn Signer> = if old_pubkey == *ctx.pubkey() {
+        vec![ctx.keypair()?]
+    } else {
+        vec![ctx.keypair()?, old]
+    };
+
+    // Each account is simulated and confirmed below already
+    let pipeline = ctx.send_pipeline().after_own_simulation();
+    let mut rotated = 0;
+    for (account, roles) in &plan {
+        let instructions: Vec<Instruction> = roles
+            .iter()
+            .map(|role| {
+                rotation_instruction(
+                    &AuthorityRecord {
+                        account: *account,
+                        role: *role,
+                        authority: old_pubkey,
+                    },
+                    new,
+                )
+            })
+            .collect();
+
+        if let Err(e) = simulate_tx(ctx, &instructions, &signers).await {
+            print_error(format!("Skipping {account}: {e}"));
+            continue;
+        }
+        if !pipeline.send {
+            println!(
+                "{}",
+                theme::warning(format!("Dry run: {account} simulated, not rotated."))
+            );
+            continue;
+        }
+
+        let roles_label = roles
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !prompt_confirmation(&format!(
+            "Move {roles_label} of {} account {account} to {new}?",
+            roles[0].account_kind().to_lowercase()
+        )) {
+            println!("{}", theme::warning(format!("Skipped {account}.")));
+            continue;
+        }
+
+        match build_and_send_tx_with(
+            ctx,
+            &instructions,
+            &signers,
+            pipeline,
+            ctx.rpc().commitment(),
+        )
+        .await
+        {
+            Ok(signature) => {
+                rotated += 1;
+                println!(
+                    "{} {}",
+                    theme::success(format!("Rotated {account}!")).bold(),
+                    theme::info(format!("Signature: {signature}"))
+                );
+                print_explorer_link(ctx, &signature);
+            }
+            Err(e) => print_error(format!("Failed to rotate {account}: {e}")),
+        }
+    }
+
+    println!(
+        "{}",
+        style(format!("Rotated {rotated} of {} account(s)", plan.len())).bold()
+    );
+
+    Ok(())
+}
+
+/// An authorize instruction that has been checked against the account's
+/// on-chain state but not sent yet.
+pub struct AuthorityChange {
+    pub account: Pubkey,
+    pub authority_type: &'static str,
+    pub current: Pubkey,
+    pub new: Pubkey,
+    pub signer: Keypair,
+    pub instruction: Instruction,
+}
+
+/// Runs an authority change in two steps: a dry run that simulates the
+/// transaction and shows exactly what would change, then the real send once
+/// the user has confirmed the simulation output.
+pub async fn process_authority_change<F>(ctx: &ScillaContext, spinner_msg: &str, prepare: F)
+where
+    F: Future<Output = anyhow::Result<AuthorityChange>>,
+{
+    let change = match prepare.await {
+        Ok(change) => change,
+        Err(e) => {
+            print_error(e);
+            return;
+        }
+    };
+
+    if let Err(e) = dry_run_authority_change(ctx, &change).await {
+        print_error(e);
+        return;
+    }
+    if !ctx.send_pipeline().send {
+        println!(
+            "{}",
+            theme::warning("Dry run: simulation only, the authority was not changed.")
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        theme::warning(format!(
+            "Make sure you control {} before continuing; losing it means losing the {} authority.",
+            change.new,
+            change.authority_type.to_lowercase()
+        ))
+    );
+
+    if !prompt_confirmation("Simulation succeeded. Send this authority change?") {
+        println!("{}", theme::warning("Authority change cancelled."));
+        return;
+    }
+
+    let _ = show_spinner(spinner_msg, send_authority_change(ctx, change)).await;
+}
+
+async fn dry_run_authority_change(
+    ctx: &ScillaContext,
+    change: &AuthorityChange,
+) -> anyhow::Result<()> {
+    if change.current == change.new {
+        bail!(
+            "{} is already the {} authority of {}",
+            change.new,
+            change.authority_type.to_lowercase(),
+            change.account
+        );
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Account"), Cell::new(change.account)])
+        .add_row(vec![
+            Cell::new("Authority"),
+            Cell::new(change.authority_type),
+        ])
+        .add_row(vec![
+            Cell::new("From"),
+            Cell::new(theme::error(change.current)),
+        ])
+        .add_row(vec![Cell::new("To"), Cell::new(theme::success(change.new))])
+        .add_row(vec![
+            Cell::new("Signed By"),
+            Cell::new(change.signer.pubkey()),
+        ]);
+
+    println!("\n{}", theme::warning("AUTHORITY CHANGE (DRY RUN)").bold());
+    println!("{}", table);
+
+    simulate_tx(
+        ctx,
+        std::slice::from_ref(&change.instruction),
+        &[ctx.keypair()?, &change.signer],
+    )
+    .await
+}
+
+async fn send_authority_change(ctx: &ScillaContext, change: AuthorityChange) -> anyhow::Result<()> {
+    let signature = build_and_send_tx_with(
+        ctx,
+        &[change.instruction],
+        &[ctx.keypair()?, &change.signer],
+        ctx.send_pipeline().after_own_simulation(),
+        ctx.rpc().commitment(),
+    )
+    .await?;
+
+    println!(
+        "{} {}",
+        theme::success(format!(
+            "{} authority changed to {}!",
+            change.authority_type, change.new
+        ))
+        .bold(),
+        theme::info(format!("Signature: {signature}"))
+    );
+    print_explorer_link(ctx, &signature);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_stake_interface::state::{Authorized, Lockup, Meta},
+    };
+
+    #[test]
+    fn test_stake_authorities_reads_staker_and_withdrawer() {
+        let account = Pubkey::new_unique();
+        let staker = Pubkey::new_unique();
+        let withdrawer = Pubkey::new_unique();
+        let state = StakeStateV2::Initialized(Meta {
+            rent_exempt_reserve: 0,
+            authorized: Authorized { staker, withdrawer },
+            lockup: Lockup::default(),
+        });
+        let mut data = vec![0; StakeStateV2::size_of()];
+        bincode::serialize_into(&mut data[..], &state).unwrap();
+
+        assert_eq!(
+            stake_authorities(&account, &data),
+            vec![
+                AuthorityRecord {
+                    account,
+                    role: AuthorityRole::Staker,
+                    authority: staker,
+                },
+                AuthorityRecord {
+                    account,
+                    role: AuthorityRole::StakeWithdrawer,
+                    authority: withdrawer,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_rotation_groups_roles_held_by_old_key() {
+        let old = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let stake = Pubkey::new_unique();
+        let vote = Pubkey::new_unique();
+        let record = |account, role, authority| AuthorityRecord {
+            account,
+            role,
+            authority,
+        };
+
+        let records = vec![
+            record(stake, AuthorityRole::Staker, old),
+            record(stake, AuthorityRole::StakeWithdrawer, old),
+            record(vote, AuthorityRole::Voter, other),
+            record(vote, AuthorityRole::VoteWithdrawer, old),
+        ];
+
+        assert_eq!(
+            plan_rotation(&records, &old),
+            vec![
+                (
+                    stake,
+                    vec![AuthorityRole::Staker, AuthorityRole::StakeWithdrawer]
+                ),
+                (vote, vec![AuthorityRole::VoteWithdrawer]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rotation_instruction_targets_owning_program() {
+        let new = Pubkey::new_unique();
+        let record = |role| AuthorityRecord {
+            account: Pubkey::new_unique(),
+            role,
+            authority: Pubkey::new_unique(),
+        };
+
+        assert_eq!(
+            rotation_instruction(&record(AuthorityRole::Staker), &new).program_id,
+            solana_stake_interface::program::id()
+        );
+        assert_eq!(
+            rotation_instruction(&record(AuthorityRole::Voter), &new).program_id,
+            solana_vote_interface::program::id()
+        );
+        assert_eq!(
+            rotation_instruction(&record(AuthorityRole::NonceAuthority), &new).program_id,
+            solana_system_interface::program::id()
+        );
+    }
+
+    #[test]
+    fn test_unparseable_accounts_have_no_authorities() {
+        let account = Pubkey::new_unique();
+
+        assert!(stake_authorities(&account, &[1, 2, 3]).is_empty());
+        assert!(nonce_authorities(&account, &[0; 80]).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use {
+    crate::constants::{ENCRYPTED_KEYPAIR_SALT_LEN, ENCRYPTED_KEYPAIR_VERSION},
+    anyhow::{anyhow, bail},
+    argon2::Argon2,
+    base64::{Engine, engine::general_purpose::STANDARD},
+    chacha20poly1305::{
+        AeadCore, ChaCha20Poly1305, Key, KeyInit, Nonce,
+        aead::{Aead, OsRng, rand_core::RngCore},
+    },
+    serde::{Deserialize, Serialize},
+    solana_keypair::Keypair,
+};
+
+/// Keypair file whose secret key is sealed with ChaCha20-Poly1305 under a key
+/// derived from a passphrase with Argon2id. Plain keypair files are JSON byte
+/// arrays, so the object form tells the two apart.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct EncryptedKeypairFile {
+    scilla_encrypted_keypair: u8,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Whether `contents` is an encrypted keypair file rather than a plain one
+pub fn is_encrypted_keypair(contents: &str) -> bool {
+    serde_json::from_str::<EncryptedKeypairFile>(contents.trim()).is_ok()
+}
+
+/// Encrypted keypair file contents for `keypair`, sealed with `passphrase`
+pub fn encrypt_keypair(keypair: &Keypair, passphrase: &str) -> anyhow::Result<String> {
+    let mut salt = [0u8; ENCRYPTED_KEYPAIR_SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&nonce, keypair.to_bytes().as_ref())
+        .map_err(|_| anyhow!("Failed to encrypt keypair"))?;
+
+    let file = EncryptedKeypairFile {
+        scilla_encrypted_keypair: ENCRYPTED_KEYPAIR_VERSION,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+
+    Ok(serde_json::to_string_pretty(&file)?)
+}
+
+/// Decrypts an encrypted keypair file in memory; nothing is written to disk
+pub fn decrypt_keypair(contents: &str, passphrase: &str) -> anyhow::Result<Keypair> {
+    let file: EncryptedKeypairFile = serde_json::from_str(contents.trim())?;
+    if file.scilla_encrypted_keypair != ENCRYPTED_KEYPAIR_VERSION {
+        bail!(
+            "Unsupported encrypted keypair version {}",
+            file.scilla_encrypted_keypair
+        );
+    }
+
+    let salt = STANDARD.decode(&file.salt)?;
+    let nonce = STANDARD.decode(&file.nonce)?;
+    let ciphertext = STANDARD.decode(&file.ciphertext)?;
+    if nonce.len() != 12 {
+        bail!(
+            "Encrypted keypair nonce must be 12 bytes, found {}",
+            nonce.len()
+        );
+    }
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let secret = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow!("Wrong passphrase, or the encrypted keypair file is corrupted"))?;
+
+    Keypair::try_from(secret.as_slice()).map_err(|e| anyhow!("Decrypted keypair is invalid: {e}"))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive key from passphrase: {e}"))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_keypair::Signer};
+
+    #[test]
+    fn test_encrypted_keypair_round_trip() {
+        let keypair = Keypair::new();
+        let contents = encrypt_keypair(&keypair, "correct horse").unwrap();
+
+        assert!(is_encrypted_keypair(&contents));
+        assert!(!contents.contains(&keypair.to_base58_string()));
+
+        let decrypted = decrypt_keypair(&contents, "correct horse").unwrap();
+        assert_eq!(decrypted.pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_encrypted_keypair_rejects_wrong_passphrase() {
+        let contents = encrypt_keypair(&Keypair::new(), "correct horse").unwrap();
+
+        let err = decrypt_keypair(&contents, "battery staple")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("Wrong passphrase"));
+    }
+
+    #[test]
+    fn test_plain_keypair_is_not_encrypted() {
+        let plain = serde_json::to_string(&Keypair::new().to_bytes().to_vec()).unwrap();
+        assert!(!is_encrypted_keypair(&plain));
+    }
+}
@@ -1,17 +1,61 @@
 use {
-    crate::{ScillaContext, constants::LAMPORTS_PER_SOL},
+    crate::{
+        ScillaContext,
+        config::ScillaConfig,
+        constants::{
+            ASSOCIATED_TOKEN_PROGRAM_ID, BATCH_BLOCKHASH_MAX_AGE_SECS, CONFIRM_POLL_INTERVAL_MS,
+            DEFAULT_MS_PER_SLOT, JSON_RPC_METHOD_NOT_FOUND, KEYPAIR_FILE_BYTES, LAMPORTS_PER_SOL,
+            PRIORITY_FEE_COMPUTE_UNIT_LIMIT, SEED_PHRASE_WORD_COUNTS,
+            STAKE_AUTHORIZED_STAKER_OFFSET, STAKE_AUTHORIZED_WITHDRAWER_OFFSET,
+        },
+        error::ScillaError,
+        misc::{
+            encrypted_keypair::{decrypt_keypair, is_encrypted_keypair},
+            rpc_provider::RpcProvider,
+        },
+        prompt::prompt_confirmation,
+        ui::{print_execution_cost, print_simulation_summary, suspend_spinner},
+    },
     anyhow::{Context, anyhow, bail},
     base64::Engine,
     bincode::Options,
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
     solana_account::Account,
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_commitment_config::CommitmentConfig,
+    solana_compute_budget_interface::ComputeBudgetInstruction,
     solana_epoch_info::EpochInfo,
+    solana_hash::Hash,
     solana_instruction::Instruction,
     solana_keypair::{EncodableKey, Keypair, Signature, Signer},
     solana_message::Message,
+    solana_nonce::{
+        state::{Data as NonceData, State as NonceState},
+        versions::Versions,
+    },
     solana_pubkey::Pubkey,
-    solana_transaction::Transaction,
-    std::{path::Path, str::FromStr},
-    tokio::try_join,
+    solana_rpc_client::rpc_client::SerializableTransaction,
+    solana_rpc_client_api::{
+        client_error::{Error as ClientError, ErrorKind},
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig},
+        filter::{Memcmp, RpcFilterType},
+        request::RpcError,
+    },
+    solana_stake_interface::program::id as stake_program_id,
+    solana_system_interface::instruction as system_instruction,
+    solana_transaction::{Transaction, versioned::VersionedTransaction},
+    solana_transaction_status::{UiTransactionEncoding, option_serializer::OptionSerializer},
+    std::{
+        fmt,
+        path::Path,
+        str::FromStr,
+        time::{Duration, Instant},
+    },
+    tokio::{
+        time::{sleep, timeout},
+        try_join,
+    },
 };
 
 pub fn trim_and_parse<T: FromStr>(s: &str, field_name: &str) -> anyhow::Result<Option<T>> {
@@ -70,6 +114,14 @@ impl FromStr for SolAmount {
         let sol = trim_and_parse::<f64>(s, "amount")?
             .ok_or_else(|| anyhow!("Amount cannot be empty. Please enter a SOL amount"))?;
 
+        SolAmount::try_from(sol)
+    }
+}
+
+impl TryFrom<f64> for SolAmount {
+    type Error = anyhow::Error;
+
+    fn try_from(sol: f64) -> Result<Self, Self::Error> {
         if sol <= 0.0 || !sol.is_finite() {
             bail!("Amount must be a positive finite number, got {sol}");
         }
@@ -80,6 +132,87 @@ impl FromStr for SolAmount {
     }
 }
 
+impl fmt::Display for SolAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for SolAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SolAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sol = f64::deserialize(deserializer)?;
+        SolAmount::try_from(sol).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Percentage(f64);
+
+impl Percentage {
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Share of `lamports`, rounded down so it never exceeds the total
+    pub fn of(&self, lamports: u64) -> u64 {
+        (((lamports as f64) * self.0 / 100.0).floor() as u64).min(lamports)
+    }
+}
+
+impl FromStr for Percentage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let percentage =
+            trim_and_parse::<f64>(trimmed.strip_suffix('%').unwrap_or(trimmed), "percentage")?
+                .ok_or_else(|| anyhow!("Percentage cannot be empty. Please enter 1-100"))?;
+
+        if !(percentage > 0.0 && percentage <= 100.0) {
+            bail!("Percentage must be above 0 and at most 100, got {percentage}");
+        }
+        Ok(Percentage(percentage))
+    }
+}
+
+/// Withdraw amount, either absolute or relative to what can be withdrawn.
+/// `Max` is everything the account allows; for stake accounts that includes
+/// the rent reserve and closes the account.
+#[derive(Debug, Clone, Copy)]
+pub enum WithdrawAmount {
+    Sol(SolAmount),
+    Percentage(Percentage),
+    Max,
+}
+
+impl WithdrawAmount {
+    pub fn to_lamports(&self, withdrawable: u64) -> u64 {
+        match self {
+            WithdrawAmount::Sol(amount) => amount.to_lamports(),
+            WithdrawAmount::Percentage(percentage) => percentage.of(withdrawable),
+            WithdrawAmount::Max => withdrawable,
+        }
+    }
+}
+
+impl fmt::Display for WithdrawAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WithdrawAmount::Sol(amount) => write!(f, "{} SOL", amount.value()),
+            WithdrawAmount::Percentage(percentage) => {
+                write!(f, "{}% of the withdrawable balance", percentage.value())
+            }
+            WithdrawAmount::Max => write!(f, "the maximum withdrawable balance"),
+        }
+    }
+}
+
 pub async fn check_minimum_balance(
     ctx: &ScillaContext,
     payer: &Pubkey,
@@ -99,54 +232,661 @@ pub async fn check_minimum_balance(
     Ok(())
 }
 
+/// Rounds to the nearest lamport: a decimal like 0.1 or 1.999999999 isn't
+/// exact in binary and would otherwise truncate one lamport short. Amounts
+/// beyond `u64::MAX` lamports saturate.
 pub fn sol_to_lamports(sol: f64) -> u64 {
-    (sol * LAMPORTS_PER_SOL as f64) as u64
+    (sol * LAMPORTS_PER_SOL as f64).round() as u64
+}
+
+/// Approximate time `target_epoch` starts, assuming every epoch is as long as
+/// the current one and slots take `DEFAULT_MS_PER_SLOT`. Epochs that already
+/// started resolve to `now`.
+pub fn estimate_epoch_start(
+    epoch_info: &EpochInfo,
+    target_epoch: u64,
+    now: DateTime<Utc>,
+) -> DateTime<Utc> {
+    if target_epoch <= epoch_info.epoch {
+        return now;
+    }
+
+    let slots_left_in_epoch = epoch_info
+        .slots_in_epoch
+        .saturating_sub(epoch_info.slot_index);
+    let full_epochs = target_epoch - epoch_info.epoch - 1;
+    let slots_remaining =
+        slots_left_in_epoch.saturating_add(full_epochs.saturating_mul(epoch_info.slots_in_epoch));
+
+    now + chrono::Duration::milliseconds(slots_remaining.saturating_mul(DEFAULT_MS_PER_SLOT) as i64)
 }
 
 pub fn lamports_to_sol(lamports: u64) -> f64 {
     lamports as f64 / LAMPORTS_PER_SOL as f64
 }
 
-pub fn read_keypair_from_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Keypair> {
+/// Whether the file at `path` is an encrypted keypair, which needs a
+/// passphrase to read
+pub fn is_encrypted_keypair_file<P: AsRef<Path>>(path: P) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|contents| is_encrypted_keypair(&contents))
+}
+
+/// Reads a keypair file, naming the offending path and whether it is missing
+/// or malformed on failure. An encrypted keypair file is decrypted in memory
+/// with `passphrase`, and is an error without one.
+pub fn read_keypair_with_passphrase<P: AsRef<Path>>(
+    path: P,
+    passphrase: Option<&str>,
+) -> anyhow::Result<Keypair> {
     let path = path.as_ref();
+    if !path.exists() {
+        bail!("Keypair file not found at {}", path.display());
+    }
+    if let Ok(contents) = std::fs::read_to_string(path) {
+        if is_encrypted_keypair(&contents) {
+            let Some(passphrase) = passphrase else {
+                bail!(
+                    "Keypair file {} is encrypted and needs a passphrase",
+                    path.display()
+                );
+            };
+            return decrypt_keypair(&contents, passphrase);
+        }
+        if let Some(found) = describe_non_keypair(&contents) {
+            return Err(ScillaError::InvalidKeypairFile {
+                path: path.to_path_buf(),
+                found,
+            }
+            .into());
+        }
+    }
     Keypair::read_from_file(path)
-        .map_err(|e| anyhow!("Failed to read keypair from {}: {}", path.display(), e))
+        .map_err(|e| anyhow!("Keypair file {} is malformed: {}", path.display(), e))
+}
+
+/// Recognizes the usual wrong files passed as a keypair, a byte array of the
+/// wrong length, a pubkey or a seed phrase, and says what was found instead
+fn describe_non_keypair(contents: &str) -> Option<String> {
+    let contents = contents.trim();
+
+    if let Ok(bytes) = serde_json::from_str::<Vec<u8>>(contents) {
+        return (bytes.len() != KEYPAIR_FILE_BYTES).then(|| format!("{} bytes", bytes.len()));
+    }
+    if Pubkey::from_str(contents).is_ok() {
+        return Some("a base58 pubkey".to_string());
+    }
+
+    let words: Vec<&str> = contents.split_whitespace().collect();
+    let is_seed_phrase = SEED_PHRASE_WORD_COUNTS.contains(&words.len())
+        && words
+            .iter()
+            .all(|word| word.chars().all(|c| c.is_ascii_lowercase()));
+    is_seed_phrase.then(|| format!("{} words, likely a seed phrase", words.len()))
+}
+
+/// Fetches an account at the configured read commitment. Only for display;
+/// anything that feeds a transaction should go through `ctx.rpc()` directly.
+pub async fn read_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<Account> {
+    ctx.rpc()
+        .get_account_with_commitment(pubkey, ctx.read_commitment())
+        .await?
+        .value
+        .ok_or_else(|| anyhow!("Account {pubkey} not found"))
 }
 
 pub async fn build_and_send_tx(
     ctx: &ScillaContext,
     instruction: &[Instruction],
     signers: &[&dyn Signer],
+) -> anyhow::Result<Signature> {
+    build_and_send_tx_with(
+        ctx,
+        instruction,
+        signers,
+        ctx.send_pipeline(),
+        ctx.rpc().commitment(),
+    )
+    .await
+}
+
+/// `build_and_send_tx` through an explicit `pipeline` instead of the
+/// session's, returning once the transaction reached `commitment`
+pub async fn build_and_send_tx_with(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+    pipeline: SendPipeline,
+    commitment: CommitmentConfig,
 ) -> anyhow::Result<Signature> {
     let recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
-    let message = Message::new(instruction, Some(ctx.pubkey()));
+    let tx = sign_tx(ctx, instruction, signers, recent_blockhash)?;
+
+    run_send_pipeline(ctx, &tx, pipeline, commitment).await
+}
+
+/// `instruction` with the priority fee added, paid by the wallet and signed
+/// with `recent_blockhash`
+fn sign_tx(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+    recent_blockhash: Hash,
+) -> anyhow::Result<Transaction> {
+    let instruction = with_priority_fee(ctx, instruction);
+    let message = Message::new(&instruction, Some(ctx.pubkey()));
     let mut tx = Transaction::new_unsigned(message);
     tx.try_sign(&signers.to_vec(), recent_blockhash)?;
 
-    let signature = ctx.rpc().send_and_confirm_transaction(&tx).await?;
+    Ok(tx)
+}
+
+/// Nonce account a transaction is signed against instead of a recent
+/// blockhash, and the authority that advances it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurableNonce {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// The stored blockhash and authority of an initialized nonce account
+pub fn nonce_data(account: &Account) -> anyhow::Result<NonceData> {
+    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+
+    match versions.state() {
+        NonceState::Initialized(data) => Ok(data.clone()),
+        NonceState::Uninitialized => bail!("This account is not an initialized nonce account"),
+    }
+}
+
+/// `build_and_send_tx` signed against the blockhash stored in `nonce` instead
+/// of a recent one. Advancing the nonce is put in front of `instruction`, as
+/// the runtime only accepts the stored blockhash when that comes first, so
+/// `signers` has to include the nonce authority.
+pub async fn build_and_send_tx_with_nonce(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+    nonce: &DurableNonce,
+) -> anyhow::Result<Signature> {
+    let account = ctx
+        .rpc()
+        .get_account_with_commitment(&nonce.account, ctx.rpc().commitment())
+        .await?
+        .value
+        .ok_or_else(|| anyhow!("Nonce account {} not found", nonce.account))?;
+    let data = nonce_data(&account)?;
+    if data.authority != nonce.authority {
+        bail!(
+            "Nonce account {} is controlled by {}, not {}",
+            nonce.account,
+            data.authority,
+            nonce.authority
+        );
+    }
+
+    let mut instructions = vec![system_instruction::advance_nonce_account(
+        &nonce.account,
+        &nonce.authority,
+    )];
+    instructions.extend(with_priority_fee(ctx, instruction));
+
+    let message = Message::new(&instructions, Some(ctx.pubkey()));
+    let mut tx = Transaction::new_unsigned(message);
+    tx.try_sign(&signers.to_vec(), data.blockhash())?;
+
+    run_send_pipeline(ctx, &tx, ctx.send_pipeline(), ctx.rpc().commitment()).await
+}
+
+/// `instruction` with compute budget instructions setting the configured
+/// priority fee in front of it, or unchanged when no priority fee is set
+pub fn with_priority_fee(ctx: &ScillaContext, instruction: &[Instruction]) -> Vec<Instruction> {
+    let price = ctx.priority_fee_micro_lamports();
+    if price == 0 {
+        return instruction.to_vec();
+    }
+
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(PRIORITY_FEE_COMPUTE_UNIT_LIMIT),
+        ComputeBudgetInstruction::set_compute_unit_price(price),
+    ];
+    instructions.extend_from_slice(instruction);
+    instructions
+}
+
+/// Lamports a priority fee of `micro_lamports` per compute unit adds on top
+/// of the base fee, for the compute-unit limit Scilla requests
+pub fn priority_fee_lamports(micro_lamports: u64) -> u64 {
+    let micro = u128::from(micro_lamports) * u128::from(PRIORITY_FEE_COMPUTE_UNIT_LIMIT);
+    u64::try_from(micro.div_ceil(1_000_000)).unwrap_or(u64::MAX)
+}
+
+/// Stages a signed transaction passes through on its way to the cluster,
+/// chosen by the `dry-run`, `simulate-before-send` and `skip-confirm` settings:
+///
+/// | dry-run | simulate-before-send | skip-confirm | stages                                  |
+/// |---------|----------------------|--------------|-----------------------------------------|
+/// | off     | off                  | any          | build → send → confirm                  |
+/// | off     | on                   | off          | build → simulate → ask → send → confirm |
+/// | off     | on                   | on           | build → simulate → send → confirm       |
+/// | on      | any                  | any          | build → simulate                        |
+///
+/// Commands still ask about their own summary before building anything; the
+/// ask stage is a second look, at the simulation result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendPipeline {
+    pub simulate: bool,
+    pub ask: bool,
+    pub send: bool,
+}
+
+impl SendPipeline {
+    pub fn new(dry_run: bool, simulate_before_send: bool, skip_confirm: bool) -> Self {
+        Self {
+            simulate: dry_run || simulate_before_send,
+            ask: simulate_before_send && !dry_run && !skip_confirm,
+            send: !dry_run,
+        }
+    }
+
+    pub fn from_config(config: &ScillaConfig) -> Self {
+        Self::new(
+            config.dry_run,
+            config.simulate_before_send,
+            config.skip_confirm,
+        )
+    }
+
+    /// This pipeline for a flow that already simulated the transaction and
+    /// showed the result to the user itself
+    pub fn after_own_simulation(self) -> Self {
+        Self {
+            simulate: false,
+            ask: false,
+            ..self
+        }
+    }
+
+    fn stages(&self) -> Vec<&'static str> {
+        let mut stages = vec!["build"];
+        if self.simulate {
+            stages.push("simulate");
+        }
+        if self.ask {
+            stages.push("ask");
+        }
+        if self.send {
+            stages.extend(["send", "confirm"]);
+        }
+        stages
+    }
+}
+
+impl fmt::Display for SendPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.stages().join(" → "))?;
+        if !self.send {
+            write!(f, " (dry run)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Takes a built and signed transaction through the remaining stages of
+/// `pipeline`. Stopping early is an error, so callers never report a send
+/// that did not happen.
+async fn run_send_pipeline(
+    ctx: &ScillaContext,
+    tx: &impl SerializableTransaction,
+    pipeline: SendPipeline,
+    commitment: CommitmentConfig,
+) -> anyhow::Result<Signature> {
+    if pipeline.simulate {
+        simulate_signed_tx(ctx, tx).await?;
+    }
+
+    if !pipeline.send {
+        bail!(
+            "Dry run: transaction {} was simulated but not sent",
+            tx.get_signature()
+        );
+    }
+
+    if pipeline.ask
+        && !suspend_spinner(|| prompt_confirmation("Simulation succeeded. Send this transaction?"))
+    {
+        bail!("Transaction cancelled, nothing was sent");
+    }
 
+    let signature = send_and_confirm_with_commitment(ctx, tx, commitment).await?;
+    if let Some((units, fee)) = fetch_execution_cost(ctx, &signature).await {
+        suspend_spinner(|| print_execution_cost(units, fee));
+    }
     Ok(signature)
 }
 
+/// Compute units consumed and fee paid by a confirmed transaction. Best
+/// effort: the send already succeeded, so a failed lookup is not an error.
+async fn fetch_execution_cost(
+    ctx: &ScillaContext,
+    signature: &Signature,
+) -> Option<(Option<u64>, u64)> {
+    // getTransaction does not serve processed transactions
+    let commitment = if ctx.rpc().commitment().is_at_least_confirmed() {
+        ctx.rpc().commitment()
+    } else {
+        CommitmentConfig::confirmed()
+    };
+
+    let tx = ctx
+        .rpc()
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(commitment),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await
+        .ok()?;
+    let meta = tx.transaction.meta?;
+
+    let units = match meta.compute_units_consumed {
+        OptionSerializer::Some(units) => Some(units),
+        _ => None,
+    };
+
+    Some((units, meta.fee))
+}
+
+/// One recent blockhash shared by the transactions of a batch. It is reused
+/// while younger than `BATCH_BLOCKHASH_MAX_AGE_SECS`, well inside its ~150
+/// slot validity window, instead of being fetched again for every send.
+#[derive(Debug, Default)]
+pub struct BatchBlockhash {
+    current: Option<(Hash, Instant)>,
+}
+
+impl BatchBlockhash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn get(&mut self, ctx: &ScillaContext) -> anyhow::Result<Hash> {
+        match self.current {
+            Some((blockhash, fetched_at))
+                if fetched_at.elapsed() < Duration::from_secs(BATCH_BLOCKHASH_MAX_AGE_SECS) =>
+            {
+                Ok(blockhash)
+            }
+            _ => self.refresh(ctx).await,
+        }
+    }
+
+    async fn refresh(&mut self, ctx: &ScillaContext) -> anyhow::Result<Hash> {
+        let blockhash = ctx.rpc().get_latest_blockhash().await?;
+        self.current = Some((blockhash, Instant::now()));
+        Ok(blockhash)
+    }
+}
+
+/// `build_and_send_tx` for one transaction of a batch, signing with the
+/// batch's shared blockhash. A send rejected for an expired blockhash is
+/// retried once with a fresh one, which the rest of the batch then reuses.
+pub async fn build_and_send_batch_tx(
+    ctx: &ScillaContext,
+    blockhash: &mut BatchBlockhash,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+) -> anyhow::Result<Signature> {
+    let commitment = ctx.rpc().commitment();
+    let pipeline = ctx.send_pipeline();
+    let tx = sign_tx(ctx, instruction, signers, blockhash.get(ctx).await?)?;
+
+    match run_send_pipeline(ctx, &tx, pipeline, commitment).await {
+        Err(err) if is_expired_blockhash(&err) => {
+            // Already simulated and approved with the stale blockhash
+            let tx = sign_tx(ctx, instruction, signers, blockhash.refresh(ctx).await?)?;
+            run_send_pipeline(ctx, &tx, pipeline.after_own_simulation(), commitment).await
+        }
+        result => result,
+    }
+}
+
+/// Whether a send failed only because its blockhash is no longer valid
+fn is_expired_blockhash(err: &anyhow::Error) -> bool {
+    let message = format!("{err:#}").to_lowercase();
+    message.contains("blockhash not found") || message.contains("block height exceeded")
+}
+
+/// Takes a transaction that is already signed, e.g. one built elsewhere,
+/// through the session's send pipeline at the session's commitment
+pub async fn send_signed_tx(
+    ctx: &ScillaContext,
+    tx: &impl SerializableTransaction,
+) -> anyhow::Result<Signature> {
+    run_send_pipeline(ctx, tx, ctx.send_pipeline(), ctx.rpc().commitment()).await
+}
+
+/// Sends `tx` and waits for `commitment`, giving up after the configured
+/// confirmation timeout. The transaction may still land after that, so the
+/// error points at the explorer instead of reporting a failure.
+async fn send_and_confirm_with_commitment(
+    ctx: &ScillaContext,
+    tx: &impl SerializableTransaction,
+    commitment: CommitmentConfig,
+) -> anyhow::Result<Signature> {
+    let signature = *tx.get_signature();
+
+    // Waits only for `commitment`, so a `processed` send returns as soon as
+    // the node has it rather than after the client's own commitment.
+    let confirm = async {
+        let signature = ctx.rpc().send_transaction(tx).await?;
+        loop {
+            if let Some(status) = ctx
+                .rpc()
+                .get_signature_status_with_commitment(&signature, commitment)
+                .await?
+            {
+                status?;
+                return Ok::<_, ClientError>(signature);
+            }
+            sleep(Duration::from_millis(CONFIRM_POLL_INTERVAL_MS)).await;
+        }
+    };
+
+    match timeout(ctx.confirm_timeout(), confirm).await {
+        Ok(result) => Ok(result?),
+        Err(_) => bail!(
+            "Transaction {signature} submitted but not confirmed within {}s — check the explorer: \
+             {}",
+            ctx.confirm_timeout().as_secs(),
+            ctx.explorer().tx_url(&signature, ctx.cluster())
+        ),
+    }
+}
+
+/// Builds and signs the same transaction `build_and_send_tx` would, but only
+/// simulates it and prints the result. Nothing is broadcast, and a failed
+/// simulation is an error.
+pub async fn simulate_tx(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+) -> anyhow::Result<()> {
+    let recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let tx = sign_tx(ctx, instruction, signers, recent_blockhash)?;
+
+    simulate_signed_tx(ctx, &tx).await
+}
+
+/// The simulate stage of the send pipeline, also behind `simulate_tx`
+async fn simulate_signed_tx(
+    ctx: &ScillaContext,
+    tx: &impl SerializableTransaction,
+) -> anyhow::Result<()> {
+    let result = ctx.rpc().simulate_transaction(tx).await?.value;
+    suspend_spinner(|| print_simulation_summary(&result));
+    if let Some(err) = result.err {
+        bail!("Simulation failed, nothing was sent: {err:?}");
+    }
+
+    Ok(())
+}
+
+/// Adds the fee payer's signature to a transaction that the other required
+/// signers have already signed. The message is left untouched so their
+/// signatures stay valid, which means the fee payer must already be the first
+/// account key.
+pub fn sign_as_fee_payer(tx: &mut VersionedTransaction, fee_payer: &Keypair) -> anyhow::Result<()> {
+    let fee_payer_pubkey = fee_payer.pubkey();
+    let account_keys = tx.message.static_account_keys();
+
+    let Some(current_fee_payer) = account_keys.first() else {
+        bail!("Transaction has no account keys");
+    };
+
+    if current_fee_payer != &fee_payer_pubkey {
+        bail!(
+            "Transaction fee payer is {current_fee_payer}, not your wallet {fee_payer_pubkey}. \
+             Ask the sender to rebuild it with {fee_payer_pubkey} as the fee payer"
+        );
+    }
+
+    let num_required_signatures = tx.message.header().num_required_signatures as usize;
+    if tx.signatures.len() != num_required_signatures {
+        bail!(
+            "Transaction expects {num_required_signatures} signatures but carries {}",
+            tx.signatures.len()
+        );
+    }
+
+    let missing_signers: Vec<String> = account_keys
+        .iter()
+        .zip(&tx.signatures)
+        .skip(1)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| pubkey.to_string())
+        .collect();
+
+    if !missing_signers.is_empty() {
+        bail!(
+            "Transaction is missing signatures from: {}",
+            missing_signers.join(", ")
+        );
+    }
+
+    tx.signatures[0] = fee_payer.sign_message(&tx.message.serialize());
+
+    Ok(())
+}
+
+/// Derives the associated token account of `wallet` for `mint`. Pure
+/// computation, no RPC involved.
+pub fn get_associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Pubkey {
+    let associated_token_program_id = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap();
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+        &associated_token_program_id,
+    )
+    .0
+}
+
+/// Whether the endpoint refused `err`'s request because it doesn't serve the
+/// method, as opposed to the request itself failing.
+fn is_unsupported_method(err: &ClientError) -> bool {
+    let restricted = |message: &str| {
+        let message = message.to_lowercase();
+        [
+            "not supported",
+            "not available",
+            "method not found",
+            "disabled",
+            "forbidden",
+        ]
+        .iter()
+        .any(|hint| message.contains(hint))
+    };
+
+    match err.kind() {
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. }) => {
+            *code == JSON_RPC_METHOD_NOT_FOUND || restricted(message)
+        }
+        ErrorKind::RpcError(RpcError::ForUser(message)) => restricted(message),
+        ErrorKind::Reqwest(err) => {
+            matches!(err.status().map(|status| status.as_u16()), Some(401 | 403))
+        }
+        _ => false,
+    }
+}
+
+/// Turns an error from an RPC method that providers commonly disable into a
+/// hint to switch endpoints; other errors pass through unchanged.
+pub fn explain_rpc_error(ctx: &ScillaContext, method: &str, err: ClientError) -> anyhow::Error {
+    if is_unsupported_method(&err) {
+        anyhow!(
+            "{method} is not available on {}. Many RPC providers disable it; switch to an \
+             endpoint that supports it (ScillaConfig > Edit > RPC URL)",
+            ctx.rpc().url()
+        )
+    } else {
+        err.into()
+    }
+}
+
 /// Fetches account data and current epoch info in parallel.
 pub async fn fetch_account_with_epoch(
-    ctx: &ScillaContext,
+    rpc: &dyn RpcProvider,
     pubkey: &Pubkey,
 ) -> anyhow::Result<(Account, EpochInfo)> {
-    try_join!(
-        async {
-            ctx.rpc()
-                .get_account(pubkey)
-                .await
-                .map_err(|_| anyhow!("{pubkey} account does not exist"))
-        },
-        async {
-            ctx.rpc()
-                .get_epoch_info()
-                .await
-                .map_err(anyhow::Error::from)
+    try_join!(rpc.get_account(pubkey), rpc.get_epoch_info())
+}
+
+/// Fetches every stake account where `authority` is either the staker or the
+/// withdrawer, without duplicates.
+pub async fn fetch_stake_accounts_by_authority(
+    ctx: &ScillaContext,
+    authority: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    let mut stake_accounts: Vec<(Pubkey, Account)> = Vec::new();
+
+    for offset in [
+        STAKE_AUTHORIZED_STAKER_OFFSET,
+        STAKE_AUTHORIZED_WITHDRAWER_OFFSET,
+    ] {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                offset,
+                authority.as_ref(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(ctx.rpc().commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = ctx
+            .rpc()
+            .get_program_accounts_with_config(&stake_program_id(), config)
+            .await?;
+
+        for (pubkey, account) in accounts {
+            if !stake_accounts
+                .iter()
+                .any(|(existing, _)| existing == &pubkey)
+            {
+                stake_accounts.push((pubkey, account));
+            }
         }
-    )
+    }
+
+    Ok(stake_accounts)
 }
 
 /// Generic helper to deserialize bincode data with consistent error
@@ -211,10 +951,150 @@ pub fn short_pubkey(pk: &Pubkey) -> String {
 #[cfg(test)]
 mod tests {
     use {
-        super::*, crate::constants::MEMO_PROGRAM_ID, solana_message::VersionedMessage,
-        solana_transaction::versioned::VersionedTransaction,
+        super::*, crate::constants::MEMO_PROGRAM_ID, solana_instruction::AccountMeta,
+        solana_message::VersionedMessage,
     };
 
+    #[test]
+    fn test_sol_to_lamports_rounds_to_nearest_lamport() {
+        assert_eq!(sol_to_lamports(0.000000001), 1);
+        assert_eq!(sol_to_lamports(0.1), 100_000_000);
+        assert_eq!(sol_to_lamports(1.5), 1_500_000_000);
+        assert_eq!(sol_to_lamports(1.999999999), 1_999_999_999);
+    }
+
+    #[test]
+    fn test_sol_to_lamports_near_u64_max() {
+        assert_eq!(
+            sol_to_lamports(18_000_000_000.0),
+            18_000_000_000_000_000_000
+        );
+        assert_eq!(sol_to_lamports(1e12), u64::MAX);
+    }
+
+    #[test]
+    fn test_priority_fee_lamports_rounds_up() {
+        assert_eq!(priority_fee_lamports(0), 0);
+        assert_eq!(priority_fee_lamports(1), 1);
+        assert_eq!(priority_fee_lamports(5), 1);
+        assert_eq!(priority_fee_lamports(1_000_000), 200_000);
+    }
+
+    fn partially_signed_tx(fee_payer: &Keypair, signer: &Keypair) -> VersionedTransaction {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::from_str(MEMO_PROGRAM_ID).unwrap(),
+            b"sponsored",
+            vec![AccountMeta::new_readonly(signer.pubkey(), true)],
+        );
+        let message = Message::new(&[ix], Some(&fee_payer.pubkey()));
+        let signer_signature = signer.sign_message(&message.serialize());
+
+        VersionedTransaction {
+            signatures: vec![Signature::default(), signer_signature],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    #[test]
+    fn test_sign_as_fee_payer_completes_partial_signatures() {
+        let fee_payer = Keypair::new();
+        let signer = Keypair::new();
+        let mut tx = partially_signed_tx(&fee_payer, &signer);
+
+        sign_as_fee_payer(&mut tx, &fee_payer).expect("fee payer should be able to sign");
+
+        assert!(tx.verify_with_results().into_iter().all(|valid| valid));
+    }
+
+    #[test]
+    fn test_sign_as_fee_payer_rejects_other_fee_payer() {
+        let fee_payer = Keypair::new();
+        let signer = Keypair::new();
+        let mut tx = partially_signed_tx(&fee_payer, &signer);
+
+        let err = sign_as_fee_payer(&mut tx, &Keypair::new()).unwrap_err();
+
+        assert!(err.to_string().contains("not your wallet"));
+    }
+
+    #[test]
+    fn test_sign_as_fee_payer_rejects_missing_signer() {
+        let fee_payer = Keypair::new();
+        let signer = Keypair::new();
+        let mut tx = partially_signed_tx(&fee_payer, &signer);
+        tx.signatures[1] = Signature::default();
+
+        let err = sign_as_fee_payer(&mut tx, &fee_payer).unwrap_err();
+
+        assert!(err.to_string().contains(&signer.pubkey().to_string()));
+    }
+
+    #[test]
+    fn test_percentage_parses_with_and_without_sign() {
+        assert_eq!(Percentage::from_str("50").unwrap().value(), 50.0);
+        assert_eq!(Percentage::from_str(" 12.5% ").unwrap().value(), 12.5);
+        assert!(Percentage::from_str("0").is_err());
+        assert!(Percentage::from_str("101%").is_err());
+        assert!(Percentage::from_str("%").is_err());
+    }
+
+    #[test]
+    fn test_percentage_of_rounds_down() {
+        let half = Percentage::from_str("50").unwrap();
+        assert_eq!(half.of(3), 1);
+        assert_eq!(half.of(2_000_000_000), 1_000_000_000);
+
+        let all = Percentage::from_str("100").unwrap();
+        assert_eq!(all.of(5_000_000_003), 5_000_000_003);
+        assert_eq!(all.of(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_send_pipeline_default_sends_without_simulating() {
+        for skip_confirm in [false, true] {
+            let pipeline = SendPipeline::new(false, false, skip_confirm);
+            assert_eq!(pipeline.stages(), vec!["build", "send", "confirm"]);
+        }
+    }
+
+    #[test]
+    fn test_send_pipeline_simulate_asks_before_sending() {
+        let pipeline = SendPipeline::new(false, true, false);
+        assert_eq!(
+            pipeline.stages(),
+            vec!["build", "simulate", "ask", "send", "confirm"]
+        );
+    }
+
+    #[test]
+    fn test_send_pipeline_skip_confirm_drops_ask() {
+        let pipeline = SendPipeline::new(false, true, true);
+        assert_eq!(
+            pipeline.stages(),
+            vec!["build", "simulate", "send", "confirm"]
+        );
+    }
+
+    #[test]
+    fn test_send_pipeline_dry_run_stops_after_simulate() {
+        for simulate_before_send in [false, true] {
+            for skip_confirm in [false, true] {
+                let pipeline = SendPipeline::new(true, simulate_before_send, skip_confirm);
+                assert_eq!(pipeline.stages(), vec!["build", "simulate"]);
+                assert_eq!(pipeline.to_string(), "build → simulate (dry run)");
+            }
+        }
+    }
+
+    #[test]
+    fn test_send_pipeline_after_own_simulation_keeps_dry_run() {
+        let pipeline = SendPipeline::new(false, true, false).after_own_simulation();
+        assert_eq!(pipeline.stages(), vec!["build", "send", "confirm"]);
+
+        let dry_run = SendPipeline::new(true, false, false).after_own_simulation();
+        assert!(!dry_run.send);
+    }
+
     #[test]
     fn test_lamports_to_sol_exact_one_sol() {
         assert_eq!(lamports_to_sol(1_000_000_000), 1.0);
@@ -227,6 +1107,97 @@ mod tests {
         assert!(result > 0.0, "Should handle u64::MAX without panic");
         assert!(result < f64::INFINITY, "Should not overflow to infinity");
     }
+
+    #[test]
+    fn test_read_keypair_with_passphrase_missing_file() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("missing.json");
+
+        let err = read_keypair_with_passphrase(&path, None)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("not found"));
+        assert!(err.contains(&path.display().to_string()));
+    }
+
+    #[test]
+    fn test_read_keypair_with_passphrase_malformed_file() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("bad.json");
+        std::fs::write(&path, "not a keypair").expect("Failed to write file");
+
+        let err = read_keypair_with_passphrase(&path, None)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("malformed"));
+        assert!(err.contains(&path.display().to_string()));
+    }
+
+    #[test]
+    fn test_read_keypair_with_passphrase_wrong_length() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("short.json");
+        std::fs::write(&path, format!("{:?}", [7u8; 32])).expect("Failed to write file");
+
+        let err = read_keypair_with_passphrase(&path, None).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ScillaError>(),
+            Some(ScillaError::InvalidKeypairFile { found, .. }) if found == "32 bytes"
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Keypair file {} is not a valid 64-byte Solana keypair (found 32 bytes)",
+                path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_read_keypair_with_passphrase_encrypted() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("encrypted.json");
+        let keypair = Keypair::new();
+        let contents = crate::misc::encrypted_keypair::encrypt_keypair(&keypair, "hunter2")
+            .expect("Failed to encrypt keypair");
+        std::fs::write(&path, contents).expect("Failed to write file");
+
+        assert!(is_encrypted_keypair_file(&path));
+        let err = read_keypair_with_passphrase(&path, None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("needs a passphrase"));
+        assert_eq!(
+            read_keypair_with_passphrase(&path, Some("hunter2"))
+                .unwrap()
+                .pubkey(),
+            keypair.pubkey()
+        );
+    }
+
+    #[test]
+    fn test_describe_non_keypair() {
+        let keypair = Keypair::new();
+        let seed_phrase = ["abandon"; 12].join(" ");
+
+        assert_eq!(
+            describe_non_keypair(&format!("{:?}\n", keypair.to_bytes())),
+            None
+        );
+        assert_eq!(
+            describe_non_keypair(&keypair.pubkey().to_string()).as_deref(),
+            Some("a base58 pubkey")
+        );
+        assert_eq!(
+            describe_non_keypair(&seed_phrase).as_deref(),
+            Some("12 words, likely a seed phrase")
+        );
+        assert_eq!(describe_non_keypair("not a keypair"), None);
+    }
+
     #[test]
     fn test_decode_base64_memo_transaction() -> anyhow::Result<()> {
         // Fixture: Real memo transaction from Solana devnet
@@ -281,4 +1252,107 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_is_expired_blockhash() {
+        let expired = anyhow::Error::from(ClientError::from(RpcError::RpcResponseError {
+            code: -32002,
+            message: "Transaction simulation failed: Blockhash not found".to_string(),
+            data: solana_rpc_client_api::request::RpcResponseErrorData::Empty,
+        }));
+        assert!(is_expired_blockhash(&expired));
+
+        assert!(is_expired_blockhash(&anyhow!(
+            "Transaction expired: block height exceeded"
+        )));
+        assert!(!is_expired_blockhash(&anyhow!(
+            "Insufficient funds for fee"
+        )));
+    }
+
+    #[test]
+    fn test_is_unsupported_method() {
+        let method_not_found = ClientError::from(RpcError::RpcResponseError {
+            code: JSON_RPC_METHOD_NOT_FOUND,
+            message: "Method not found".to_string(),
+            data: solana_rpc_client_api::request::RpcResponseErrorData::Empty,
+        });
+        assert!(is_unsupported_method(&method_not_found));
+
+        let disabled = ClientError::from(RpcError::RpcResponseError {
+            code: -32000,
+            message: "getLargestAccounts is disabled on this plan".to_string(),
+            data: solana_rpc_client_api::request::RpcResponseErrorData::Empty,
+        });
+        assert!(is_unsupported_method(&disabled));
+
+        let other = ClientError::from(RpcError::RpcResponseError {
+            code: -32602,
+            message: "Invalid params".to_string(),
+            data: solana_rpc_client_api::request::RpcResponseErrorData::Empty,
+        });
+        assert!(!is_unsupported_method(&other));
+    }
+
+    #[test]
+    fn test_associated_token_address_depends_on_token_program() {
+        let token = Pubkey::from_str(crate::constants::TOKEN_PROGRAM_ID).unwrap();
+        let token_2022 = Pubkey::from_str(crate::constants::TOKEN_2022_PROGRAM_ID).unwrap();
+        let wallet = Pubkey::from_str("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").unwrap();
+
+        // (mint, token program, ATA derived by spl-associated-token-account):
+        // USDC under Token, PYUSD under Token-2022
+        let cases = [
+            (
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                token,
+                "FGETo8T8wMcN2wCjav8VK6eh3dLk63evNDPxzLSJra8B",
+            ),
+            (
+                "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo",
+                token_2022,
+                "897krAvWH3RbymaCYE3o9emopUwocieHuKTUk9nySpq6",
+            ),
+        ];
+
+        for (mint, token_program, expected) in cases {
+            let mint = Pubkey::from_str(mint).unwrap();
+            let expected = Pubkey::from_str(expected).unwrap();
+            assert_eq!(
+                get_associated_token_address(&wallet, &mint, &token_program),
+                expected
+            );
+        }
+
+        let mint = Pubkey::from_str(cases[0].0).unwrap();
+        assert_ne!(
+            get_associated_token_address(&wallet, &mint, &token),
+            get_associated_token_address(&wallet, &mint, &token_2022)
+        );
+    }
+
+    #[test]
+    fn test_estimate_epoch_start() {
+        let now = Utc::now();
+        let epoch_info = EpochInfo {
+            epoch: 10,
+            slot_index: 332_000,
+            slots_in_epoch: 432_000,
+            absolute_slot: 4_652_000,
+            block_height: 4_652_000,
+            transaction_count: None,
+        };
+
+        // 100k slots left in epoch 10 at 400ms each
+        assert_eq!(
+            estimate_epoch_start(&epoch_info, 11, now),
+            now + chrono::Duration::seconds(40_000)
+        );
+        // plus one full epoch
+        assert_eq!(
+            estimate_epoch_start(&epoch_info, 12, now),
+            now + chrono::Duration::seconds(40_000 + 172_800)
+        );
+        assert_eq!(estimate_epoch_start(&epoch_info, 10, now), now);
+    }
 }
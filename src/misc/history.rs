@@ -0,0 +1,196 @@
+use {
+    crate::constants::{SCILLA_HISTORY_MAX_ENTRIES, SCILLA_HISTORY_RELATIVE_PATH},
+    chrono::{DateTime, Local},
+    std::{env::home_dir, fs, path::Path},
+};
+
+const REDACTED_PATH: &str = "<redacted path>";
+const REDACTED_SECRET: &str = "<redacted secret>";
+
+/// Base58 length from which a token is treated as a secret key. Signatures have
+/// the same shape as keypair bytes, so they get redacted too.
+const SECRET_MIN_BASE58_LEN: usize = 64;
+
+/// One command run in an interactive session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Local>,
+    pub wallet: String,
+    pub command: String,
+}
+
+impl HistoryEntry {
+    pub fn new(wallet: &str, command: &str) -> Self {
+        Self {
+            timestamp: Local::now(),
+            wallet: redact(wallet),
+            command: redact(command),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}",
+            self.timestamp.to_rfc3339(),
+            self.wallet.replace(['\t', '\n'], " "),
+            self.command.replace(['\t', '\n'], " ")
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, '\t');
+        let timestamp = DateTime::parse_from_rfc3339(parts.next()?).ok()?;
+        Some(Self {
+            timestamp: timestamp.with_timezone(&Local),
+            wallet: parts.next()?.to_string(),
+            command: parts.next()?.to_string(),
+        })
+    }
+}
+
+/// Replaces keypair paths and secret-looking values so the history file never
+/// holds anything that could unlock a wallet
+pub fn redact(text: &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            if looks_like_path(word) {
+                REDACTED_PATH
+            } else if looks_like_secret(word) {
+                REDACTED_SECRET
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn looks_like_path(word: &str) -> bool {
+    word.starts_with('/')
+        || word.starts_with('~')
+        || word.starts_with("./")
+        || word.contains('\\')
+        || word.ends_with(".json")
+}
+
+fn looks_like_secret(word: &str) -> bool {
+    let is_byte_array = word.starts_with('[') && word.contains(',');
+    let is_long_base58 =
+        word.len() >= SECRET_MIN_BASE58_LEN && bs58::decode(word).into_vec().is_ok();
+    is_byte_array || is_long_base58
+}
+
+pub fn scilla_history_path() -> std::path::PathBuf {
+    let mut path = home_dir().expect("Error getting home path");
+    path.push(SCILLA_HISTORY_RELATIVE_PATH);
+    path
+}
+
+/// Appends `entry`, keeping only the most recent `SCILLA_HISTORY_MAX_ENTRIES`
+pub fn append_history(path: &Path, entry: &HistoryEntry) -> anyhow::Result<()> {
+    let mut lines: Vec<String> = match fs::read_to_string(path) {
+        Ok(data) => data.lines().map(str::to_string).collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    lines.push(entry.to_line());
+
+    let start = lines.len().saturating_sub(SCILLA_HISTORY_MAX_ENTRIES);
+    let mut data = lines[start..].join("\n");
+    data.push('\n');
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, data)?;
+
+    Ok(())
+}
+
+/// Reads up to `limit` of the most recent entries, newest first. Lines that
+/// fail to parse are skipped.
+pub fn read_history(path: &Path, limit: usize) -> anyhow::Result<Vec<HistoryEntry>> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(data
+        .lines()
+        .rev()
+        .filter_map(HistoryEntry::from_line)
+        .take(limit)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_keypair::Keypair, solana_pubkey::Pubkey, tempfile::TempDir};
+
+    #[test]
+    fn test_redact_paths_and_secrets() {
+        let secret = Keypair::new().to_base58_string();
+        let pubkey = Pubkey::new_unique().to_string();
+
+        assert_eq!(
+            redact("Switch wallet ~/.config/solana/id.json"),
+            "Switch wallet <redacted path>"
+        );
+        assert_eq!(redact("key /keys/stake.json"), "key <redacted path>");
+        assert_eq!(
+            redact(&format!("import {secret}")),
+            "import <redacted secret>"
+        );
+        assert_eq!(redact("bytes [1,2,3]"), "bytes <redacted secret>");
+        assert_eq!(
+            redact(&format!("Transfer {pubkey}")),
+            format!("Transfer {pubkey}")
+        );
+    }
+
+    #[test]
+    fn test_history_round_trip_newest_first() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("nested").join("history");
+
+        append_history(&path, &HistoryEntry::new("hot", "Cluster › Epoch Info")).unwrap();
+        append_history(&path, &HistoryEntry::new("hot", "Account › Balance")).unwrap();
+
+        let entries = read_history(&path, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "Account › Balance");
+        assert_eq!(entries[1].command, "Cluster › Epoch Info");
+        assert_eq!(entries[0].wallet, "hot");
+
+        assert_eq!(read_history(&path, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("history");
+
+        for i in 0..SCILLA_HISTORY_MAX_ENTRIES + 5 {
+            append_history(&path, &HistoryEntry::new("hot", &format!("cmd {i}"))).unwrap();
+        }
+
+        let entries = read_history(&path, usize::MAX).unwrap();
+        assert_eq!(entries.len(), SCILLA_HISTORY_MAX_ENTRIES);
+        assert_eq!(
+            entries[0].command,
+            format!("cmd {}", SCILLA_HISTORY_MAX_ENTRIES + 4)
+        );
+    }
+
+    #[test]
+    fn test_missing_history_is_empty() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+        assert!(
+            read_history(&temp_dir.path().join("missing"), 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
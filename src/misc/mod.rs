@@ -1 +1,9 @@
+pub mod authority;
+pub mod encrypted_keypair;
 pub mod helpers;
+pub mod history;
+pub mod report;
+pub mod rpc_metrics;
+pub mod rpc_provider;
+pub mod signers;
+pub mod token;
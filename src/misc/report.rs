@@ -0,0 +1,425 @@
+use {
+    crate::{
+        constants::WALLET_REPORT_TRANSACTIONS,
+        context::ScillaContext,
+        misc::{
+            authority::fetch_vote_accounts_by_withdrawer,
+            helpers::{bincode_deserialize, fetch_stake_accounts_by_authority, lamports_to_sol},
+            token::fetch_token_holdings,
+        },
+        ui::{table_to_csv, theme},
+    },
+    chrono::DateTime,
+    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    serde::Serialize,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config,
+    solana_stake_interface::state::StakeStateV2,
+    solana_vote_interface::state::VoteStateV4,
+    std::fmt::{self, Write},
+};
+
+/// How a wallet report is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn all() -> Vec<Self> {
+        vec![ReportFormat::Table, ReportFormat::Json, ReportFormat::Csv]
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Table => write!(f, "Table"),
+            ReportFormat::Json => write!(f, "JSON"),
+            ReportFormat::Csv => write!(f, "CSV"),
+        }
+    }
+}
+
+/// Snapshot of what a wallet holds and controls on one cluster
+#[derive(Debug, Serialize)]
+pub struct WalletReport {
+    pub wallet: String,
+    pub cluster: String,
+    pub generated_at: String,
+    pub sol_balance: f64,
+    pub tokens: Vec<ReportToken>,
+    pub stake_accounts: Vec<ReportStakeAccount>,
+    pub vote_accounts: Vec<ReportVoteAccount>,
+    pub recent_transactions: Vec<ReportTransaction>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportToken {
+    pub account: String,
+    pub mint: String,
+    pub program: String,
+    pub balance: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportStakeAccount {
+    pub address: String,
+    pub sol: f64,
+    pub state: String,
+    /// Authorities the wallet holds over the account
+    pub roles: String,
+}
+
+/// Vote account the wallet is the withdrawer of
+#[derive(Debug, Serialize)]
+pub struct ReportVoteAccount {
+    pub address: String,
+    pub sol: f64,
+    pub node: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub time: Option<String>,
+    pub status: String,
+}
+
+impl WalletReport {
+    pub async fn gather(ctx: &ScillaContext, wallet: &Pubkey) -> anyhow::Result<Self> {
+        let balance = ctx
+            .rpc()
+            .get_balance_with_commitment(wallet, ctx.read_commitment())
+            .await?
+            .value;
+
+        let tokens = fetch_token_holdings(ctx, wallet)
+            .await?
+            .into_iter()
+            .map(|holding| ReportToken {
+                account: holding.address.to_string(),
+                mint: holding.mint.to_string(),
+                program: holding.program.to_string(),
+                balance: holding.balance,
+            })
+            .collect();
+
+        let stake_accounts = fetch_stake_accounts_by_authority(ctx, wallet)
+            .await?
+            .into_iter()
+            .map(|(address, account)| {
+                let (state, roles) = describe_stake(&account.data, wallet);
+                ReportStakeAccount {
+                    address: address.to_string(),
+                    sol: lamports_to_sol(account.lamports),
+                    state,
+                    roles,
+                }
+            })
+            .collect();
+
+        let vote_accounts = fetch_vote_accounts_by_withdrawer(ctx, wallet)
+            .await?
+            .into_iter()
+            .map(|(address, account)| ReportVoteAccount {
+                address: address.to_string(),
+                sol: lamports_to_sol(account.lamports),
+                node: VoteStateV4::deserialize(&account.data, &address)
+                    .map(|vote_state| vote_state.node_pubkey.to_string())
+                    .unwrap_or_else(|_| "Unknown".to_string()),
+            })
+            .collect();
+
+        let recent_transactions = ctx
+            .rpc()
+            .get_signatures_for_address_with_config(
+                wallet,
+                GetConfirmedSignaturesForAddress2Config {
+                    limit: Some(WALLET_REPORT_TRANSACTIONS),
+                    commitment: Some(ctx.read_commitment()),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )
+            .await?
+            .into_iter()
+            .map(|entry| ReportTransaction {
+                signature: entry.signature,
+                slot: entry.slot,
+                time: entry
+                    .block_time
+                    .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+                status: if entry.err.is_some() {
+                    "Failed".to_string()
+                } else {
+                    "Success".to_string()
+                },
+            })
+            .collect();
+
+        Ok(Self {
+            wallet: wallet.to_string(),
+            cluster: ctx.cluster().to_string(),
+            generated_at: chrono::Utc::now()
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+            sol_balance: lamports_to_sol(balance),
+            tokens,
+            stake_accounts,
+            vote_accounts,
+            recent_transactions,
+        })
+    }
+
+    pub fn render(&self, format: ReportFormat) -> anyhow::Result<String> {
+        match format {
+            ReportFormat::Table => Ok(self.render_tables()),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ReportFormat::Csv => Ok(table_to_csv(&self.flat_table())),
+        }
+    }
+
+    fn render_tables(&self) -> String {
+        let mut summary = Table::new();
+        summary
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                theme::header_cell("Field"),
+                theme::header_cell("Value"),
+            ])
+            .add_row(vec![Cell::new("Wallet"), Cell::new(&self.wallet)])
+            .add_row(vec![Cell::new("Cluster"), Cell::new(&self.cluster)])
+            .add_row(vec![Cell::new("Generated"), Cell::new(&self.generated_at)])
+            .add_row(vec![
+                Cell::new("SOL Balance"),
+                Cell::new(format!("{} SOL", self.sol_balance)),
+            ]);
+
+        let sections = [
+            (
+                "TOKENS",
+                section_table(
+                    ["Token Account", "Mint", "Program", "Balance"],
+                    self.tokens.iter().map(|token| {
+                        [
+                            token.account.clone(),
+                            token.mint.clone(),
+                            token.program.clone(),
+                            token.balance.clone(),
+                        ]
+                    }),
+                ),
+            ),
+            (
+                "STAKE ACCOUNTS",
+                section_table(
+                    ["Stake Account", "Balance", "State", "Wallet Is"],
+                    self.stake_accounts.iter().map(|stake| {
+                        [
+                            stake.address.clone(),
+                            format!("{} SOL", stake.sol),
+                            stake.state.clone(),
+                            stake.roles.clone(),
+                        ]
+                    }),
+                ),
+            ),
+            (
+                "VOTE ACCOUNTS",
+                section_table(
+                    ["Vote Account", "Balance", "Node"],
+                    self.vote_accounts.iter().map(|vote| {
+                        [
+                            vote.address.clone(),
+                            format!("{} SOL", vote.sol),
+                            vote.node.clone(),
+                        ]
+                    }),
+                ),
+            ),
+            (
+                "RECENT TRANSACTIONS",
+                section_table(
+                    ["Signature", "Slot", "Time", "Status"],
+                    self.recent_transactions.iter().map(|tx| {
+                        [
+                            tx.signature.clone(),
+                            tx.slot.to_string(),
+                            tx.time.clone().unwrap_or_else(|| "N/A".to_string()),
+                            tx.status.clone(),
+                        ]
+                    }),
+                ),
+            ),
+        ];
+
+        let mut out = format!("{}\n{summary}\n", theme::success("WALLET REPORT").bold());
+        for (title, table) in sections {
+            let _ = write!(out, "\n{}\n", theme::success(title).bold());
+            match table {
+                Some(table) => {
+                    let _ = writeln!(out, "{table}");
+                }
+                None => {
+                    let _ = writeln!(out, "{}", theme::muted("None"));
+                }
+            }
+        }
+        out
+    }
+
+    /// Every entry of the report in one table with a `Section` column, so
+    /// the CSV imports as a single sheet
+    fn flat_table(&self) -> Table {
+        let mut table = Table::new();
+        table.set_header(vec!["Section", "Address", "Detail", "Amount"]);
+
+        table.add_row(vec![
+            "SOL".to_string(),
+            self.wallet.clone(),
+            self.cluster.clone(),
+            self.sol_balance.to_string(),
+        ]);
+        for token in &self.tokens {
+            table.add_row(vec![
+                "Token".to_string(),
+                token.account.clone(),
+                format!("{} mint {}", token.program, token.mint),
+                token.balance.clone(),
+            ]);
+        }
+        for stake in &self.stake_accounts {
+            table.add_row(vec![
+                "Stake".to_string(),
+                stake.address.clone(),
+                format!("{}; {}", stake.state, stake.roles),
+                stake.sol.to_string(),
+            ]);
+        }
+        for vote in &self.vote_accounts {
+            table.add_row(vec![
+                "Vote".to_string(),
+                vote.address.clone(),
+                format!("node {}", vote.node),
+                vote.sol.to_string(),
+            ]);
+        }
+        for tx in &self.recent_transactions {
+            table.add_row(vec![
+                "Transaction".to_string(),
+                tx.signature.clone(),
+                format!(
+                    "{} at slot {}{}",
+                    tx.status,
+                    tx.slot,
+                    tx.time
+                        .as_ref()
+                        .map(|time| format!(", {time}"))
+                        .unwrap_or_default()
+                ),
+                String::new(),
+            ]);
+        }
+
+        table
+    }
+}
+
+/// Table for one report section, `None` when the section is empty
+fn section_table<const N: usize>(
+    headers: [&str; N],
+    rows: impl Iterator<Item = [String; N]>,
+) -> Option<Table> {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(headers.map(theme::bold_cell));
+    for row in rows {
+        table.add_row(row);
+    }
+
+    (table.row_count() > 0).then_some(table)
+}
+
+/// State of a stake account and the authorities `wallet` holds over it
+fn describe_stake(data: &[u8], wallet: &Pubkey) -> (String, String) {
+    let (meta, state) = match bincode_deserialize::<StakeStateV2>(data, "stake account data") {
+        Ok(StakeStateV2::Initialized(meta)) => (meta, "Initialized".to_string()),
+        Ok(StakeStateV2::Stake(meta, stake, _)) => (
+            meta,
+            format!("Delegated to {}", stake.delegation.voter_pubkey),
+        ),
+        Ok(StakeStateV2::Uninitialized) => return ("Uninitialized".to_string(), String::new()),
+        Ok(StakeStateV2::RewardsPool) | Err(_) => {
+            return ("Unknown".to_string(), String::new());
+        }
+    };
+
+    let mut roles = Vec::new();
+    if meta.authorized.staker == *wallet {
+        roles.push("Staker");
+    }
+    if meta.authorized.withdrawer == *wallet {
+        roles.push("Withdrawer");
+    }
+
+    (state, roles.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> WalletReport {
+        WalletReport {
+            wallet: "Wallet111".to_string(),
+            cluster: "devnet".to_string(),
+            generated_at: "2026-01-01 00:00:00 UTC".to_string(),
+            sol_balance: 1.5,
+            tokens: vec![ReportToken {
+                account: "TokenAcc1".to_string(),
+                mint: "Mint1".to_string(),
+                program: "Token".to_string(),
+                balance: "1,000".to_string(),
+            }],
+            stake_accounts: Vec::new(),
+            vote_accounts: Vec::new(),
+            recent_transactions: vec![ReportTransaction {
+                signature: "Sig1".to_string(),
+                slot: 42,
+                time: None,
+                status: "Failed".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_wallet_report_csv_has_one_row_per_entry() {
+        let csv = sample_report().render(ReportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.split("\r\n").filter(|line| !line.is_empty()).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Section,Address,Detail,Amount",
+                "SOL,Wallet111,devnet,1.5",
+                "Token,TokenAcc1,Token mint Mint1,\"1,000\"",
+                "Transaction,Sig1,Failed at slot 42,",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wallet_report_json_keeps_empty_sections() {
+        let json: serde_json::Value =
+            serde_json::from_str(&sample_report().render(ReportFormat::Json).unwrap()).unwrap();
+
+        assert_eq!(json["sol_balance"], 1.5);
+        assert_eq!(json["stake_accounts"], serde_json::json!([]));
+        assert_eq!(json["recent_transactions"][0]["slot"], 42);
+    }
+}
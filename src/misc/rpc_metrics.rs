@@ -0,0 +1,88 @@
+use {
+    async_trait::async_trait,
+    solana_rpc_client::{
+        http_sender::HttpSender,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    },
+    solana_rpc_client_api::{client_error::Result as ClientResult, request::RpcRequest},
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+};
+
+/// Call count and latency of a single RPC method
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcCallStats {
+    pub count: u64,
+    pub total_time: Duration,
+}
+
+impl RpcCallStats {
+    pub fn average(&self) -> Duration {
+        self.total_time
+            .checked_div(self.count as u32)
+            .unwrap_or_default()
+    }
+}
+
+/// RPC calls made during the session, keyed by method name
+#[derive(Debug, Default)]
+pub struct RpcMetrics {
+    calls: Mutex<HashMap<String, RpcCallStats>>,
+}
+
+impl RpcMetrics {
+    fn record(&self, method: String, elapsed: Duration) {
+        let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = calls.entry(method).or_default();
+        stats.count += 1;
+        stats.total_time += elapsed;
+    }
+
+    pub fn snapshot(&self) -> Vec<(String, RpcCallStats)> {
+        let calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        calls
+            .iter()
+            .map(|(method, stats)| (method.clone(), *stats))
+            .collect()
+    }
+}
+
+/// `HttpSender` that times every request into a shared `RpcMetrics`
+pub struct InstrumentedSender {
+    inner: HttpSender,
+    metrics: Arc<RpcMetrics>,
+}
+
+impl InstrumentedSender {
+    pub fn new(url: String, metrics: Arc<RpcMetrics>) -> Self {
+        Self {
+            inner: HttpSender::new(url),
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for InstrumentedSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let start = Instant::now();
+        let result = self.inner.send(request, params).await;
+        self.metrics.record(request.to_string(), start.elapsed());
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
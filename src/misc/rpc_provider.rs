@@ -0,0 +1,73 @@
+use {
+    anyhow::anyhow, async_trait::async_trait, solana_account::Account,
+    solana_epoch_info::EpochInfo, solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+};
+
+/// The account and epoch reads behind stake withdraw validation
+/// (`validate_withdraw_stake`) and `fetch_account_with_epoch`, so tests can
+/// run them against `MockRpcProvider` without a network. `RpcClient` is the
+/// real implementation; all other RPC access goes through
+/// `ScillaContext::rpc` directly and is not mockable.
+#[async_trait]
+pub trait RpcProvider: Send + Sync {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Account>;
+
+    async fn get_epoch_info(&self) -> anyhow::Result<EpochInfo>;
+}
+
+#[async_trait]
+impl RpcProvider for RpcClient {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Account> {
+        RpcClient::get_account(self, pubkey)
+            .await
+            .map_err(|_| anyhow!("{pubkey} account does not exist"))
+    }
+
+    async fn get_epoch_info(&self) -> anyhow::Result<EpochInfo> {
+        Ok(RpcClient::get_epoch_info(self).await?)
+    }
+}
+
+/// In-memory `RpcProvider` serving fixed accounts at a fixed epoch
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockRpcProvider {
+    accounts: std::collections::HashMap<Pubkey, Account>,
+    epoch: u64,
+}
+
+#[cfg(test)]
+impl MockRpcProvider {
+    pub fn with_account(mut self, pubkey: Pubkey, account: Account) -> Self {
+        self.accounts.insert(pubkey, account);
+        self
+    }
+
+    pub fn at_epoch(mut self, epoch: u64) -> Self {
+        self.epoch = epoch;
+        self
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl RpcProvider for MockRpcProvider {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Account> {
+        self.accounts
+            .get(pubkey)
+            .cloned()
+            .ok_or_else(|| anyhow!("{pubkey} account does not exist"))
+    }
+
+    async fn get_epoch_info(&self) -> anyhow::Result<EpochInfo> {
+        Ok(EpochInfo {
+            epoch: self.epoch,
+            slot_index: 0,
+            slots_in_epoch: 432_000,
+            absolute_slot: self.epoch * 432_000,
+            block_height: self.epoch * 432_000,
+            transaction_count: None,
+        })
+    }
+}
@@ -0,0 +1,133 @@
+use {
+    crate::prompt::prompt_keypair_from_path,
+    anyhow::bail,
+    solana_instruction::Instruction,
+    solana_keypair::{Keypair, Signer},
+    solana_pubkey::Pubkey,
+    std::path::{Path, PathBuf},
+};
+
+/// Local keypairs collected for one transaction, at most one per pubkey
+#[derive(Default)]
+pub struct SignerSet {
+    keypairs: Vec<Keypair>,
+    /// Files already read, so an encrypted one is asked for only once
+    paths: Vec<(PathBuf, Pubkey)>,
+}
+
+impl SignerSet {
+    /// Reads the keypair at `path` into the set and returns its pubkey,
+    /// asking for the passphrase of an encrypted file. A file or keypair
+    /// already in the set is not read or added twice.
+    pub fn read<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<Pubkey> {
+        let path = path.as_ref();
+        if let Some((_, pubkey)) = self.paths.iter().find(|(read, _)| read == path) {
+            return Ok(*pubkey);
+        }
+
+        let keypair = prompt_keypair_from_path(path)?;
+        let pubkey = keypair.pubkey();
+        self.paths.push((path.to_path_buf(), pubkey));
+        if !self.contains(&pubkey) {
+            self.keypairs.push(keypair);
+        }
+        Ok(pubkey)
+    }
+
+    pub fn contains(&self, pubkey: &Pubkey) -> bool {
+        self.keypairs
+            .iter()
+            .any(|keypair| keypair.pubkey() == *pubkey)
+    }
+
+    /// The signers `instructions` need, fee payer first, ready for
+    /// `build_and_send_tx`. Collected keypairs the instructions don't ask for
+    /// are left out, since signing with them would be rejected.
+    pub fn signers_for<'a>(
+        &'a self,
+        fee_payer: &'a Keypair,
+        instructions: &[Instruction],
+    ) -> anyhow::Result<Vec<&'a dyn Signer>> {
+        let mut signers: Vec<&dyn Signer> = vec![fee_payer];
+
+        for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+            if !meta.is_signer || signers.iter().any(|s| s.pubkey() == meta.pubkey) {
+                continue;
+            }
+
+            match self.keypairs.iter().find(|k| k.pubkey() == meta.pubkey) {
+                Some(keypair) => signers.push(keypair),
+                None => bail!(
+                    "{} must sign this transaction, but none of the provided keypairs belongs to \
+                     it",
+                    meta.pubkey
+                ),
+            }
+        }
+
+        Ok(signers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_instruction::AccountMeta, solana_keypair::EncodableKey, tempfile::TempDir,
+    };
+
+    fn instruction_signed_by(signers: &[Pubkey]) -> Instruction {
+        Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            signers
+                .iter()
+                .map(|pubkey| AccountMeta::new(*pubkey, true))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_read_dedupes_keypairs() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("signer.json");
+        let keypair = Keypair::new();
+        keypair.write_to_file(&path).unwrap();
+
+        let mut set = SignerSet::default();
+        assert_eq!(set.read(&path).unwrap(), keypair.pubkey());
+        assert_eq!(set.read(&path).unwrap(), keypair.pubkey());
+        assert_eq!(set.keypairs.len(), 1);
+        assert_eq!(set.paths.len(), 1);
+    }
+
+    #[test]
+    fn test_signers_for_orders_and_filters() {
+        let fee_payer = Keypair::new();
+        let needed = Keypair::new();
+        let unused = Keypair::new();
+        let needed_pubkey = needed.pubkey();
+        let set = SignerSet {
+            keypairs: vec![unused, needed],
+            ..SignerSet::default()
+        };
+
+        let ix = instruction_signed_by(&[fee_payer.pubkey(), needed_pubkey, needed_pubkey]);
+        let signers = set.signers_for(&fee_payer, &[ix]).unwrap();
+
+        let pubkeys: Vec<Pubkey> = signers.iter().map(|s| s.pubkey()).collect();
+        assert_eq!(pubkeys, vec![fee_payer.pubkey(), needed_pubkey]);
+    }
+
+    #[test]
+    fn test_signers_for_missing_signer() {
+        let fee_payer = Keypair::new();
+        let missing = Pubkey::new_unique();
+
+        let err = SignerSet::default()
+            .signers_for(&fee_payer, &[instruction_signed_by(&[missing])])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains(&missing.to_string()));
+    }
+}
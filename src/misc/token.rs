@@ -0,0 +1,460 @@
+use {
+    crate::{
+        constants::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        context::ScillaContext,
+    },
+    anyhow::{anyhow, bail},
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+    },
+    std::{collections::HashMap, fmt, str::FromStr},
+};
+
+/// Size of the base mint layout shared by Token and Token-2022
+const MINT_LEN: usize = 82;
+/// Size of the base token account layout; Token-2022 extensions start after it
+const TOKEN_ACCOUNT_LEN: usize = 165;
+/// Token-2022 `AccountType` byte written right after the base account length
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+/// Token-2022 extension type of `TransferFeeConfig`
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+/// Token instruction index of `TransferChecked`
+const TRANSFER_CHECKED_IX: u8 = 12;
+/// Associated token instruction index of `CreateIdempotent`
+const CREATE_IDEMPOTENT_IX: u8 = 1;
+
+/// Token program a mint belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenProgram {
+    Token,
+    Token2022,
+    Custom(Pubkey),
+}
+
+impl TokenProgram {
+    pub fn id(&self) -> Pubkey {
+        match self {
+            TokenProgram::Token => Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap(),
+            TokenProgram::Token2022 => Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap(),
+            TokenProgram::Custom(program_id) => *program_id,
+        }
+    }
+
+    /// Token program owning an account, if it is one Scilla can decode
+    pub fn from_owner(owner: &Pubkey) -> Option<Self> {
+        [TokenProgram::Token, TokenProgram::Token2022]
+            .into_iter()
+            .find(|program| program.id() == *owner)
+    }
+}
+
+impl fmt::Display for TokenProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenProgram::Token => write!(f, "Token"),
+            TokenProgram::Token2022 => write!(f, "Token-2022"),
+            TokenProgram::Custom(program_id) => write!(f, "Custom ({program_id})"),
+        }
+    }
+}
+
+/// One side of a Token-2022 `TransferFeeConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    /// Fee withheld from a transfer of `amount`, rounded up and capped like
+    /// the program does
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        let fee = (amount as u128 * self.basis_points as u128).div_ceil(10_000);
+        (fee as u64).min(self.maximum_fee)
+    }
+}
+
+/// Transfer fee of a Token-2022 mint; `newer` takes over from its epoch on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub older: TransferFee,
+    pub newer: TransferFee,
+}
+
+impl TransferFeeConfig {
+    pub fn active(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer.epoch {
+            &self.newer
+        } else {
+            &self.older
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintInfo {
+    pub program: TokenProgram,
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub freeze_authority: Option<Pubkey>,
+    pub transfer_fee: Option<TransferFeeConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAccountInfo {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+/// `COption<Pubkey>`: a 4 byte tag followed by the key
+fn read_optional_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    (data[offset] == 1).then(|| read_pubkey(data, offset + 4))
+}
+
+fn read_transfer_fee(data: &[u8], offset: usize) -> TransferFee {
+    TransferFee {
+        epoch: read_u64(data, offset),
+        maximum_fee: read_u64(data, offset + 8),
+        basis_points: read_u16(data, offset + 16),
+    }
+}
+
+/// Walks the Token-2022 TLV extensions that follow the base layout and returns
+/// the value of the first extension of `extension_type`.
+fn find_extension(data: &[u8], extension_type: u16) -> Option<&[u8]> {
+    let mut offset = TOKEN_ACCOUNT_LEN + 1;
+
+    while offset + 4 <= data.len() {
+        let current_type = read_u16(data, offset);
+        let length = read_u16(data, offset + 2) as usize;
+        let value = data.get(offset + 4..offset + 4 + length)?;
+
+        if current_type == extension_type {
+            return Some(value);
+        }
+        offset += 4 + length;
+    }
+
+    None
+}
+
+/// Decodes a mint owned by `owner`, which must be Token or Token-2022
+pub fn parse_mint(owner: &Pubkey, data: &[u8]) -> anyhow::Result<MintInfo> {
+    let program = TokenProgram::from_owner(owner)
+        .ok_or_else(|| anyhow!("Account is owned by {owner}, not a token program"))?;
+
+    let is_mint = match program {
+        TokenProgram::Token => data.len() == MINT_LEN,
+        _ => {
+            data.len() == MINT_LEN
+                || (data.len() > TOKEN_ACCOUNT_LEN && data[TOKEN_ACCOUNT_LEN] == ACCOUNT_TYPE_MINT)
+        }
+    };
+    if !is_mint || data[45] != 1 {
+        bail!("Account is not an initialized {program} mint");
+    }
+
+    let transfer_fee = find_extension(data, EXTENSION_TRANSFER_FEE_CONFIG)
+        .filter(|value| value.len() >= 108)
+        .map(|value| TransferFeeConfig {
+            older: read_transfer_fee(value, 72),
+            newer: read_transfer_fee(value, 90),
+        });
+
+    Ok(MintInfo {
+        program,
+        mint_authority: read_optional_pubkey(data, 0),
+        supply: read_u64(data, 36),
+        decimals: data[44],
+        freeze_authority: read_optional_pubkey(data, 46),
+        transfer_fee,
+    })
+}
+
+/// Decodes a token account of either token program
+pub fn parse_token_account(data: &[u8]) -> anyhow::Result<TokenAccountInfo> {
+    let is_account = data.len() == TOKEN_ACCOUNT_LEN
+        || (data.len() > TOKEN_ACCOUNT_LEN && data[TOKEN_ACCOUNT_LEN] == ACCOUNT_TYPE_ACCOUNT);
+    if !is_account {
+        bail!("Account is not a token account");
+    }
+
+    Ok(TokenAccountInfo {
+        mint: read_pubkey(data, 0),
+        owner: read_pubkey(data, 32),
+        amount: read_u64(data, 64),
+    })
+}
+
+/// Converts raw token units into a decimal string using the mint's decimals
+pub fn format_token_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    // split the zero-padded digits rather than dividing, since 10^decimals
+    // overflows u64 for decimals above 19
+    let digits = format!("{amount:0>width$}", width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+/// Parses a decimal token amount into raw units without going through f64
+pub fn parse_token_amount(input: &str, decimals: u8) -> anyhow::Result<u64> {
+    let input = input.trim();
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+
+    if fraction.len() > decimals as usize {
+        bail!("Amount {input} has more than {decimals} decimal places");
+    }
+    if whole.is_empty() && fraction.is_empty() {
+        bail!("Amount cannot be empty");
+    }
+
+    let padded = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+    let amount: u64 = padded
+        .parse()
+        .map_err(|_| anyhow!("Invalid amount: {input}"))?;
+
+    if amount == 0 {
+        bail!("Amount must be greater than 0");
+    }
+    Ok(amount)
+}
+
+/// `TransferChecked`, which both token programs accept and which Token-2022
+/// requires for mints with a transfer fee
+pub fn transfer_checked(
+    program: TokenProgram,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    let mut data = vec![TRANSFER_CHECKED_IX];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+
+    Instruction::new_with_bytes(
+        program.id(),
+        &data,
+        vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Creates `wallet`'s associated token account for `mint`, succeeding if it
+/// already exists
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    program: TokenProgram,
+) -> Instruction {
+    let program_id = program.id();
+    let associated_token_address =
+        crate::misc::helpers::get_associated_token_address(wallet, mint, &program_id);
+
+    Instruction::new_with_bytes(
+        Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap(),
+        &[CREATE_IDEMPOTENT_IX],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(program_id, false),
+        ],
+    )
+}
+
+/// A token account owned by a wallet, with its balance in the mint's units
+#[derive(Debug, Clone)]
+pub struct TokenHolding {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    pub program: TokenProgram,
+    pub balance: String,
+}
+
+/// Every Token and Token-2022 account owned by `wallet`. Balances of mints
+/// that can't be read are shown as raw amounts.
+pub async fn fetch_token_holdings(
+    ctx: &ScillaContext,
+    wallet: &Pubkey,
+) -> anyhow::Result<Vec<TokenHolding>> {
+    let mut token_accounts = Vec::new();
+
+    for program in [TokenProgram::Token, TokenProgram::Token2022] {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                32,
+                wallet.as_ref(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(ctx.read_commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = ctx
+            .rpc()
+            .get_program_accounts_with_config(&program.id(), config)
+            .await?;
+
+        // the owner filter can also match mints, which don't parse as accounts
+        token_accounts.extend(accounts.into_iter().filter_map(|(address, account)| {
+            parse_token_account(&account.data)
+                .ok()
+                .map(|info| (address, program, info))
+        }));
+    }
+
+    if token_accounts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut mints: Vec<Pubkey> = token_accounts
+        .iter()
+        .map(|(_, _, info)| info.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let decimals: HashMap<Pubkey, u8> = ctx
+        .rpc()
+        .get_multiple_accounts(&mints)
+        .await?
+        .into_iter()
+        .zip(&mints)
+        .filter_map(|(account, mint)| {
+            let account = account?;
+            let info = parse_mint(&account.owner, &account.data).ok()?;
+            Some((*mint, info.decimals))
+        })
+        .collect();
+
+    Ok(token_accounts
+        .into_iter()
+        .map(|(address, program, info)| TokenHolding {
+            address,
+            mint: info.mint,
+            program,
+            balance: match decimals.get(&info.mint) {
+                Some(decimals) => format_token_amount(info.amount, *decimals),
+                None => format!("{} (raw)", info.amount),
+            },
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mint_data(decimals: u8) -> Vec<u8> {
+        let mut data = vec![0; MINT_LEN];
+        data[0] = 1;
+        data[4..36].copy_from_slice(Pubkey::new_unique().as_ref());
+        data[36..44].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[44] = decimals;
+        data[45] = 1;
+        data
+    }
+
+    #[test]
+    fn test_parse_legacy_mint() {
+        let data = mint_data(6);
+        let mint = parse_mint(&TokenProgram::Token.id(), &data).unwrap();
+
+        assert_eq!(mint.program, TokenProgram::Token);
+        assert_eq!(mint.supply, 1_000_000);
+        assert_eq!(mint.decimals, 6);
+        assert!(mint.mint_authority.is_some());
+        assert_eq!(mint.freeze_authority, None);
+        assert_eq!(mint.transfer_fee, None);
+    }
+
+    #[test]
+    fn test_parse_token_2022_mint_with_transfer_fee() {
+        let mut data = mint_data(9);
+        data.resize(TOKEN_ACCOUNT_LEN, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+
+        let mut value = vec![0; 108];
+        value[90..98].copy_from_slice(&10u64.to_le_bytes());
+        value[98..106].copy_from_slice(&5_000u64.to_le_bytes());
+        value[106..108].copy_from_slice(&50u16.to_le_bytes());
+        data.extend_from_slice(&EXTENSION_TRANSFER_FEE_CONFIG.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(&value);
+
+        let mint = parse_mint(&TokenProgram::Token2022.id(), &data).unwrap();
+        let fee = mint.transfer_fee.expect("transfer fee should be parsed");
+
+        assert_eq!(mint.program, TokenProgram::Token2022);
+        assert_eq!(fee.active(10).basis_points, 50);
+        assert_eq!(fee.active(9).basis_points, 0);
+        // 0.5% of 100_001 rounds up to 501
+        assert_eq!(fee.active(10).fee_for(100_001), 501);
+        assert_eq!(fee.active(10).fee_for(10_000_000), 5_000);
+    }
+
+    #[test]
+    fn test_parse_mint_rejects_other_owners() {
+        assert!(parse_mint(&Pubkey::new_unique(), &mint_data(6)).is_err());
+    }
+
+    #[test]
+    fn test_token_amount_round_trip() {
+        assert_eq!(parse_token_amount("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_token_amount(".25", 2).unwrap(), 25);
+        assert_eq!(parse_token_amount("3", 0).unwrap(), 3);
+        assert!(parse_token_amount("1.1234567", 6).is_err());
+        assert!(parse_token_amount("0", 6).is_err());
+
+        assert_eq!(format_token_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_token_amount(2_000_000, 6), "2");
+        assert_eq!(format_token_amount(7, 0), "7");
+        assert_eq!(format_token_amount(5, 3), "0.005");
+        assert_eq!(
+            format_token_amount(u64::MAX, 25),
+            "0.0000018446744073709551615"
+        );
+    }
+}
@@ -1,173 +1,470 @@
 use {
     crate::{
         commands::{
-            Command, CommandGroup, account::AccountCommand, cluster::ClusterCommand,
-            config::ConfigCommand, stake::StakeCommand, transaction::TransactionCommand,
-            vote::VoteCommand,
+            Command, CommandGroup, NavStack, account::AccountCommand, cluster::ClusterCommand,
+            config::ConfigCommand, stake::StakeCommand, token::TokenCommand,
+            transaction::TransactionCommand, vote::VoteCommand,
         },
+        config::{Cluster, ScillaConfig},
+        constants::MAINNET_CONFIRM_PHRASE,
         context::ScillaContext,
-        ui::print_error,
+        misc::helpers::{
+            DurableNonce, SolAmount, WithdrawAmount, is_encrypted_keypair_file, lamports_to_sol,
+            read_keypair_with_passphrase,
+        },
+        ui::{print_error, print_summary, theme},
+    },
+    inquire::{
+        Confirm, InquireError, Password, PasswordDisplayMode, Select, Text, validator::Validation,
+    },
+    solana_commitment_config::CommitmentLevel,
+    solana_keypair::Keypair,
+    std::{
+        fmt::{self, Display},
+        path::{Path, PathBuf},
+        process::exit,
+        str::FromStr,
+        sync::atomic::{AtomicBool, Ordering},
     },
-    console::style,
-    inquire::{Confirm, InquireError, Select, Text},
-    std::{fmt::Display, path::PathBuf, process::exit, str::FromStr},
 };
-pub fn prompt_for_command() -> anyhow::Result<Command> {
-    let top_level = Select::new(
-        "Choose a command group:",
-        vec![
-            CommandGroup::Account,
-            CommandGroup::Cluster,
-            CommandGroup::Stake,
-            CommandGroup::Vote,
-            CommandGroup::Transaction,
-            CommandGroup::ScillaConfig,
-            CommandGroup::Exit,
-        ],
-    )
-    .prompt()?;
 
-    let command = match top_level {
+/// Whether confirmations currently answer themselves
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+/// `--yes` on the command line, kept across config reloads
+static ASSUME_YES_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Menu entry that shows a short description next to the command name
+struct CommandChoice<T> {
+    command: T,
+    help: &'static str,
+}
+
+impl<T: Display> Display for CommandChoice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} — {}", self.command, self.help)
+    }
+}
+
+fn select_command<T: Display>(
+    msg: &str,
+    commands: Vec<T>,
+    help: fn(&T) -> &'static str,
+) -> anyhow::Result<T> {
+    let choices = commands
+        .into_iter()
+        .map(|command| CommandChoice {
+            help: help(&command),
+            command,
+        })
+        .collect();
+
+    let choice = Select::new(msg, choices)
+        .with_formatter(&|option| option.value.command.to_string())
+        .prompt()?;
+
+    Ok(choice.command)
+}
+
+/// Prompts from the menu on top of `nav`, or from the group list when no menu
+/// is open. Choosing a group with its own menu enters it.
+pub fn prompt_for_command(nav: &mut NavStack) -> anyhow::Result<Command> {
+    let group = match nav.current() {
+        Some(group) => group.clone(),
+        None => prompt_command_group()?,
+    };
+
+    if group.has_menu() {
+        if nav.current().is_none() {
+            nav.enter(group.clone());
+        }
+        println!("\n{}", theme::info(nav.breadcrumb()).bold());
+    }
+
+    let command = match group {
+        CommandGroup::Search => prompt_command_palette()?,
         CommandGroup::Cluster => Command::Cluster(prompt_cluster()?),
         CommandGroup::Stake => Command::Stake(prompt_stake()?),
         CommandGroup::Account => Command::Account(prompt_account()?),
         CommandGroup::Vote => Command::Vote(prompt_vote()?),
+        CommandGroup::Token => Command::Token(prompt_token()?),
         CommandGroup::ScillaConfig => Command::ScillaConfig(prompt_config()?),
         CommandGroup::Transaction => Command::Transaction(prompt_transaction()?),
+        CommandGroup::SwitchWallet => Command::SwitchWallet,
+        CommandGroup::ToggleReadCommitment => Command::ToggleReadCommitment,
+        CommandGroup::ToggleDryRun => Command::ToggleDryRun,
         CommandGroup::Exit => Command::Exit,
     };
 
     Ok(command)
 }
 
-fn prompt_cluster() -> anyhow::Result<ClusterCommand> {
-    let choice = Select::new(
-        "Cluster Command:",
+fn prompt_command_group() -> anyhow::Result<CommandGroup> {
+    select_command(
+        "Choose a command group:",
         vec![
-            ClusterCommand::EpochInfo,
-            ClusterCommand::CurrentSlot,
-            ClusterCommand::BlockHeight,
-            ClusterCommand::BlockTime,
-            ClusterCommand::Validators,
-            ClusterCommand::ClusterVersion,
-            ClusterCommand::SupplyInfo,
-            ClusterCommand::Inflation,
-            ClusterCommand::GoBack,
+            CommandGroup::Search,
+            CommandGroup::Account,
+            CommandGroup::Cluster,
+            CommandGroup::Stake,
+            CommandGroup::Vote,
+            CommandGroup::Token,
+            CommandGroup::Transaction,
+            CommandGroup::ScillaConfig,
+            CommandGroup::SwitchWallet,
+            CommandGroup::ToggleReadCommitment,
+            CommandGroup::ToggleDryRun,
+            CommandGroup::Exit,
         ],
+        CommandGroup::help,
     )
-    .prompt()?;
+}
+
+/// Every command of every group in one list, filtered as you type
+fn prompt_command_palette() -> anyhow::Result<Command> {
+    let commands = cluster_commands()
+        .into_iter()
+        .map(Command::Cluster)
+        .chain(stake_commands().into_iter().map(Command::Stake))
+        .chain(account_commands().into_iter().map(Command::Account))
+        .chain(vote_commands().into_iter().map(Command::Vote))
+        .chain(token_commands().into_iter().map(Command::Token))
+        .chain(transaction_commands().into_iter().map(Command::Transaction))
+        .chain(config_commands().into_iter().map(Command::ScillaConfig))
+        .chain([
+            Command::SwitchWallet,
+            Command::ToggleReadCommitment,
+            Command::ToggleDryRun,
+        ])
+        .collect();
 
-    Ok(choice)
+    select_command("Search commands:", commands, Command::help)
+}
+
+fn cluster_commands() -> Vec<ClusterCommand> {
+    vec![
+        ClusterCommand::EpochInfo,
+        ClusterCommand::CurrentSlot,
+        ClusterCommand::BlockHeight,
+        ClusterCommand::BlockTime,
+        ClusterCommand::Validators,
+        ClusterCommand::StakeDistribution,
+        ClusterCommand::LeaderSchedule,
+        ClusterCommand::BlockProduction,
+        ClusterCommand::RpcHealth,
+        ClusterCommand::RpcBenchmark,
+        ClusterCommand::PriorityFees,
+        ClusterCommand::ClusterVersion,
+        ClusterCommand::ClusterIdentity,
+        ClusterCommand::FeatureGates,
+        ClusterCommand::Sysvars,
+        ClusterCommand::SupplyInfo,
+        ClusterCommand::Inflation,
+    ]
+}
+
+fn prompt_cluster() -> anyhow::Result<ClusterCommand> {
+    let mut commands = cluster_commands();
+    commands.push(ClusterCommand::GoBack);
+
+    select_command("Cluster Command:", commands, ClusterCommand::help)
+}
+
+fn stake_commands() -> Vec<StakeCommand> {
+    vec![
+        StakeCommand::Create,
+        StakeCommand::Delegate,
+        StakeCommand::Deactivate,
+        StakeCommand::Withdraw,
+        StakeCommand::Authorize,
+        StakeCommand::Merge,
+        StakeCommand::Consolidate,
+        StakeCommand::Split,
+        StakeCommand::Show,
+        StakeCommand::History,
+        StakeCommand::Rewards,
+        StakeCommand::RewardHistory,
+        StakeCommand::EstimateRewards,
+    ]
 }
 
 fn prompt_stake() -> anyhow::Result<StakeCommand> {
-    let choice = Select::new(
-        "Stake Command:",
-        vec![
-            StakeCommand::Create,
-            StakeCommand::Delegate,
-            StakeCommand::Deactivate,
-            StakeCommand::Withdraw,
-            StakeCommand::Merge,
-            StakeCommand::Split,
-            StakeCommand::Show,
-            StakeCommand::History,
-            StakeCommand::GoBack,
-        ],
-    )
-    .prompt()?;
+    let mut commands = stake_commands();
+    commands.push(StakeCommand::GoBack);
 
-    Ok(choice)
+    select_command("Stake Command:", commands, StakeCommand::help)
+}
+
+fn account_commands() -> Vec<AccountCommand> {
+    vec![
+        AccountCommand::FetchAccount,
+        AccountCommand::Balance,
+        AccountCommand::RentReserve,
+        AccountCommand::Transfer,
+        AccountCommand::Airdrop,
+        AccountCommand::BulkAirdrop,
+        AccountCommand::LargestAccounts,
+        AccountCommand::NonceAccount,
+        AccountCommand::CreateNonceAccount,
+        AccountCommand::NonceStatus,
+        AccountCommand::NonceTransfer,
+        AccountCommand::History,
+        AccountCommand::Provenance,
+        AccountCommand::WalletReport,
+        AccountCommand::WatchDeposits,
+        AccountCommand::ExportAccount,
+        AccountCommand::CloseAccount,
+        AccountCommand::Authorities,
+        AccountCommand::RotateAuthorities,
+        AccountCommand::DerivePda,
+    ]
 }
 
 fn prompt_account() -> anyhow::Result<AccountCommand> {
-    let choice = Select::new(
-        "Account Command:",
-        vec![
-            AccountCommand::FetchAccount,
-            AccountCommand::Balance,
-            AccountCommand::Transfer,
-            AccountCommand::Airdrop,
-            AccountCommand::LargestAccounts,
-            AccountCommand::NonceAccount,
-            AccountCommand::GoBack,
-        ],
-    )
-    .prompt()?;
+    let mut commands = account_commands();
+    commands.push(AccountCommand::GoBack);
+
+    select_command("Account Command:", commands, AccountCommand::help)
+}
 
-    Ok(choice)
+fn vote_commands() -> Vec<VoteCommand> {
+    vec![
+        VoteCommand::CreateVoteAccount,
+        VoteCommand::AuthorizeVoter,
+        VoteCommand::WithdrawFromVoteAccount,
+        VoteCommand::ShowVoteAccount,
+        VoteCommand::VerifyIdentity,
+        VoteCommand::CloseVoteAccount,
+    ]
 }
 
 fn prompt_vote() -> anyhow::Result<VoteCommand> {
-    let choice = Select::new(
-        "Vote Command:",
-        vec![
-            VoteCommand::CreateVoteAccount,
-            VoteCommand::AuthorizeVoter,
-            VoteCommand::WithdrawFromVoteAccount,
-            VoteCommand::ShowVoteAccount,
-            VoteCommand::CloseVoteAccount,
-            VoteCommand::GoBack,
-        ],
-    )
-    .prompt()?;
+    let mut commands = vote_commands();
+    commands.push(VoteCommand::GoBack);
+
+    select_command("Vote Command:", commands, VoteCommand::help)
+}
+
+fn token_commands() -> Vec<TokenCommand> {
+    vec![
+        TokenCommand::MintInfo,
+        TokenCommand::TokenAccounts,
+        TokenCommand::Balance,
+        TokenCommand::Transfer,
+        TokenCommand::AssociatedTokenAddress,
+    ]
+}
+
+fn prompt_token() -> anyhow::Result<TokenCommand> {
+    let mut commands = token_commands();
+    commands.push(TokenCommand::GoBack);
+
+    select_command("Token Command:", commands, TokenCommand::help)
+}
 
-    Ok(choice)
+fn transaction_commands() -> Vec<TransactionCommand> {
+    vec![
+        TransactionCommand::CheckConfirmation,
+        TransactionCommand::MonitorTransaction,
+        TransactionCommand::FetchStatus,
+        TransactionCommand::BulkStatus,
+        TransactionCommand::FetchTransaction,
+        TransactionCommand::SendTransaction,
+        TransactionCommand::SponsorTransaction,
+        TransactionCommand::SimulateTransaction,
+        TransactionCommand::PrepareUnsigned,
+    ]
 }
 
 fn prompt_transaction() -> anyhow::Result<TransactionCommand> {
-    let choice = Select::new(
-        "Transaction Command:",
-        vec![
-            TransactionCommand::CheckConfirmation,
-            TransactionCommand::FetchStatus,
-            TransactionCommand::FetchTransaction,
-            TransactionCommand::SendTransaction,
-            TransactionCommand::GoBack,
-        ],
-    )
-    .prompt()?;
+    let mut commands = transaction_commands();
+    commands.push(TransactionCommand::GoBack);
 
-    Ok(choice)
+    select_command("Transaction Command:", commands, TransactionCommand::help)
+}
+
+fn config_commands() -> Vec<ConfigCommand> {
+    vec![
+        ConfigCommand::Show,
+        ConfigCommand::Generate,
+        ConfigCommand::Edit,
+        ConfigCommand::Inspect,
+        ConfigCommand::History,
+        ConfigCommand::RenameWallet,
+        ConfigCommand::SwitchWallet,
+        ConfigCommand::EncryptKeypair,
+    ]
 }
 
 fn prompt_config() -> anyhow::Result<ConfigCommand> {
-    let choice = Select::new(
-        "ScillaConfig Command:",
-        vec![
-            ConfigCommand::Show,
-            ConfigCommand::Edit,
-            ConfigCommand::GoBack,
-        ],
-    )
-    .prompt()?;
+    let mut commands = config_commands();
+    commands.push(ConfigCommand::GoBack);
+
+    select_command("ScillaConfig Command:", commands, ConfigCommand::help)
+}
 
-    Ok(choice)
+/// Trims surrounding whitespace and parses what is left. Returns `None` when
+/// nothing but whitespace was entered.
+fn parse_input<T: FromStr>(input: &str) -> Option<Result<T, T::Err>> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.parse())
+    }
 }
 
+fn prompt_text(msg: &str) -> String {
+    loop {
+        match Text::new(msg).prompt() {
+            Ok(v) => return v,
+            Err(e) => match e {
+                InquireError::OperationInterrupted | InquireError::OperationCanceled => {
+                    println!("{}", theme::warning("Operation cancelled. Exiting.").bold());
+                    exit(0);
+                }
+                _ => print_error(format!("Invalid input: {e}. Please try again.")),
+            },
+        }
+    }
+}
+
+/// Prompts for a required value, asking again on empty or unparsable input.
 pub fn prompt_input_data<T>(msg: &str) -> T
 where
     T: FromStr,
     T::Err: std::fmt::Display,
 {
     loop {
-        let input = match Text::new(msg).prompt() {
+        match parse_input::<T>(&prompt_text(msg)) {
+            Some(Ok(value)) => return value,
+            Some(Err(e)) => print_error(format!("Parse error : {e}. Please try again.")),
+            None => print_error("This field is required. Please try again."),
+        }
+    }
+}
+
+/// Prompts for a required value with `default` pre-filled as editable text.
+pub fn prompt_input_data_with_default<T>(msg: &str, default: Option<T>) -> T
+where
+    T: FromStr + Display,
+    T::Err: std::fmt::Display,
+{
+    let Some(default) = default else {
+        return prompt_input_data(msg);
+    };
+    let initial = default.to_string();
+
+    loop {
+        let input = match Text::new(msg).with_initial_value(&initial).prompt() {
             Ok(v) => v,
-            Err(e) => match e {
-                InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
-                    exit(0);
-                }
-                _ => {
-                    print_error(format!("Invalid input: {e}. Please try again."));
-                    continue;
-                }
-            },
+            Err(InquireError::OperationInterrupted | InquireError::OperationCanceled) => {
+                println!("{}", theme::warning("Operation cancelled. Exiting.").bold());
+                exit(0);
+            }
+            Err(e) => {
+                print_error(format!("Invalid input: {e}. Please try again."));
+                continue;
+            }
         };
 
-        match input.parse::<T>() {
-            Ok(value) => return value,
-            Err(e) => print_error(format!("Parse error : {e}. Please try again.")),
+        match parse_input::<T>(&input) {
+            Some(Ok(value)) => return value,
+            Some(Err(e)) => print_error(format!("Parse error : {e}. Please try again.")),
+            None => print_error("This field is required. Please try again."),
+        }
+    }
+}
+
+/// Prompts for a SOL amount to spend from the wallet, showing its balance and
+/// rejecting amounts that don't leave the `reserve` before the prompt is
+/// accepted. `reserve` lists what is kept back and why, e.g. `("fees",
+/// 5_000)`. Falls back to a plain prompt when the balance can't be fetched.
+pub async fn prompt_transfer_amount(
+    ctx: &ScillaContext,
+    msg: &str,
+    default: Option<SolAmount>,
+    reserve: &[(&str, u64)],
+) -> SolAmount {
+    let reserve_lamports = reserve.iter().map(|(_, lamports)| lamports).sum();
+    let balance = match ctx.rpc().get_balance(ctx.pubkey()).await {
+        Ok(balance) => balance,
+        Err(_) => return prompt_input_data_with_default(msg, default),
+    };
+
+    let initial = default.map(|amount| amount.to_string()).unwrap_or_default();
+    let help = format!(
+        "Balance: {} SOL, keeping {}",
+        lamports_to_sol(balance),
+        reserve_description(reserve)
+    );
+
+    loop {
+        let result = Text::new(msg)
+            .with_initial_value(&initial)
+            .with_help_message(&help)
+            .with_validator(move |input: &str| {
+                Ok(
+                    match transfer_amount_error(input, balance, reserve_lamports) {
+                        Some(error) => Validation::Invalid(error.into()),
+                        None => Validation::Valid,
+                    },
+                )
+            })
+            .prompt();
+
+        match result {
+            Ok(input) => {
+                if let Some(Ok(amount)) = parse_input::<SolAmount>(&input) {
+                    return amount;
+                }
+            }
+            Err(InquireError::OperationInterrupted | InquireError::OperationCanceled) => {
+                println!("{}", theme::warning("Operation cancelled. Exiting.").bold());
+                exit(0);
+            }
+            Err(e) => print_error(format!("Invalid input: {e}. Please try again.")),
+        }
+    }
+}
+
+/// `reserve` as text, e.g. "0.000005 SOL for fees and 0.00228288 SOL for rent"
+fn reserve_description(reserve: &[(&str, u64)]) -> String {
+    reserve
+        .iter()
+        .map(|(purpose, lamports)| format!("{} SOL for {purpose}", lamports_to_sol(*lamports)))
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// Why `input` is not an amount that fits in `balance` alongside
+/// `reserve_lamports`, if it isn't
+fn transfer_amount_error(input: &str, balance: u64, reserve_lamports: u64) -> Option<String> {
+    let amount = match parse_input::<SolAmount>(input) {
+        None => return Some("This field is required".to_string()),
+        Some(Err(e)) => return Some(e.to_string()),
+        Some(Ok(amount)) => amount,
+    };
+
+    let available = balance.saturating_sub(reserve_lamports);
+    (amount.to_lamports() > available).then(|| {
+        format!(
+            "Exceeds what you can send: {} SOL after the reserve",
+            lamports_to_sol(available)
+        )
+    })
+}
+
+/// Prompts for an optional value; empty or whitespace-only input is `None`.
+pub fn prompt_optional_data<T>(msg: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    loop {
+        match parse_input::<T>(&prompt_text(msg)) {
+            Some(Ok(value)) => return Some(value),
+            Some(Err(e)) => print_error(format!("Parse error : {e}. Please try again.")),
+            None => return None,
         }
     }
 }
@@ -181,7 +478,7 @@ where
             Ok(v) => return v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
+                    println!("{}", theme::warning("Operation cancelled. Exiting.").bold());
                     exit(0);
                 }
                 _ => {
@@ -193,19 +490,87 @@ where
     }
 }
 
+/// Asks which commitment to wait for, with the cursor starting on `default`
+pub fn prompt_commitment(msg: &str, default: CommitmentLevel) -> CommitmentLevel {
+    let options = vec![
+        CommitmentLevel::Processed,
+        CommitmentLevel::Confirmed,
+        CommitmentLevel::Finalized,
+    ];
+    let starting_cursor = options
+        .iter()
+        .position(|level| *level == default)
+        .unwrap_or_default();
+
+    loop {
+        match Select::new(msg, options.clone())
+            .with_starting_cursor(starting_cursor)
+            .prompt()
+        {
+            Ok(level) => return level,
+            Err(InquireError::OperationInterrupted | InquireError::OperationCanceled) => {
+                println!("{}", theme::warning("Operation cancelled. Exiting.").bold());
+                exit(0);
+            }
+            Err(e) => print_error(format!("Invalid Choice: {e}. Please try again.")),
+        }
+    }
+}
+
+/// Asks whether to sign against a recent blockhash or a durable nonce account
+/// the wallet is authority of, for transactions signed now and sent later
+pub fn prompt_durable_nonce(ctx: &ScillaContext) -> Option<DurableNonce> {
+    const RECENT_BLOCKHASH: &str = "Recent blockhash";
+    const DURABLE_NONCE: &str = "Durable nonce account";
+
+    match prompt_select_data("Sign against:", vec![RECENT_BLOCKHASH, DURABLE_NONCE]) {
+        DURABLE_NONCE => Some(DurableNonce {
+            account: prompt_input_data("Enter Nonce Account Pubkey:"),
+            authority: *ctx.pubkey(),
+        }),
+        _ => None,
+    }
+}
+
+/// Asks whether to withdraw an absolute SOL amount or a percentage of the
+/// withdrawable balance, then prompts for that value.
+pub fn prompt_withdraw_amount() -> WithdrawAmount {
+    const BY_AMOUNT: &str = "Amount (SOL)";
+    const BY_PERCENTAGE: &str = "Percentage of withdrawable balance";
+    const MAX: &str = "Maximum";
+
+    match prompt_select_data("Withdraw by:", vec![BY_AMOUNT, BY_PERCENTAGE, MAX]) {
+        BY_PERCENTAGE => {
+            WithdrawAmount::Percentage(prompt_input_data("Enter percentage to withdraw (1-100):"))
+        }
+        MAX => WithdrawAmount::Max,
+        _ => WithdrawAmount::Sol(prompt_input_data("Enter withdraw amount in SOL:")),
+    }
+}
+
 pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
     let default_path = ctx.keypair_path().display().to_string();
+    // start from the folder of the previous keypair, handy when several
+    // keypairs of one flow sit next to each other
+    let prefix = ctx
+        .last_keypair_dir()
+        .map(|dir| format!("{}/", dir.display()));
 
     loop {
-        let input = match Text::new(msg)
+        let mut text = Text::new(msg)
             .with_default(&default_path)
-            .with_help_message("Press Enter to use the default keypair")
-            .prompt()
-        {
+            .with_help_message("Press Enter to use the default keypair");
+        if let Some(prefix) = &prefix {
+            text = text
+                .with_initial_value(prefix)
+                .with_help_message("Type the file name, or press Enter to use the default keypair");
+        }
+
+        let input = match text.prompt() {
             Ok(v) => v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
+                    println!("{}", theme::warning("Operation cancelled. Exiting.").bold());
                     exit(0);
                 }
                 _ => {
@@ -215,14 +580,20 @@ pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
             },
         };
 
-        let input = if input.trim().is_empty() {
-            &default_path
+        let input = input.trim();
+        let input = if input.is_empty() || Some(input) == prefix.as_deref() {
+            default_path.as_str()
         } else {
-            &input
+            input
         };
 
         match PathBuf::from_str(input) {
-            Ok(value) => return value,
+            Ok(value) => {
+                if input != default_path {
+                    ctx.remember_keypair_dir(&value);
+                }
+                return value;
+            }
             Err(e) => {
                 print_error(format!("Invalid path: {e}. Please try again."));
             }
@@ -230,6 +601,208 @@ pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
     }
 }
 
+/// Prompts for the keypair of each signer role in turn, e.g. "Vote Account".
+/// Read them into a `SignerSet` to send.
+pub fn prompt_signer_paths<const N: usize>(roles: [&str; N], ctx: &ScillaContext) -> [PathBuf; N] {
+    roles.map(|role| prompt_keypair_path(&format!("Enter {role} Keypair Path:"), ctx))
+}
+
 pub fn prompt_confirmation(msg: &str) -> bool {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        println!("{msg} {}", theme::muted("yes (assume-yes)"));
+        return true;
+    }
     Confirm::new(msg).prompt().unwrap_or(false)
 }
+
+/// Shows what is about to be sent, and to which cluster, before asking
+/// whether to go ahead. Off the test clusters the user has to type
+/// `MAINNET_CONFIRM_PHRASE`, which assume-yes only skips with
+/// `assume-yes-mainnet` set.
+pub fn confirm_send_summary(ctx: &ScillaContext, title: &str, rows: &[(&str, String)]) -> bool {
+    let mut rows = rows.to_vec();
+    rows.push(("Cluster RPC", ctx.rpc().url()));
+    print_summary(title, &rows);
+
+    if is_test_cluster(ctx.cluster()) {
+        return prompt_confirmation("Send this transaction?");
+    }
+
+    // assume-yes is only active here when assume-yes-mainnet allows it
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        println!(
+            "{} {}",
+            theme::warning("Sending on mainnet:"),
+            theme::muted("confirmed (assume-yes-mainnet)")
+        );
+        return true;
+    }
+
+    Text::new(&format!(
+        "This sends on mainnet. Type \"{MAINNET_CONFIRM_PHRASE}\" to go ahead:"
+    ))
+    .prompt()
+    .is_ok_and(|input| is_mainnet_confirmation(&input))
+}
+
+fn is_mainnet_confirmation(input: &str) -> bool {
+    input.trim() == MAINNET_CONFIRM_PHRASE
+}
+
+fn is_test_cluster(cluster: &Cluster) -> bool {
+    matches!(
+        cluster,
+        Cluster::Devnet | Cluster::Testnet | Cluster::Localnet
+    )
+}
+
+/// Records `--yes` from the command line for the rest of the process
+pub fn set_assume_yes_flag() {
+    ASSUME_YES_FLAG.store(true, Ordering::Relaxed);
+}
+
+/// Whether confirmations may answer themselves. Only devnet, testnet and
+/// localnet are trusted by default; mainnet and custom endpoints, which may
+/// well be mainnet, also need `assume-yes-mainnet`.
+pub fn effective_assume_yes(wanted: bool, mainnet_override: bool, cluster: &Cluster) -> bool {
+    wanted && (is_test_cluster(cluster) || mainnet_override)
+}
+
+/// Applies `assume-yes` (or `--yes`) for the cluster `config` points at.
+/// Process-wide, so only for the active config: once at startup and again
+/// when the config is edited or regenerated.
+pub fn apply_assume_yes(config: &ScillaConfig) {
+    let wanted = config.assume_yes || ASSUME_YES_FLAG.load(Ordering::Relaxed);
+    let active = effective_assume_yes(
+        wanted,
+        config.assume_yes_mainnet,
+        &Cluster::from_rpc_url(&config.rpc_url),
+    );
+    ASSUME_YES.store(active, Ordering::Relaxed);
+
+    if wanted && !active {
+        println!(
+            "{}",
+            theme::warning(
+                "assume-yes is ignored on this cluster; set assume-yes-mainnet to allow it"
+            )
+        );
+    }
+}
+
+/// Reads the keypair at `path`, asking for its passphrase first when the
+/// file is encrypted. For interactive flows; anything that must not prompt
+/// reads with `read_keypair_with_passphrase` instead.
+pub fn prompt_keypair_from_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Keypair> {
+    let path = path.as_ref();
+    let passphrase = if is_encrypted_keypair_file(path) {
+        Some(prompt_passphrase(
+            &format!("Passphrase for encrypted keypair {}:", path.display()),
+            false,
+        )?)
+    } else {
+        None
+    };
+
+    read_keypair_with_passphrase(path, passphrase.as_deref())
+}
+
+/// Asks for a passphrase without echoing it. With `confirm`, it has to be
+/// typed twice, for setting a new one.
+pub fn prompt_passphrase(msg: &str, confirm: bool) -> anyhow::Result<String> {
+    let prompt = Password::new(msg).with_display_mode(PasswordDisplayMode::Masked);
+    let prompt = if confirm {
+        prompt.with_custom_confirmation_message("Repeat passphrase:")
+    } else {
+        prompt.without_confirmation()
+    };
+
+    Ok(prompt.prompt()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_pubkey::Pubkey};
+
+    #[test]
+    fn test_parse_input_trims_whitespace() {
+        assert_eq!(parse_input::<u64>("  42\t").unwrap().unwrap(), 42);
+
+        let pubkey = Pubkey::new_unique();
+        let parsed = parse_input::<Pubkey>(&format!(" {pubkey} \n")).unwrap();
+        assert_eq!(parsed.unwrap(), pubkey);
+    }
+
+    #[test]
+    fn test_parse_input_empty_is_none() {
+        assert!(parse_input::<u64>("").is_none());
+        assert!(parse_input::<u64>("   ").is_none());
+        assert!(parse_input::<String>(" \t\n ").is_none());
+    }
+
+    #[test]
+    fn test_parse_input_invalid_is_error() {
+        assert!(parse_input::<u64>(" abc ").unwrap().is_err());
+        assert!(parse_input::<Pubkey>("not-a-pubkey").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_parse_input_keeps_inner_whitespace() {
+        let parsed = parse_input::<String>("  my wallet  ").unwrap().unwrap();
+        assert_eq!(parsed, "my wallet");
+    }
+
+    #[test]
+    fn test_assume_yes_needs_override_off_test_clusters() {
+        assert!(effective_assume_yes(true, false, &Cluster::Devnet));
+        assert!(effective_assume_yes(true, false, &Cluster::Localnet));
+        assert!(!effective_assume_yes(false, true, &Cluster::Devnet));
+
+        assert!(!effective_assume_yes(true, false, &Cluster::Mainnet));
+        assert!(!effective_assume_yes(
+            true,
+            false,
+            &Cluster::Custom("https://rpc.example.com".into())
+        ));
+        assert!(effective_assume_yes(true, true, &Cluster::Mainnet));
+    }
+
+    #[test]
+    fn test_mainnet_confirmation_needs_the_exact_phrase() {
+        assert!(is_mainnet_confirmation(MAINNET_CONFIRM_PHRASE));
+        assert!(is_mainnet_confirmation(&format!(
+            " {MAINNET_CONFIRM_PHRASE}\n"
+        )));
+        assert!(!is_mainnet_confirmation("y"));
+        assert!(!is_mainnet_confirmation("yes"));
+        assert!(!is_mainnet_confirmation(
+            &MAINNET_CONFIRM_PHRASE.to_uppercase()
+        ));
+    }
+
+    #[test]
+    fn test_reserve_description_lists_each_part() {
+        assert_eq!(
+            reserve_description(&[("fees", 10_000), ("rent", 2_282_880)]),
+            format!(
+                "{} SOL for fees and {} SOL for rent",
+                lamports_to_sol(10_000),
+                lamports_to_sol(2_282_880)
+            )
+        );
+    }
+
+    #[test]
+    fn test_transfer_amount_error_checks_balance_and_reserve() {
+        let balance = 2_000_000_000;
+        assert_eq!(transfer_amount_error("1.5", balance, 5_000), None);
+        assert_eq!(transfer_amount_error("1.999995", balance, 5_000), None);
+        assert!(
+            transfer_amount_error("2", balance, 5_000)
+                .unwrap()
+                .contains("Exceeds")
+        );
+        assert!(transfer_amount_error("", balance, 5_000).is_some());
+        assert!(transfer_amount_error("-1", balance, 5_000).is_some());
+    }
+}
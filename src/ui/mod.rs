@@ -0,0 +1,352 @@
+use {
+    crate::{
+        config::OutputFormat,
+        context::ScillaContext,
+        misc::{helpers::lamports_to_sol, rpc_metrics::RpcMetrics},
+    },
+    comfy_table::{Cell, Row, Table, presets::UTF8_FULL},
+    indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle},
+    serde::Serialize,
+    serde_json::{Map, Value},
+    solana_rpc_client_api::response::RpcSimulateTransactionResult,
+    solana_signature::Signature,
+    std::sync::Mutex,
+};
+
+pub mod theme;
+
+/// Spinner of the `show_spinner` call currently running, if any
+static ACTIVE_SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Runs `fut` behind a spinner and reports how it went. The result is handed
+/// back as well; its error has already been printed, so callers that don't
+/// branch on it can drop it.
+pub async fn show_spinner<F, T>(message: &str, fut: F) -> anyhow::Result<T>
+where
+    F: std::future::Future<Output = anyhow::Result<T>>,
+{
+    if theme::is_plain() {
+        println!("{message}");
+        let result = fut.await;
+        match &result {
+            Ok(_) => println!("...done"),
+            Err(e) => println!("Error : {e}"),
+        }
+        return result;
+    }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::with_template("{spinner:.cyan} {msg}")
+            .unwrap()
+            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+    );
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    spinner.set_message(message.to_string());
+    *ACTIVE_SPINNER.lock().unwrap() = Some(spinner.clone());
+
+    let result = fut.await;
+    ACTIVE_SPINNER.lock().unwrap().take();
+
+    match &result {
+        Ok(_) => spinner.finish_with_message("✅ Done"),
+        Err(e) => spinner
+            .finish_with_message(format!("{}", theme::error(format!("Error : {}", e)).bold())),
+    }
+
+    result
+}
+
+/// Runs `f` with the active spinner cleared from the terminal, so output and
+/// prompts from inside a `show_spinner` future are not drawn over
+pub fn suspend_spinner<R>(f: impl FnOnce() -> R) -> R {
+    let spinner = ACTIVE_SPINNER.lock().unwrap().clone();
+    match spinner {
+        Some(spinner) => spinner.suspend(f),
+        None => f(),
+    }
+}
+
+/// Hides `progress` in plain output so pipes and logs get no redraws
+pub fn plain_aware(progress: ProgressBar) -> ProgressBar {
+    if theme::is_plain() {
+        progress.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    progress
+}
+
+/// Ends `progress` with `message`, which plain output prints as its own line
+pub fn finish_progress(progress: &ProgressBar, message: String) {
+    if theme::is_plain() {
+        println!("{message}");
+    }
+    progress.finish_with_message(message);
+}
+
+/// Prints `rows` as a Field/Value table under `title`
+pub fn print_summary(title: &str, rows: &[(&str, String)]) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        theme::header_cell("Field"),
+        theme::header_cell("Value"),
+    ]);
+    for (field, value) in rows {
+        table.add_row(vec![Cell::new(field), Cell::new(value)]);
+    }
+
+    println!("\n{}", theme::success(title).bold());
+    println!("{table}");
+}
+
+/// Prints a list-style view under `title`, or as CSV or JSON on stdout when
+/// the configured output format asks for it
+pub fn print_list_table(ctx: &ScillaContext, title: &str, table: &Table) {
+    match ctx.output_format() {
+        OutputFormat::Table => {
+            println!("\n{}", theme::success(title).bold());
+            println!("{table}");
+        }
+        OutputFormat::Csv => print!("{}", table_to_csv(table)),
+        OutputFormat::Json => println!("{}", table_to_json(table)),
+    }
+}
+
+/// Prints a single-record view under `title`. With JSON output a Field/Value
+/// table becomes one object keyed by its fields; wider tables print their
+/// rows as with `print_list_table`.
+pub fn print_record_table(ctx: &ScillaContext, title: &str, table: &Table) {
+    match ctx.output_format() {
+        OutputFormat::Json if table.header().map(Row::cell_count) == Some(2) => {
+            println!("{}", record_table_to_json(table))
+        }
+        _ => print_list_table(ctx, title, table),
+    }
+}
+
+/// The rows of a Field/Value `table` as one JSON object, styling stripped
+pub fn record_table_to_json(table: &Table) -> Value {
+    let plain = |cell: &Cell| console::strip_ansi_codes(&cell.content()).into_owned();
+    let object: Map<String, Value> = table
+        .row_iter()
+        .filter_map(|row| {
+            let mut cells = row.cell_iter();
+            Some((plain(cells.next()?), Value::String(plain(cells.next()?))))
+        })
+        .collect();
+
+    Value::Object(object)
+}
+
+/// Prints `value` as pretty JSON on stdout, for `output-format = "json"`
+pub fn print_json(value: &impl Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// The rows of `table` as a JSON array of objects keyed by the header,
+/// styling stripped
+pub fn table_to_json(table: &Table) -> Value {
+    let plain = |cell: &Cell| console::strip_ansi_codes(&cell.content()).into_owned();
+    let header: Vec<String> = table
+        .header()
+        .map(|row| row.cell_iter().map(plain).collect())
+        .unwrap_or_default();
+
+    let rows = table
+        .row_iter()
+        .map(|row| {
+            let object: Map<String, Value> = header
+                .iter()
+                .cloned()
+                .zip(row.cell_iter().map(|cell| Value::String(plain(cell))))
+                .collect();
+            Value::Object(object)
+        })
+        .collect();
+
+    Value::Array(rows)
+}
+
+/// RFC 4180 CSV of the header and rows of `table`, styling stripped
+pub fn table_to_csv(table: &Table) -> String {
+    table
+        .header()
+        .into_iter()
+        .chain(table.row_iter())
+        .map(|row| {
+            let fields: Vec<String> = row
+                .cell_iter()
+                .map(|cell| csv_field(&console::strip_ansi_codes(&cell.content())))
+                .collect();
+            format!("{}\r\n", fields.join(","))
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn print_error(message: impl std::fmt::Display) {
+    println!("{}", theme::error(message).bold());
+}
+
+pub fn print_explorer_link(ctx: &ScillaContext, signature: &Signature) {
+    println!(
+        "{} {}",
+        theme::muted("Explorer:"),
+        theme::info(ctx.explorer().tx_url(signature, ctx.cluster()))
+    );
+}
+
+/// One line with what a confirmed transaction cost to execute
+pub fn print_execution_cost(units_consumed: Option<u64>, fee: u64) {
+    let units = units_consumed
+        .map(|units| units.to_string())
+        .unwrap_or_else(|| "N/A".to_string());
+    println!(
+        "{}",
+        theme::muted(format!(
+            "Compute units consumed: {units} · Fee: {} SOL",
+            lamports_to_sol(fee)
+        ))
+    );
+}
+
+/// Prints the status, consumed compute units and program logs of a simulation.
+pub fn print_simulation_summary(result: &RpcSimulateTransactionResult) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![
+            Cell::new("Status"),
+            Cell::new(match &result.err {
+                None => theme::success("Success").to_string(),
+                Some(err) => theme::error(format!("Error: {err:?}")).to_string(),
+            }),
+        ])
+        .add_row(vec![
+            Cell::new("Units Consumed"),
+            Cell::new(
+                result
+                    .units_consumed
+                    .map(|units| units.to_string())
+                    .unwrap_or_else(|| "N/A".to_string()),
+            ),
+        ]);
+
+    println!("\n{}", theme::success("SIMULATION RESULT").bold());
+    println!("{}", table);
+
+    println!("\n{}", theme::info("PROGRAM LOGS").bold());
+    match &result.logs {
+        Some(logs) if !logs.is_empty() => {
+            for log in logs {
+                println!("  {}", log);
+            }
+        }
+        _ => println!("  {}", theme::muted("No logs returned")),
+    }
+}
+
+/// Prints how many RPC calls the session made, how long they took in total
+/// and which method was slowest on average.
+pub fn print_rpc_summary(metrics: &RpcMetrics) {
+    let calls = metrics.snapshot();
+    if calls.is_empty() {
+        return;
+    }
+
+    let total_calls: u64 = calls.iter().map(|(_, stats)| stats.count).sum();
+    let total_time: std::time::Duration = calls.iter().map(|(_, stats)| stats.total_time).sum();
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec![
+            theme::header_cell("Field"),
+            theme::header_cell("Value"),
+        ])
+        .add_row(vec![Cell::new("Total Calls"), Cell::new(total_calls)])
+        .add_row(vec![
+            Cell::new("Total Time"),
+            Cell::new(format!("{:.2?}", total_time)),
+        ]);
+
+    if let Some((method, stats)) = calls.iter().max_by_key(|(_, stats)| stats.average()) {
+        table.add_row(vec![
+            Cell::new("Slowest Call Type"),
+            Cell::new(format!(
+                "{method} (avg {:.2?} over {} call(s))",
+                stats.average(),
+                stats.count
+            )),
+        ]);
+    }
+
+    println!("\n{}", theme::success("RPC SESSION SUMMARY").bold());
+    println!("{}", table);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_to_csv_escapes_fields() {
+        let mut table = Table::new();
+        table
+            .set_header(vec!["Address", "Note"])
+            .add_row(vec!["abc", "plain"])
+            .add_row(vec!["def", "has, comma"])
+            .add_row(vec!["ghi", "says \"hi\""]);
+
+        assert_eq!(
+            table_to_csv(&table),
+            "Address,Note\r\nabc,plain\r\ndef,\"has, comma\"\r\nghi,\"says \"\"hi\"\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_table_to_json_keys_rows_by_header() {
+        let mut table = Table::new();
+        table
+            .set_header(vec!["Address", "Balance (SOL)"])
+            .add_row(vec!["abc".to_string(), theme::success("1.5").to_string()]);
+
+        assert_eq!(
+            table_to_json(&table),
+            serde_json::json!([{ "Address": "abc", "Balance (SOL)": "1.5" }])
+        );
+    }
+
+    #[test]
+    fn test_record_table_to_json_keys_by_field() {
+        let mut table = Table::new();
+        table
+            .set_header(vec!["Field", "Value"])
+            .add_row(vec!["Slot".to_string(), theme::success("42").to_string()])
+            .add_row(vec!["Epoch", "7"]);
+
+        assert_eq!(
+            record_table_to_json(&table),
+            serde_json::json!({ "Slot": "42", "Epoch": "7" })
+        );
+    }
+
+    #[test]
+    fn test_table_to_csv_strips_styling() {
+        let mut table = Table::new();
+        table.add_row(vec![theme::success("Success").to_string()]);
+
+        assert_eq!(table_to_csv(&table), "Success\r\n");
+    }
+}
@@ -0,0 +1,169 @@
+use {
+    crate::config::Theme,
+    comfy_table::{Attribute, Cell, Color},
+    console::{StyledObject, Term, style},
+    inquire::ui::RenderConfig,
+    std::{
+        ffi::OsStr,
+        sync::atomic::{AtomicU8, Ordering},
+    },
+};
+
+static ACTIVE_THEME: AtomicU8 = AtomicU8::new(Theme::Default as u8);
+
+/// The configured theme, unless `NO_COLOR` is set to a non-empty value or
+/// stdout is piped or redirected
+pub fn resolve(configured: Theme, no_color_env: Option<&OsStr>, stdout_is_tty: bool) -> Theme {
+    match no_color_env {
+        Some(value) if !value.is_empty() => Theme::NoColor,
+        _ if !stdout_is_tty => Theme::NoColor,
+        _ => configured,
+    }
+}
+
+/// Makes `configured` the theme for all further output, prompts included.
+/// Process-wide, so only for the active config: at startup and when the
+/// config is edited or regenerated.
+pub fn apply(configured: Theme) {
+    let theme = resolve(
+        configured,
+        std::env::var_os("NO_COLOR").as_deref(),
+        Term::stdout().is_term(),
+    );
+    ACTIVE_THEME.store(theme as u8, Ordering::Relaxed);
+
+    if theme == Theme::NoColor {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+        inquire::set_global_render_config(RenderConfig::empty());
+    } else {
+        console::set_colors_enabled(Term::stdout().features().colors_supported());
+        console::set_colors_enabled_stderr(Term::stderr().features().colors_supported());
+        inquire::set_global_render_config(RenderConfig::default_colored());
+    }
+}
+
+/// Whether output should be free of ANSI codes and animations, for logs,
+/// pipes and `NO_COLOR`
+pub fn is_plain() -> bool {
+    active() == Theme::NoColor
+}
+
+pub fn active() -> Theme {
+    match ACTIVE_THEME.load(Ordering::Relaxed) {
+        theme if theme == Theme::HighContrast as u8 => Theme::HighContrast,
+        theme if theme == Theme::NoColor as u8 => Theme::NoColor,
+        _ => Theme::Default,
+    }
+}
+
+/// Hints, links and values worth noticing
+pub fn info<D>(value: D) -> StyledObject<D> {
+    match active() {
+        Theme::Default => style(value).cyan(),
+        Theme::HighContrast => style(value).bold(),
+        Theme::NoColor => style(value),
+    }
+}
+
+/// Headings and completed actions
+pub fn success<D>(value: D) -> StyledObject<D> {
+    match active() {
+        Theme::Default => style(value).green(),
+        Theme::HighContrast => style(value).blue().bold(),
+        Theme::NoColor => style(value),
+    }
+}
+
+pub fn warning<D>(value: D) -> StyledObject<D> {
+    match active() {
+        Theme::Default => style(value).yellow(),
+        Theme::HighContrast => style(value).magenta().bold(),
+        Theme::NoColor => style(value),
+    }
+}
+
+pub fn error<D>(value: D) -> StyledObject<D> {
+    match active() {
+        Theme::Default => style(value).red(),
+        Theme::HighContrast => style(value).red().bold().underlined(),
+        Theme::NoColor => style(value),
+    }
+}
+
+/// Secondary detail. Dim text is hard to read on light or low-contrast
+/// terminals, so the high-contrast theme keeps it at full strength.
+pub fn muted<D>(value: D) -> StyledObject<D> {
+    match active() {
+        Theme::Default => style(value).dim(),
+        Theme::HighContrast | Theme::NoColor => style(value),
+    }
+}
+
+/// Bold, highlighted table header cell
+pub fn header_cell<T: ToString>(content: T) -> Cell {
+    match active() {
+        Theme::Default => Cell::new(content)
+            .add_attribute(Attribute::Bold)
+            .fg(Color::Cyan),
+        Theme::HighContrast => Cell::new(content).add_attribute(Attribute::Bold),
+        Theme::NoColor => Cell::new(content),
+    }
+}
+
+/// Bold without color, dropped entirely in the no-color theme
+pub fn bold_cell<T: ToString>(content: T) -> Cell {
+    match active() {
+        Theme::NoColor => Cell::new(content),
+        Theme::Default | Theme::HighContrast => Cell::new(content).add_attribute(Attribute::Bold),
+    }
+}
+
+pub fn success_cell<T: ToString>(content: T) -> Cell {
+    colored_cell(content, Color::Green, Color::Blue)
+}
+
+pub fn warning_cell<T: ToString>(content: T) -> Cell {
+    colored_cell(content, Color::Yellow, Color::Magenta)
+}
+
+pub fn error_cell<T: ToString>(content: T) -> Cell {
+    colored_cell(content, Color::Red, Color::Red)
+}
+
+fn colored_cell<T: ToString>(content: T, default: Color, high_contrast: Color) -> Cell {
+    match active() {
+        Theme::Default => Cell::new(content).fg(default),
+        Theme::HighContrast => Cell::new(content)
+            .fg(high_contrast)
+            .add_attribute(Attribute::Bold),
+        Theme::NoColor => Cell::new(content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_respects_no_color_env() {
+        assert_eq!(
+            resolve(Theme::HighContrast, None, true),
+            Theme::HighContrast
+        );
+        assert_eq!(
+            resolve(Theme::Default, Some(OsStr::new("1")), true),
+            Theme::NoColor
+        );
+        assert_eq!(
+            resolve(Theme::HighContrast, Some(OsStr::new("")), true),
+            Theme::HighContrast
+        );
+    }
+
+    #[test]
+    fn test_resolve_plain_when_not_a_tty() {
+        assert_eq!(resolve(Theme::Default, None, false), Theme::NoColor);
+        assert_eq!(resolve(Theme::HighContrast, None, false), Theme::NoColor);
+    }
+}